@@ -0,0 +1,29 @@
+//! Previews a markdown article in the browser with live reload.
+//!
+//! Usage: `cargo run --example preview -- fixtures/example.md [theme.css]`
+
+use wechat_pub_rs::Result;
+use wechat_pub_rs::preview::PreviewServer;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let markdown_path = args
+        .next()
+        .unwrap_or_else(|| "fixtures/example.md".to_string());
+
+    let mut server = PreviewServer::new(&markdown_path);
+    // An optional CSS file is offered as the "custom" theme and reloaded on change
+    if let Some(css_path) = args.next() {
+        server = server.theme_file("custom", css_path);
+    }
+
+    let preview = server.bind().await?;
+    println!(
+        "Previewing {markdown_path} at http://{}",
+        preview.local_addr()?
+    );
+    preview.run().await
+}
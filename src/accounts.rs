@@ -0,0 +1,494 @@
+//! Publishing to several official accounts from one process.
+//!
+//! An [`AccountRegistry`] holds named [`AccountProfile`]s, each with its own credentials
+//! and defaults for the theme, author, header and footer. The [`WeChatClient`] of an
+//! account is built the first time it's used, and all clients share one HTTP client and
+//! its connection pool.
+//!
+//! ## Accounts File
+//!
+//! ```json
+//! {
+//!   "accounts": {
+//!     "tech": {
+//!       "app_id": "wx1234567890abcdef",
+//!       "app_secret": "0123456789abcdef0123456789abcdef",
+//!       "theme": "lapis",
+//!       "author": "技术组",
+//!       "footer": "partials/tech-footer.md"
+//!     },
+//!     "life": {
+//!       "app_id": "wxfedcba0987654321",
+//!       "app_secret": "fedcba9876543210fedcba9876543210"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Relative header and footer paths are resolved against the directory of the file.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::accounts::AccountRegistry;
+//! use wechat_pub_rs::Config;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let registry = AccountRegistry::load("accounts.json", Config::default()).await?;
+//!
+//! // Upload to one account with its defaults
+//! let draft_id = registry.upload("tech", "article.md").await?;
+//!
+//! // Publish the same article to several accounts, retitled for one of them
+//! let uploads = registry
+//!     .publish_with("article.md", &["tech", "life"], |account, options| {
+//!         if account == "life" { options.title("周末读点技术") } else { options }
+//!     })
+//!     .await;
+//! for upload in uploads {
+//!     match upload.result {
+//!         Ok(draft_id) => println!("{}: {draft_id}", upload.account),
+//!         Err(e) => eprintln!("{}: {e}", upload.account),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::auth::TokenManager;
+use crate::client::{UploadOptions, WeChatClient};
+use crate::config::Config;
+use crate::error::{Result, WeChatError};
+use crate::http::WeChatHttpClient;
+use crate::utils;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use tracing::{info, warn};
+
+/// Credentials and upload defaults of one official account.
+#[derive(Clone, Deserialize)]
+pub struct AccountProfile {
+    /// AppID of the account
+    pub app_id: String,
+    /// AppSecret of the account
+    pub app_secret: String,
+    /// Theme used when the frontmatter doesn't set one
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Author used when the frontmatter doesn't set one
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Header partial used when the frontmatter doesn't set one
+    #[serde(default)]
+    pub header: Option<String>,
+    /// Footer partial used when the frontmatter doesn't set one
+    #[serde(default)]
+    pub footer: Option<String>,
+}
+
+impl AccountProfile {
+    /// Creates a profile with credentials and no defaults.
+    pub fn new(app_id: impl Into<String>, app_secret: impl Into<String>) -> Self {
+        Self {
+            app_id: app_id.into(),
+            app_secret: app_secret.into(),
+            theme: None,
+            author: None,
+            header: None,
+            footer: None,
+        }
+    }
+
+    /// Sets the default theme.
+    pub fn theme(mut self, theme: impl Into<String>) -> Self {
+        self.theme = Some(theme.into());
+        self
+    }
+
+    /// Sets the default author.
+    pub fn author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Sets the default header partial.
+    pub fn header(mut self, path: impl Into<String>) -> Self {
+        self.header = Some(path.into());
+        self
+    }
+
+    /// Sets the default footer partial.
+    pub fn footer(mut self, path: impl Into<String>) -> Self {
+        self.footer = Some(path.into());
+        self
+    }
+
+    /// Upload options carrying the defaults of this account.
+    pub fn upload_options(&self) -> UploadOptions {
+        let mut options = match &self.theme {
+            Some(theme) => UploadOptions::with_theme(theme),
+            None => UploadOptions::default(),
+        };
+        options.default_author = self.author.clone();
+        options.header = self.header.clone();
+        options.footer = self.footer.clone();
+        options
+    }
+
+    /// Resolves relative partial paths against `dir`.
+    fn resolve_partials(&mut self, dir: &Path) {
+        for path in [&mut self.header, &mut self.footer].into_iter().flatten() {
+            if Path::new(path.as_str()).is_relative() {
+                *path = dir.join(path.as_str()).to_string_lossy().into_owned();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for AccountProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountProfile")
+            .field("app_id", &self.app_id)
+            .field("theme", &self.theme)
+            .field("author", &self.author)
+            .field("header", &self.header)
+            .field("footer", &self.footer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Layout of an accounts file.
+#[derive(Deserialize)]
+struct AccountsFile {
+    accounts: BTreeMap<String, AccountProfile>,
+}
+
+/// An account and its client, once built.
+#[derive(Debug)]
+struct Account {
+    profile: AccountProfile,
+    client: OnceLock<Arc<WeChatClient>>,
+}
+
+/// The result of uploading an article to one account.
+#[derive(Debug)]
+pub struct AccountUpload {
+    /// Name of the account
+    pub account: String,
+    /// Media ID of the created draft
+    pub result: Result<String>,
+}
+
+/// Named official accounts whose clients share one connection pool.
+///
+/// Each account has its own rate limiter, as WeChat counts quotas per AppID.
+#[derive(Debug)]
+pub struct AccountRegistry {
+    http_client: Arc<WeChatHttpClient>,
+    accounts: BTreeMap<String, Account>,
+}
+
+impl AccountRegistry {
+    /// Creates an empty registry; `config` applies to every account.
+    pub fn new(config: Config) -> Result<Self> {
+        config.validate()?;
+        Ok(Self {
+            http_client: Arc::new(WeChatHttpClient::with_config(config)?),
+            accounts: BTreeMap::new(),
+        })
+    }
+
+    /// Loads account profiles from a JSON accounts file.
+    pub async fn load(path: impl AsRef<Path>, config: Config) -> Result<Self> {
+        let path = path.as_ref();
+        let data = tokio::fs::read(path).await.map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to read accounts file: {e}"),
+            )
+        })?;
+        let file: AccountsFile = serde_json::from_slice(&data).map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to parse accounts file: {e}"),
+            )
+        })?;
+
+        let dir = utils::get_base_directory(path)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .canonicalize()
+            .map_err(|e| {
+                WeChatError::file_error(
+                    path.display().to_string(),
+                    format!("Failed to resolve accounts file directory: {e}"),
+                )
+            })?;
+
+        let mut registry = Self::new(config)?;
+        for (name, mut profile) in file.accounts {
+            profile.resolve_partials(&dir);
+            registry.add(name, profile)?;
+        }
+        info!(
+            "Loaded {} accounts from {}",
+            registry.accounts.len(),
+            path.display()
+        );
+        Ok(registry)
+    }
+
+    /// Adds an account, replacing any account with the same name.
+    pub fn add(&mut self, name: impl Into<String>, profile: AccountProfile) -> Result<()> {
+        let name = name.into();
+        utils::validate_app_credentials(&profile.app_id, &profile.app_secret)
+            .map_err(|e| WeChatError::config_error(format!("Account '{name}': {e}")))?;
+
+        let account = Account {
+            profile,
+            client: OnceLock::new(),
+        };
+        if self.accounts.insert(name.clone(), account).is_some() {
+            warn!("Account '{name}' was replaced");
+        }
+        Ok(())
+    }
+
+    /// Names of the registered accounts, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    /// Gets the profile of an account.
+    pub fn profile(&self, name: &str) -> Result<&AccountProfile> {
+        Ok(&self.account(name)?.profile)
+    }
+
+    /// Gets the client of an account, building it on first use.
+    pub fn client(&self, name: &str) -> Result<Arc<WeChatClient>> {
+        let account = self.account(name)?;
+        let client = account.client.get_or_init(|| {
+            info!("Creating client for account '{name}'");
+            let http_client = Arc::new(self.http_client.with_own_rate_limiter());
+            let token_manager = TokenManager::new(
+                account.profile.app_id.clone(),
+                account.profile.app_secret.clone(),
+                Arc::clone(&http_client),
+            );
+            // Accounts share markdown files, so each keeps its own sync and series records
+            Arc::new(
+                WeChatClient::with_http_client(http_client, Arc::new(token_manager))
+                    .with_account_key(account.profile.app_id.clone()),
+            )
+        });
+        Ok(Arc::clone(client))
+    }
+
+    /// Upload options carrying the defaults of an account.
+    pub fn options(&self, name: &str) -> Result<UploadOptions> {
+        Ok(self.profile(name)?.upload_options())
+    }
+
+    /// Uploads a markdown file to an account with its defaults.
+    pub async fn upload(&self, name: &str, markdown_path: &str) -> Result<String> {
+        let options = self.options(name)?;
+        self.upload_with_options(name, markdown_path, options).await
+    }
+
+    /// Uploads a markdown file to an account with custom options.
+    ///
+    /// Start from [`options`](Self::options) to keep the defaults of the account.
+    pub async fn upload_with_options(
+        &self,
+        name: &str,
+        markdown_path: &str,
+        options: UploadOptions,
+    ) -> Result<String> {
+        self.client(name)?
+            .upload_with_options(markdown_path, options)
+            .await
+    }
+
+    /// Uploads a markdown file to several accounts, each with its defaults.
+    pub async fn publish(&self, markdown_path: &str, accounts: &[&str]) -> Vec<AccountUpload> {
+        self.publish_with(markdown_path, accounts, |_, options| options)
+            .await
+    }
+
+    /// Uploads a markdown file to several accounts, adjusting the options of each.
+    ///
+    /// `customize` receives the account name and its default options. Accounts are
+    /// uploaded one after another, and a failure doesn't stop the remaining uploads. Each
+    /// account keeps its own sync and series records, keyed by its AppID.
+    pub async fn publish_with<F>(
+        &self,
+        markdown_path: &str,
+        accounts: &[&str],
+        customize: F,
+    ) -> Vec<AccountUpload>
+    where
+        F: Fn(&str, UploadOptions) -> UploadOptions,
+    {
+        let mut uploads = Vec::with_capacity(accounts.len());
+        for &account in accounts {
+            let result = match self.options(account) {
+                Ok(options) => {
+                    let options = customize(account, options);
+                    self.upload_with_options(account, markdown_path, options)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            if let Err(e) = &result {
+                warn!("Upload of {markdown_path} to account '{account}' failed: {e}");
+            }
+            uploads.push(AccountUpload {
+                account: account.to_string(),
+                result,
+            });
+        }
+        uploads
+    }
+
+    fn account(&self, name: &str) -> Result<&Account> {
+        self.accounts
+            .get(name)
+            .ok_or_else(|| WeChatError::config_error(format!("Unknown account: {name}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TECH_ID: &str = "wx1234567890123456";
+    const LIFE_ID: &str = "wx6543210987654321";
+    const SECRET: &str = "12345678901234567890123456789012";
+
+    #[tokio::test]
+    async fn test_load_accounts_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("accounts.json");
+        let accounts = format!(
+            r#"{{"accounts": {{
+                "tech": {{"app_id": "{TECH_ID}", "app_secret": "{SECRET}", "theme": "lapis", "author": "技术组", "footer": "partials/footer.md"}},
+                "life": {{"app_id": "{LIFE_ID}", "app_secret": "{SECRET}", "header": "/srv/partials/header.html"}}
+            }}}}"#
+        );
+        tokio::fs::write(&path, accounts).await.unwrap();
+
+        let registry = AccountRegistry::load(&path, Config::default())
+            .await
+            .unwrap();
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["life", "tech"]);
+
+        let options = registry.options("tech").unwrap();
+        assert_eq!(options.theme, "lapis");
+        assert_eq!(options.author, None);
+        assert_eq!(options.default_author.as_deref(), Some("技术组"));
+        let footer = dir
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join("partials/footer.md");
+        assert_eq!(options.footer.as_deref(), footer.to_str());
+
+        let options = registry.options("life").unwrap();
+        assert_eq!(options.theme, "default");
+        assert_eq!(options.header.as_deref(), Some("/srv/partials/header.html"));
+
+        // The secret stays out of debug output
+        let debug = format!("{:?}", registry.profile("tech").unwrap());
+        assert!(debug.contains(TECH_ID));
+        assert!(!debug.contains(SECRET));
+    }
+
+    #[tokio::test]
+    async fn test_clients_are_lazy() {
+        let mut registry = AccountRegistry::new(Config::default()).unwrap();
+        registry
+            .add("tech", AccountProfile::new(TECH_ID, SECRET))
+            .unwrap();
+        registry
+            .add("life", AccountProfile::new(LIFE_ID, SECRET))
+            .unwrap();
+        assert!(registry.accounts["tech"].client.get().is_none());
+
+        let tech = registry.client("tech").unwrap();
+        assert!(Arc::ptr_eq(&tech, &registry.client("tech").unwrap()));
+        assert!(registry.accounts["life"].client.get().is_none());
+
+        registry.client("life").unwrap();
+        assert!(registry.accounts["life"].client.get().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_accounts_have_separate_rate_limits() {
+        use crate::config::{RateLimit, RateLimitConfig};
+        use crate::token_provider::StaticTokenProvider;
+
+        let config = Config::builder()
+            .rate_limit(
+                RateLimitConfig::builder()
+                    .limit("/cgi-bin/draft/add", RateLimit::per_day(100))
+                    .build(),
+            )
+            .build();
+        let mut registry = AccountRegistry::new(config).unwrap();
+        registry
+            .add("tech", AccountProfile::new(TECH_ID, SECRET))
+            .unwrap();
+        registry
+            .add("life", AccountProfile::new(LIFE_ID, SECRET))
+            .unwrap();
+        let tech = registry.client("tech").unwrap();
+        let life = registry.client("life").unwrap();
+
+        // One account running out of daily quota leaves the other alone
+        let error = tech
+            .http_client()
+            .execute_authorized(
+                "/cgi-bin/draft/add",
+                &StaticTokenProvider::new("token"),
+                |_| async {
+                    Ok(reqwest::Response::from(http::Response::new(
+                        r#"{"errcode":45009,"errmsg":"reach max api daily quota limit"}"#,
+                    )))
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), WeChatError::QuotaExceeded { .. }));
+
+        let remaining = |client: &WeChatClient| {
+            client
+                .http_client()
+                .rate_limiter()
+                .remaining("/cgi-bin/draft/add")
+        };
+        assert_eq!(remaining(&tech), Some(0));
+        assert_eq!(remaining(&life), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_invalid_accounts() {
+        let mut registry = AccountRegistry::new(Config::default()).unwrap();
+        assert!(matches!(
+            registry.client("tech"),
+            Err(WeChatError::Config { .. })
+        ));
+        assert!(
+            registry
+                .add("tech", AccountProfile::new("not-an-app-id", SECRET))
+                .is_err()
+        );
+
+        let uploads = registry.publish("article.md", &["tech"]).await;
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].account, "tech");
+        assert!(uploads[0].result.is_err());
+    }
+}
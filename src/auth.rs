@@ -10,6 +10,7 @@
 //! - **Expiration Handling**: Built-in buffer time to prevent edge cases
 //! - **Concurrent Protection**: Prevents multiple simultaneous refresh requests
 //! - **Error Recovery**: Comprehensive error handling for auth failures
+//! - **Stable Tokens**: Optional `cgi-bin/stable_token` strategy for AppIDs shared between services
 //!
 //! ## Token Lifecycle
 //!
@@ -71,16 +72,23 @@
 //! # }
 //! ```
 
+use crate::config::TokenStrategy;
 use crate::error::Result;
 use crate::http::{AccessTokenResponse, WeChatHttpClient, WeChatResponse};
+use crate::token_store::{FileTokenStore, MemoryTokenStore, RefreshGuard, TokenStore};
+use crate::traits::TokenProvider;
+use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Access token with expiration information.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Its `Debug` output leaves out the token.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct AccessToken {
     /// The access token string
     pub token: String,
@@ -108,31 +116,49 @@ impl AccessToken {
 }
 
 /// Token manager responsible for obtaining and caching access tokens.
-#[derive(Debug)]
+///
+/// Its `Debug` output leaves out the AppSecret and the cached token.
 pub struct TokenManager {
     app_id: String,
     app_secret: String,
     http_client: Arc<WeChatHttpClient>,
     token_cache: Arc<RwLock<Option<AccessToken>>>,
     refresh_lock: Arc<tokio::sync::Mutex<()>>,
+    store: Arc<dyn TokenStore>,
 }
 
 impl TokenManager {
     /// Creates a new token manager.
+    ///
+    /// Tokens are shared through the file set in
+    /// [`AuthConfig::token_file`](crate::config::AuthConfig::token_file), if any, and
+    /// otherwise kept in memory.
     pub fn new(
         app_id: impl Into<String>,
         app_secret: impl Into<String>,
         http_client: Arc<WeChatHttpClient>,
     ) -> Self {
+        let store: Arc<dyn TokenStore> = match &http_client.config().auth.token_file {
+            Some(path) => Arc::new(FileTokenStore::new(path)),
+            None => Arc::new(MemoryTokenStore::new()),
+        };
+
         Self {
             app_id: app_id.into(),
             app_secret: app_secret.into(),
             http_client,
             token_cache: Arc::new(RwLock::new(None)),
             refresh_lock: Arc::new(tokio::sync::Mutex::new(())),
+            store,
         }
     }
 
+    /// Reads and writes tokens through `store`, to share them with other managers.
+    pub fn with_store(mut self, store: Arc<dyn TokenStore>) -> Self {
+        self.store = store;
+        self
+    }
+
     /// Gets a valid access token, refreshing if necessary.
     ///
     /// This method is thread-safe and will prevent concurrent token refreshes.
@@ -168,18 +194,79 @@ impl TokenManager {
             return Ok(token);
         }
 
-        info!("Refreshing WeChat access token");
+        // Another manager sharing the store may have refreshed it already
+        let _store_guard = self.lock_store().await;
+        if let Some(token) = self.load_stored(None).await {
+            return Ok(token);
+        }
+
+        self.fetch_token(false).await
+    }
+
+    /// Replaces a token that WeChat rejected and returns a fresh one.
+    ///
+    /// Runs under the refresh lock. When another task has already replaced the rejected
+    /// token, its token is returned instead of fetching a new one again.
+    pub async fn refresh_rejected(&self, rejected: &str) -> Result<String> {
+        let _guard = self.refresh_lock.lock().await;
+
+        {
+            let mut cache = self.token_cache.write().await;
+            match cache.as_ref() {
+                Some(token) if token.token != rejected && !token.is_expired(60) => {
+                    return Ok(token.token.clone());
+                }
+                _ => *cache = None,
+            }
+        }
+
+        let _store_guard = self.lock_store().await;
+        if let Some(token) = self.load_stored(Some(rejected)).await {
+            return Ok(token);
+        }
+
+        warn!("Cached access token was rejected by WeChat, fetching a new one");
+        let token = self.fetch_token(false).await?;
+        if token != rejected {
+            return Ok(token);
+        }
 
-        // Make API call to get new token
-        let url = format!(
-            "https://api.weixin.qq.com/cgi-bin/token?grant_type=client_credential&appid={}&secret={}",
-            self.app_id, self.app_secret
-        );
+        // The stable endpoint handed back the rejected token, so replace it outright
+        self.fetch_token(true).await
+    }
 
-        let response_bytes = self.http_client.download(&url).await?;
+    /// Fetches a new token from the WeChat API and caches it.
+    ///
+    /// With [`TokenStrategy::Stable`], `force` asks WeChat to issue a new stable token
+    /// instead of returning the current one. Callers must hold the refresh lock.
+    async fn fetch_token(&self, force: bool) -> Result<String> {
+        info!("Refreshing WeChat access token");
 
-        let api_response: WeChatResponse<AccessTokenResponse> =
-            serde_json::from_slice(&response_bytes)?;
+        let config = self.http_client.config();
+        let api_response: WeChatResponse<AccessTokenResponse> = match self.strategy() {
+            TokenStrategy::Classic => {
+                self.http_client.throttle("/cgi-bin/token").await?;
+                let url = format!(
+                    "{}/cgi-bin/token?grant_type=client_credential&appid={}&secret={}",
+                    config.http.base_url, self.app_id, self.app_secret
+                );
+                let response_bytes = self.http_client.download(&url).await?;
+                serde_json::from_slice(&response_bytes)?
+            }
+            TokenStrategy::Stable => {
+                let request = serde_json::json!({
+                    "grant_type": "client_credential",
+                    "appid": self.app_id,
+                    "secret": self.app_secret,
+                    "force_refresh": force,
+                });
+                self.http_client
+                    .post_json("/cgi-bin/stable_token", &request)
+                    .await?
+                    .json()
+                    .await?
+            }
+        };
 
         let token_response = api_response.into_result()?;
 
@@ -187,6 +274,10 @@ impl TokenManager {
         let new_token = AccessToken::new(token_response.access_token, token_response.expires_in);
         let token_string = new_token.token.clone();
 
+        if let Err(e) = self.store.save(&self.app_id, &new_token).await {
+            warn!("Could not save the access token to the token store: {e}");
+        }
+
         // Update cache
         {
             let mut cache = self.token_cache.write().await;
@@ -198,33 +289,116 @@ impl TokenManager {
     }
 
     /// Forces a token refresh (useful for testing or when token is known to be invalid).
+    ///
+    /// With [`TokenStrategy::Stable`] this requests `force_refresh`, which invalidates the
+    /// stable token for every service sharing the AppID. WeChat limits how often that
+    /// may be done.
     pub async fn force_refresh(&self) -> Result<String> {
+        let _guard = self.refresh_lock.lock().await;
+
         // Clear cache first
         {
             let mut cache = self.token_cache.write().await;
             *cache = None;
         }
 
-        self.refresh_token().await
+        let _store_guard = self.lock_store().await;
+        self.fetch_token(true).await
+    }
+
+    /// Waits for other managers sharing the token store to finish refreshing.
+    ///
+    /// A store that cannot be locked is skipped, so tokens can still be fetched.
+    async fn lock_store(&self) -> Option<RefreshGuard> {
+        self.store
+            .lock_refresh(&self.app_id)
+            .await
+            .inspect_err(|e| warn!("Could not lock the token store: {e}"))
+            .ok()
+    }
+
+    /// Moves a valid stored token other than `rejected` into the cache.
+    async fn load_stored(&self, rejected: Option<&str>) -> Option<String> {
+        let stored = self
+            .store
+            .load(&self.app_id)
+            .await
+            .inspect_err(|e| warn!("Could not read the token store: {e}"))
+            .ok()??;
+        if stored.is_expired(60) || Some(stored.token.as_str()) == rejected {
+            return None;
+        }
+
+        let token = stored.token.clone();
+        *self.token_cache.write().await = Some(stored);
+        Some(token)
+    }
+
+    /// Gets the endpoint used to obtain tokens, from the HTTP client's configuration.
+    pub fn strategy(&self) -> TokenStrategy {
+        self.http_client.config().auth.token_strategy
     }
 
     /// Gets token information for debugging purposes.
     pub async fn get_token_info(&self) -> Option<TokenInfo> {
         let cache = self.token_cache.read().await;
-        cache.as_ref().map(|token| TokenInfo {
-            is_expired: token.is_expired(0),
-            expires_at: token.expires_at,
-            time_until_expiry: token.time_until_expiry(),
-        })
+        cache
+            .as_ref()
+            .map(|token| TokenInfo::from_expiry(token.expires_at))
     }
 
-    /// Clears the token cache.
+    /// Clears the token cache; the token store is left untouched.
     pub async fn clear_cache(&self) {
         let mut cache = self.token_cache.write().await;
         *cache = None;
     }
 }
 
+#[async_trait]
+impl TokenProvider for TokenManager {
+    async fn get_token(&self) -> Result<String> {
+        self.get_access_token().await
+    }
+
+    async fn refresh_token(&self) -> Result<String> {
+        self.force_refresh().await
+    }
+
+    async fn refresh_rejected(&self, rejected: &str) -> Result<String> {
+        TokenManager::refresh_rejected(self, rejected).await
+    }
+
+    async fn is_token_expired(&self) -> bool {
+        self.get_cached_token().await.is_none()
+    }
+
+    async fn token_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.token_cache
+            .read()
+            .await
+            .as_ref()
+            .map(|token| token.expires_at)
+    }
+}
+
+impl fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessToken")
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for TokenManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenManager")
+            .field("app_id", &self.app_id)
+            .field("strategy", &self.strategy())
+            .field("store", &self.store)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Token information for debugging and monitoring.
 #[derive(Debug, Clone)]
 pub struct TokenInfo {
@@ -233,9 +407,23 @@ pub struct TokenInfo {
     pub time_until_expiry: Duration,
 }
 
+impl TokenInfo {
+    /// Describes a token that expires at `expires_at`.
+    pub fn from_expiry(expires_at: DateTime<Utc>) -> Self {
+        let time_until_expiry = expires_at - Utc::now();
+        Self {
+            is_expired: time_until_expiry <= Duration::zero(),
+            expires_at,
+            time_until_expiry,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::WeChatError;
+    use crate::test_support::MockServer;
 
     #[test]
     fn test_access_token_expiry() {
@@ -298,6 +486,174 @@ mod tests {
         assert!(manager.get_cached_token().await.is_none());
     }
 
+    fn token_manager_for(base_url: &str, strategy: TokenStrategy) -> TokenManager {
+        let config = crate::config::Config::builder()
+            .http(
+                crate::config::HttpConfig::builder()
+                    .base_url(base_url.to_string())
+                    .build(),
+            )
+            .auth(
+                crate::config::AuthConfig::builder()
+                    .token_strategy(strategy)
+                    .build(),
+            )
+            .build();
+        let http_client = Arc::new(WeChatHttpClient::with_config(config).unwrap());
+        TokenManager::new("wx_app", "secret", http_client)
+    }
+
+    #[tokio::test]
+    async fn test_secrets_never_formatted() {
+        let token = AccessToken::new("token_value_123".to_string(), 3600);
+        assert!(!format!("{token:?}").contains("token_value_123"));
+
+        // Nothing listens on port 1, so the token request fails
+        let config = crate::config::Config::builder()
+            .http(
+                crate::config::HttpConfig::builder()
+                    .base_url("http://127.0.0.1:1".to_string())
+                    .build(),
+            )
+            .retry(
+                crate::config::RetryConfig::builder()
+                    .max_attempts(1)
+                    .build(),
+            )
+            .build();
+        let http_client = Arc::new(WeChatHttpClient::with_config(config).unwrap());
+        let manager = TokenManager::new("wx_app", "secret_value_456", http_client);
+        *manager.token_cache.write().await = Some(token);
+
+        let debug = format!("{manager:?}");
+        assert!(debug.contains("wx_app"));
+        assert!(!debug.contains("secret_value_456"));
+        assert!(!debug.contains("token_value_123"));
+
+        let error = manager.force_refresh().await.unwrap_err();
+        for formatted in [error.to_string(), format!("{error:?}")] {
+            assert!(formatted.contains("secret=***"), "{formatted}");
+            assert!(!formatted.contains("secret_value_456"), "{formatted}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_classic_token_uses_base_url() {
+        let server = MockServer::tokens().await;
+        let manager = token_manager_for(&server.url, TokenStrategy::Classic);
+
+        assert_eq!(manager.get_access_token().await.unwrap(), "token1");
+        // Served from the cache
+        assert_eq!(manager.get_access_token().await.unwrap(), "token1");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with(
+            "GET /cgi-bin/token?grant_type=client_credential&appid=wx_app&secret=secret "
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stable_token_strategy() {
+        let server = MockServer::tokens().await;
+        let manager = token_manager_for(&server.url, TokenStrategy::Stable);
+
+        assert_eq!(manager.get_access_token().await.unwrap(), "token1");
+        assert_eq!(manager.force_refresh().await.unwrap(), "token2");
+
+        let requests = server.requests();
+        let bodies: Vec<serde_json::Value> = requests
+            .iter()
+            .map(|request| {
+                assert!(request.starts_with("POST /cgi-bin/stable_token "));
+                serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap()
+            })
+            .collect();
+        assert_eq!(bodies[0]["appid"], "wx_app");
+        assert_eq!(bodies[0]["force_refresh"], false);
+        assert_eq!(bodies[1]["force_refresh"], true);
+    }
+
+    #[tokio::test]
+    async fn test_managers_share_tokens_through_store() {
+        let server = MockServer::tokens().await;
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(FileTokenStore::new(dir.path().join("tokens.json")));
+
+        let cron = token_manager_for(&server.url, TokenStrategy::Classic).with_store(store.clone());
+        let web = token_manager_for(&server.url, TokenStrategy::Classic).with_store(store);
+
+        assert_eq!(cron.get_access_token().await.unwrap(), "token1");
+        assert_eq!(web.get_access_token().await.unwrap(), "token1");
+        assert_eq!(server.requests().len(), 1);
+
+        // The web service replaces the rejected token, and the cron job picks it up
+        assert_eq!(web.refresh_rejected("token1").await.unwrap(), "token2");
+        assert_eq!(cron.refresh_rejected("token1").await.unwrap(), "token2");
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    fn json_response(body: &str) -> reqwest::Response {
+        reqwest::Response::from(http::Response::new(body.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_rejected_token_is_replaced_and_request_replayed() {
+        let http_client = Arc::new(WeChatHttpClient::new().unwrap());
+        let manager = TokenManager::new("test_app_id", "test_app_secret", http_client.clone());
+        *manager.token_cache.write().await = Some(AccessToken::new("stale".to_string(), 3600));
+
+        let sent = std::sync::Mutex::new(Vec::new());
+        let response = http_client
+            .execute_authorized("/cgi-bin/draft/add", &manager, |token| {
+                sent.lock().unwrap().push(token.clone());
+                let manager = &manager;
+                async move {
+                    if token == "stale" {
+                        // Another task refreshes the token while this request is in flight
+                        *manager.token_cache.write().await =
+                            Some(AccessToken::new("fresh".to_string(), 3600));
+                        Ok(json_response(
+                            r#"{"errcode":40001,"errmsg":"invalid credential"}"#,
+                        ))
+                    } else {
+                        Ok(json_response(r#"{"errcode":0,"media_id":"m1"}"#))
+                    }
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(*sent.lock().unwrap(), ["stale", "fresh"]);
+        assert!(response.text().await.unwrap().contains("m1"));
+        assert_eq!(manager.get_cached_token().await, Some("fresh".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_replayed() {
+        let http_client = Arc::new(WeChatHttpClient::new().unwrap());
+        let manager = TokenManager::new("test_app_id", "test_app_secret", http_client.clone());
+        *manager.token_cache.write().await = Some(AccessToken::new("valid".to_string(), 3600));
+
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let error = http_client
+            .execute_authorized("/cgi-bin/draft/add", &manager, |_| {
+                calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Ok(json_response(r#"{"errcode":45009,"errmsg":"quota"}"#)) }
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded {
+                code: Some(45009),
+                ..
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn test_token_info() {
         let http_client = Arc::new(WeChatHttpClient::new().unwrap());
@@ -1,18 +1,39 @@
 //! Main WeChat client implementation.
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::auth::TokenManager;
+use crate::config::Config;
 use crate::datacube::DatacubeClient;
-use crate::error::{Result, WeChatError};
+use crate::diff::{self, DraftDiff, SyncRecord};
+use crate::error::{ErrorContext, ErrorFrame, Result, Stage, WeChatError};
+use crate::export::{self, HtmlExport};
 use crate::http::WeChatHttpClient;
-use crate::markdown::{MarkdownContent, MarkdownParser};
+use crate::markdown::ImageRef;
+use crate::markdown::{DigestOptions, MarkdownContent, MarkdownParser};
 use crate::mermaid::MermaidProcessor;
-use crate::theme::ThemeManager;
-use crate::upload::{Article, DraftInfo, DraftManager, ImageUploader};
+use crate::plan::{DraftAction, PlannedImage, UploadPlan};
+use crate::quota::QuotaClient;
+use crate::reverse::{self, PulledArticle};
+use crate::series::{self, SeriesInfo, SeriesRecord};
+use crate::theme::{CodeOverflow, DEFAULT_CODE_COLUMNS, Partials, ThemeManager};
+use crate::traits::{TokenProvider, Validate};
+use crate::upload::{
+    Article, DraftInfo, DraftManager, ImagePolicy, ImageUploadReport, ImageUploader,
+    MAX_DOWNLOAD_SIZE, image_extension, load_local_image,
+};
 use crate::utils;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
+
+/// Number of materials fetched per page when resolving image names.
+const MATERIAL_PAGE_SIZE: u32 = 20;
+
+/// Matches the `src` attribute of `<img>` tags in HTML partials.
+static IMG_SRC_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<img\b[^>]*?\ssrc="([^"]+)""#).unwrap());
 
 /// Upload options for customizing the upload behavior.
 #[derive(Debug, Clone)]
@@ -23,6 +44,8 @@ pub struct UploadOptions {
     pub title: Option<String>,
     /// Custom author (overrides extracted author)
     pub author: Option<String>,
+    /// Author used when neither the options nor the frontmatter set one
+    pub default_author: Option<String>,
     /// Path to cover image file
     pub cover_image: Option<String>,
     /// Whether to show cover image in content
@@ -33,6 +56,16 @@ pub struct UploadOptions {
     pub fans_only_comments: bool,
     /// Source URL for the article
     pub source_url: Option<String>,
+    /// Path to a header partial (markdown or HTML), relative to the article
+    pub header: Option<String>,
+    /// Path to a footer partial (markdown or HTML), relative to the article
+    pub footer: Option<String>,
+    /// Overwrite drafts even if they were edited remotely since the last upload
+    pub force: bool,
+    /// What to do when an image can't be uploaded
+    pub image_policy: ImagePolicy,
+    /// URL used instead of images skipped by the image policy (default: their original URL)
+    pub image_placeholder: Option<String>,
 }
 
 impl Default for UploadOptions {
@@ -41,11 +74,17 @@ impl Default for UploadOptions {
             theme: "default".to_string(),
             title: None,
             author: None,
+            default_author: None,
             cover_image: None,
             show_cover: true,
             enable_comments: false,
             fans_only_comments: false,
             source_url: None,
+            header: None,
+            footer: None,
+            force: false,
+            image_policy: ImagePolicy::default(),
+            image_placeholder: None,
         }
     }
 }
@@ -71,6 +110,12 @@ impl UploadOptions {
         self
     }
 
+    /// Sets the author used when the frontmatter doesn't name one.
+    pub fn default_author(mut self, author: impl Into<String>) -> Self {
+        self.default_author = Some(author.into());
+        self
+    }
+
     /// Sets the cover image path.
     pub fn cover_image(mut self, path: impl Into<String>) -> Self {
         self.cover_image = Some(path.into());
@@ -95,31 +140,100 @@ impl UploadOptions {
         self.source_url = Some(url.into());
         self
     }
+
+    /// Sets the header partial shown above every article uploaded with these options.
+    ///
+    /// A `header:` key in frontmatter takes precedence; `header: none` disables it.
+    pub fn header(mut self, path: impl Into<String>) -> Self {
+        self.header = Some(path.into());
+        self
+    }
+
+    /// Sets the footer partial shown below every article uploaded with these options.
+    ///
+    /// A `footer:` key in frontmatter takes precedence; `footer: none` disables it.
+    pub fn footer(mut self, path: impl Into<String>) -> Self {
+        self.footer = Some(path.into());
+        self
+    }
+
+    /// Sets whether to overwrite drafts that were edited remotely.
+    ///
+    /// Without it, uploads stop with [`WeChatError::DraftConflict`] when the draft no
+    /// longer matches its state at the last upload or pull, and fail when that draft
+    /// can't be fetched to check; see [`crate::diff`].
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Sets what to do when an image can't be uploaded.
+    ///
+    /// By default the upload fails at the first broken image. With
+    /// [`ImagePolicy::SkipBroken`] or [`ImagePolicy::RetryThenSkip`], broken images are
+    /// left out and listed in the [`UploadReport`] of
+    /// [`upload_with_report`](WeChatClient::upload_with_report).
+    pub fn image_policy(mut self, policy: ImagePolicy) -> Self {
+        self.image_policy = policy;
+        self
+    }
+
+    /// Sets the URL used instead of skipped images, such as an already uploaded
+    /// "image unavailable" picture.
+    pub fn image_placeholder(mut self, url: impl Into<String>) -> Self {
+        self.image_placeholder = Some(url.into());
+        self
+    }
+}
+
+/// The result of uploading an article.
+#[derive(Debug, Clone)]
+pub struct UploadReport {
+    /// Media ID of the created draft
+    pub media_id: String,
+    /// Outcome of each image of the article
+    pub images: ImageUploadReport,
 }
 
 /// Main WeChat Official Account client.
 #[derive(Debug)]
 pub struct WeChatClient {
     http_client: Arc<WeChatHttpClient>,
-    token_manager: Arc<TokenManager>,
+    token_provider: Arc<dyn TokenProvider>,
     image_uploader: ImageUploader,
     draft_manager: DraftManager,
     markdown_parser: MarkdownParser,
     theme_manager: ThemeManager,
     datacube_client: DatacubeClient,
+    quota_client: QuotaClient,
+    /// Account whose sync and series records this client keeps, if kept per account
+    account_key: Option<String>,
 }
 
 impl WeChatClient {
     /// Creates a new WeChat client with app credentials.
     pub async fn new(app_id: impl Into<String>, app_secret: impl Into<String>) -> Result<Self> {
+        Self::with_config(app_id, app_secret, Config::default()).await
+    }
+
+    /// Creates a new WeChat client with app credentials and custom configuration.
+    ///
+    /// The configuration sets the API base URL, timeouts, retries and the
+    /// [`TokenStrategy`](crate::config::TokenStrategy) used to obtain access tokens.
+    pub async fn with_config(
+        app_id: impl Into<String>,
+        app_secret: impl Into<String>,
+        config: Config,
+    ) -> Result<Self> {
         let app_id = app_id.into();
         let app_secret = app_secret.into();
 
         // Validate credentials format
         utils::validate_app_credentials(&app_id, &app_secret).map_err(WeChatError::config_error)?;
+        config.validate()?;
 
         // Create HTTP client
-        let http_client = Arc::new(WeChatHttpClient::new()?);
+        let http_client = Arc::new(WeChatHttpClient::with_config(config)?);
 
         // Create token manager
         let token_manager = Arc::new(TokenManager::new(
@@ -128,28 +242,66 @@ impl WeChatClient {
             Arc::clone(&http_client),
         ));
 
+        Ok(Self::with_http_client(http_client, token_manager))
+    }
+
+    /// Creates a client that gets its access tokens from `token_provider`.
+    ///
+    /// Use this when the AppSecret is held elsewhere, such as by a central token service
+    /// reached through [`HttpTokenProvider`](crate::token_provider::HttpTokenProvider).
+    /// Every API call, including image, draft and datacube calls, goes through the provider.
+    pub fn with_token_provider(
+        token_provider: Arc<dyn TokenProvider>,
+        config: Config,
+    ) -> Result<Self> {
+        config.validate()?;
+        let http_client = Arc::new(WeChatHttpClient::with_config(config)?);
+        Ok(Self::with_http_client(http_client, token_provider))
+    }
+
+    /// Creates a client from an existing HTTP client and token provider.
+    ///
+    /// Clients created from the same HTTP client share its connection pool.
+    pub fn with_http_client(
+        http_client: Arc<WeChatHttpClient>,
+        token_provider: Arc<dyn TokenProvider>,
+    ) -> Self {
         // Create service components
         let image_uploader =
-            ImageUploader::new(Arc::clone(&http_client), Arc::clone(&token_manager));
+            ImageUploader::new(Arc::clone(&http_client), Arc::clone(&token_provider));
 
-        let draft_manager = DraftManager::new(Arc::clone(&http_client), Arc::clone(&token_manager));
+        let draft_manager =
+            DraftManager::new(Arc::clone(&http_client), Arc::clone(&token_provider));
 
         let datacube_client =
-            DatacubeClient::new(Arc::clone(&http_client), Arc::clone(&token_manager));
+            DatacubeClient::new(Arc::clone(&http_client), Arc::clone(&token_provider));
 
+        let quota_client = QuotaClient::new(Arc::clone(&http_client), Arc::clone(&token_provider));
 
         let markdown_parser = MarkdownParser::new();
         let theme_manager = ThemeManager::new();
 
-        Ok(Self {
+        Self {
             http_client,
-            token_manager,
+            token_provider,
             image_uploader,
             draft_manager,
             markdown_parser,
             theme_manager,
             datacube_client,
-        })
+            quota_client,
+            account_key: None,
+        }
+    }
+
+    /// Keeps the sync and series records of this client apart from other accounts.
+    ///
+    /// Records are named after `key`, usually the AppID, so clients of several accounts
+    /// uploading the same markdown file each track their own drafts. See
+    /// [`SyncRecord::path_for`] and [`SeriesRecord::path_for`].
+    pub fn with_account_key(mut self, key: impl Into<String>) -> Self {
+        self.account_key = Some(key.into());
+        self
     }
 
     /// Uploads a markdown file as a WeChat draft article.
@@ -179,56 +331,102 @@ impl WeChatClient {
     ///
     /// # Returns
     /// Returns the media ID of the created draft
+    ///
+    /// Errors carry an [`ErrorFrame::Article`] and the [`Stage`] that failed; see
+    /// [`WeChatError::kind`] to match on the underlying error.
     pub async fn upload_with_options(
         &self,
         markdown_path: &str,
         options: UploadOptions,
     ) -> Result<String> {
-        let markdown_path = Path::new(markdown_path);
+        self.upload_with_report(markdown_path, options)
+            .await
+            .map(|report| report.media_id)
+    }
 
+    /// Uploads a markdown file and reports the outcome of each image.
+    ///
+    /// Use it with an [`ImagePolicy`] that skips broken images to find out which were left
+    /// out of the draft.
+    pub async fn upload_with_report(
+        &self,
+        markdown_path: &str,
+        options: UploadOptions,
+    ) -> Result<UploadReport> {
+        self.upload_article(Path::new(markdown_path), options)
+            .await
+            .with_context(|| ErrorFrame::Article(markdown_path.to_string()))
+    }
+
+    async fn upload_article(
+        &self,
+        markdown_path: &Path,
+        options: UploadOptions,
+    ) -> Result<UploadReport> {
         // Validate input
-        self.validate_upload_input(markdown_path, &options).await?;
+        Self::validate_upload_input(&self.markdown_parser, markdown_path, &options, true)
+            .await
+            .context(ErrorFrame::Stage(Stage::Parse))?;
 
         info!("Starting upload process for: {}", markdown_path.display());
 
         // Step 1: Parse markdown content
-        let mut content = self.parse_markdown_file(markdown_path).await?;
+        let mut content = self
+            .parse_markdown_file(markdown_path)
+            .await
+            .context(ErrorFrame::Stage(Stage::Parse))?;
         debug!("Found {} images in content", content.images.len());
 
         // Step 1.5: Process Mermaid charts
         let base_dir = utils::get_base_directory(markdown_path).unwrap_or_else(|| Path::new("."));
-        let document_slug = MermaidProcessor::extract_slug_from_path(markdown_path);
-        let mermaid_processor = MermaidProcessor::new(base_dir.to_path_buf(), document_slug);
-
-        let (modified_content, mermaid_images) = mermaid_processor
-            .process_mermaid_content_with_source_path(
-                &content.content,
-                base_dir,
-                Some(markdown_path),
-            )
-            .await?;
-
-        // Update content with Mermaid-processed version
-        content.content = modified_content;
-
-        // Add Mermaid-generated images to the image list
-        content.images.extend(mermaid_images.clone());
+        render_mermaid_charts(&mut content, markdown_path, base_dir)
+            .await
+            .context(ErrorFrame::Stage(Stage::Mermaid))?;
 
         debug!(
             "Total images to upload (including Mermaid): {}",
             content.images.len()
         );
 
+        // Resolve theme (from frontmatter, options, or default)
+        let theme = content
+            .theme
+            .clone()
+            .unwrap_or_else(|| options.theme.clone());
+
+        // Validate theme exists
+        if !self.theme_manager.has_theme(&theme) {
+            return Err(
+                WeChatError::ThemeNotFound { theme }.context(ErrorFrame::Stage(Stage::Render))
+            );
+        }
+
+        // Check WeChat field limits before uploading anything
+        Self::preflight_check(&self.theme_manager, &content, &theme, &options)
+            .context(ErrorFrame::Stage(Stage::Render))?;
+
+        // A draft with the same title is updated, so make sure it has no remote edits
+        let existing_draft = self
+            .draft_manager
+            .find_draft_by_title(&article_title(&content, &options))
+            .await
+            .context(ErrorFrame::Stage(Stage::Draft))?;
+        if let Some(media_id) = &existing_draft {
+            self.check_synced_draft(markdown_path, media_id, &options)
+                .await
+                .context(ErrorFrame::Stage(Stage::Draft))?;
+        }
+
         // Step 2: Upload images concurrently
-        let upload_results = self
-            .image_uploader
-            .upload_images(content.images.clone(), base_dir)
+        let images = self
+            .upload_article_images(&content, base_dir, &options)
             .await?;
-        info!("Completed uploading {} images", upload_results.len());
 
         // Step 3: Replace image URLs in content
-        let url_mapping = self.draft_manager.create_url_mapping(&upload_results);
-        content.replace_image_urls(&url_mapping)?;
+        let url_mapping = images.url_mapping(options.image_placeholder.as_deref());
+        content
+            .replace_image_urls(&url_mapping)
+            .context(ErrorFrame::Stage(Stage::Image))?;
 
         // Step 4: Upload cover image (from options or frontmatter)
         let cover_path = options
@@ -238,32 +436,92 @@ impl WeChatClient {
             .expect("Cover image should be available from validation");
 
         info!("Starting to upload cover image: {}", cover_path);
-        let cover_media_id = Some(self.upload_cover_image(cover_path, base_dir).await?);
+        let cover_media_id = Some(
+            self.upload_cover_image(cover_path, base_dir)
+                .await
+                .context(ErrorFrame::Stage(Stage::Cover))?,
+        );
         info!("Completed uploading cover image");
 
-        // Step 5: Render content with theme (from frontmatter, options, or default)
-        let theme = content
-            .theme
-            .as_ref()
-            .or(Some(&options.theme))
-            .map(|t| t.as_str())
-            .unwrap_or("default");
-
-        // Validate theme exists
-        if !self.theme_manager.has_theme(theme) {
-            return Err(WeChatError::ThemeNotFound {
-                theme: theme.to_string(),
-            });
-        }
-
-        let html_content = self.render_content(&content, theme, &options)?;
+        // Step 5: Render content with theme
+        let partials = self
+            .load_partials(&content, &options, base_dir)
+            .await
+            .context(ErrorFrame::Stage(Stage::Render))?;
+        let html_content =
+            Self::render_content(&self.theme_manager, &content, &theme, &options, &partials)
+                .context(ErrorFrame::Stage(Stage::Render))?;
 
         // Step 6: Create article and draft
-        let article = self.create_article(&content, &options, html_content, cover_media_id);
-        let draft_id = self.draft_manager.create_draft(vec![article]).await?;
+        let article = Self::create_article(&content, &options, html_content, cover_media_id);
+        article
+            .validate()
+            .context(ErrorFrame::Stage(Stage::Draft))?;
+        let title = article.title.clone();
+        let draft_id = match existing_draft {
+            Some(media_id) => {
+                info!("Updating existing draft with title '{title}': {media_id}");
+                self.draft_manager
+                    .update_draft(&media_id, vec![article])
+                    .await
+                    .context(ErrorFrame::Stage(Stage::Draft))?;
+                media_id
+            }
+            None => self
+                .draft_manager
+                .add_draft(vec![article])
+                .await
+                .context(ErrorFrame::Stage(Stage::Draft))?,
+        };
+        self.record_sync(markdown_path, &draft_id).await;
+        record_series_draft(
+            &content,
+            base_dir,
+            self.account_key.as_deref(),
+            &title,
+            &draft_id,
+        )
+        .await;
 
         info!("Successfully created draft with ID: {draft_id}");
-        Ok(draft_id)
+        Ok(UploadReport {
+            media_id: draft_id,
+            images,
+        })
+    }
+
+    /// Runs the upload pipeline offline and reports what would be published.
+    ///
+    /// Parses the markdown, renders Mermaid charts, reads and hashes local images,
+    /// renders the article and checks its fields, without credentials or network
+    /// access. Errors are the ones a real upload would hit before reaching WeChat.
+    pub async fn plan(markdown_path: &str, options: UploadOptions) -> Result<UploadPlan> {
+        Self::build_plan(markdown_path, options, None).await
+    }
+
+    /// Like [`plan`](Self::plan), for updating an existing draft.
+    pub async fn plan_update(
+        media_id: &str,
+        markdown_path: &str,
+        options: UploadOptions,
+    ) -> Result<UploadPlan> {
+        Self::build_plan(markdown_path, options, Some(media_id)).await
+    }
+
+    /// Renders the article offline into a self-contained HTML export.
+    ///
+    /// Runs the same pipeline as [`plan`](Self::plan), then embeds local images as
+    /// data URIs. The article is validated with local image paths, since embedded
+    /// images would count towards WeChat's content size limit. Unlike drafts, exports
+    /// need no cover image.
+    pub async fn export_html(markdown_path: &str, options: UploadOptions) -> Result<HtmlExport> {
+        let offline = OfflineArticle::load(Path::new(markdown_path), &options, false, None).await?;
+
+        let html_content = offline.render(&options, &ImageUrls::default())?;
+        Self::create_article(&offline.content, &options, html_content, None).validate()?;
+
+        let urls = offline.embedded_image_urls().await?;
+        Ok(HtmlExport::new(offline.render(&options, &urls)?))
     }
 
     /// Gets a draft by media ID.
@@ -279,14 +537,32 @@ impl WeChatClient {
     }
 
     /// Updates an existing draft with custom options.
+    ///
+    /// Errors carry context like those of [`upload_with_options`](Self::upload_with_options).
     pub async fn update_draft_with_options(
         &self,
         media_id: &str,
         markdown_path: &str,
         options: UploadOptions,
     ) -> Result<()> {
-        let markdown_path = Path::new(markdown_path);
-        self.validate_upload_input(markdown_path, &options).await?;
+        self.update_article(media_id, Path::new(markdown_path), options)
+            .await
+            .with_context(|| ErrorFrame::Article(markdown_path.to_string()))
+    }
+
+    async fn update_article(
+        &self,
+        media_id: &str,
+        markdown_path: &Path,
+        options: UploadOptions,
+    ) -> Result<()> {
+        Self::validate_upload_input(&self.markdown_parser, markdown_path, &options, true)
+            .await
+            .context(ErrorFrame::Stage(Stage::Parse))?;
+
+        self.check_synced_draft(markdown_path, media_id, &options)
+            .await
+            .context(ErrorFrame::Stage(Stage::Draft))?;
 
         info!(
             "Updating draft {} with: {}",
@@ -295,34 +571,40 @@ impl WeChatClient {
         );
 
         // Parse and process content (same as upload)
-        let mut content = self.parse_markdown_file(markdown_path).await?;
+        let mut content = self
+            .parse_markdown_file(markdown_path)
+            .await
+            .context(ErrorFrame::Stage(Stage::Parse))?;
         let base_dir = utils::get_base_directory(markdown_path).unwrap_or_else(|| Path::new("."));
 
         // Process Mermaid charts
-        let document_slug = MermaidProcessor::extract_slug_from_path(markdown_path);
-        let mermaid_processor = MermaidProcessor::new(base_dir.to_path_buf(), document_slug);
-
-        let (modified_content, mermaid_images) = mermaid_processor
-            .process_mermaid_content_with_source_path(
-                &content.content,
-                base_dir,
-                Some(markdown_path),
-            )
-            .await?;
+        render_mermaid_charts(&mut content, markdown_path, base_dir)
+            .await
+            .context(ErrorFrame::Stage(Stage::Mermaid))?;
 
-        // Update content with Mermaid-processed version
-        content.content = modified_content;
+        let theme = content
+            .theme
+            .clone()
+            .unwrap_or_else(|| options.theme.clone());
 
-        // Add Mermaid-generated images to the image list
-        content.images.extend(mermaid_images);
+        // Validate theme exists
+        if !self.theme_manager.has_theme(&theme) {
+            return Err(
+                WeChatError::ThemeNotFound { theme }.context(ErrorFrame::Stage(Stage::Render))
+            );
+        }
 
-        let upload_results = self
-            .image_uploader
-            .upload_images(content.images.clone(), base_dir)
+        Self::preflight_check(&self.theme_manager, &content, &theme, &options)
+            .context(ErrorFrame::Stage(Stage::Render))?;
+
+        let images = self
+            .upload_article_images(&content, base_dir, &options)
             .await?;
 
-        let url_mapping = self.draft_manager.create_url_mapping(&upload_results);
-        content.replace_image_urls(&url_mapping)?;
+        let url_mapping = images.url_mapping(options.image_placeholder.as_deref());
+        content
+            .replace_image_urls(&url_mapping)
+            .context(ErrorFrame::Stage(Stage::Image))?;
 
         let cover_path = options
             .cover_image
@@ -330,28 +612,38 @@ impl WeChatClient {
             .or(content.cover.as_ref())
             .expect("Cover image should be available from validation");
 
-        let cover_media_id = Some(self.upload_cover_image(cover_path, base_dir).await?);
-
-        let theme = content
-            .theme
-            .as_ref()
-            .or(Some(&options.theme))
-            .map(|t| t.as_str())
-            .unwrap_or("default");
-
-        // Validate theme exists
-        if !self.theme_manager.has_theme(theme) {
-            return Err(WeChatError::ThemeNotFound {
-                theme: theme.to_string(),
-            });
-        }
+        let cover_media_id = Some(
+            self.upload_cover_image(cover_path, base_dir)
+                .await
+                .context(ErrorFrame::Stage(Stage::Cover))?,
+        );
 
-        let html_content = self.render_content(&content, theme, &options)?;
-        let article = self.create_article(&content, &options, html_content, cover_media_id);
+        let partials = self
+            .load_partials(&content, &options, base_dir)
+            .await
+            .context(ErrorFrame::Stage(Stage::Render))?;
+        let html_content =
+            Self::render_content(&self.theme_manager, &content, &theme, &options, &partials)
+                .context(ErrorFrame::Stage(Stage::Render))?;
+        let article = Self::create_article(&content, &options, html_content, cover_media_id);
+        article
+            .validate()
+            .context(ErrorFrame::Stage(Stage::Draft))?;
+        let title = article.title.clone();
 
         self.draft_manager
             .update_draft(media_id, vec![article])
-            .await?;
+            .await
+            .context(ErrorFrame::Stage(Stage::Draft))?;
+        self.record_sync(markdown_path, media_id).await;
+        record_series_draft(
+            &content,
+            base_dir,
+            self.account_key.as_deref(),
+            &title,
+            media_id,
+        )
+        .await;
 
         info!("Successfully updated draft: {media_id}");
         Ok(())
@@ -367,6 +659,136 @@ impl WeChatClient {
         self.draft_manager.list_drafts(offset, count).await
     }
 
+    /// Compares a local markdown file with a remote draft.
+    pub async fn diff_draft(&self, media_id: &str, markdown_path: &str) -> Result<DraftDiff> {
+        self.diff_draft_with_options(media_id, markdown_path, UploadOptions::default())
+            .await
+    }
+
+    /// Compares a local markdown file, rendered with custom options, with a remote draft.
+    ///
+    /// Local images are compared by content hash, and remote images are resolved to
+    /// hashes through the names of the permanent materials they were uploaded as.
+    /// See [`diff`] for how the HTML is normalised.
+    pub async fn diff_draft_with_options(
+        &self,
+        media_id: &str,
+        markdown_path: &str,
+        options: UploadOptions,
+    ) -> Result<DraftDiff> {
+        let offline = OfflineArticle::load(
+            Path::new(markdown_path),
+            &options,
+            true,
+            self.account_key.as_deref(),
+        )
+        .await?;
+        let html_content = offline.render(&options, &offline.hashed_image_urls().await?)?;
+        let cover = offline
+            .cover
+            .as_ref()
+            .and_then(|cover| cover.hash.as_deref())
+            .map(diff::image_token);
+        let local = Self::create_article(&offline.content, &options, html_content, cover);
+
+        let remote = self.draft_article(media_id).await?;
+        let image_names = self
+            .material_image_names(
+                &reverse::image_urls(&remote.content),
+                remote.thumb_media_id.as_deref(),
+            )
+            .await?;
+
+        Ok(DraftDiff::new(media_id, &local, &remote, &image_names))
+    }
+
+    /// Gets the articles of a published item by its article ID.
+    pub async fn get_published_article(&self, article_id: &str) -> Result<Vec<Article>> {
+        self.draft_manager.get_published_article(article_id).await
+    }
+
+    /// Pulls a draft back into a markdown file.
+    ///
+    /// Writes the first article of the draft to `markdown_path`, with frontmatter
+    /// rebuilt from its fields and its images and cover downloaded into an `images/`
+    /// folder next to it. See [`reverse`] for what the conversion recovers.
+    ///
+    /// An existing markdown file is only replaced when `overwrite` is set.
+    pub async fn pull_draft(
+        &self,
+        media_id: &str,
+        markdown_path: &str,
+        overwrite: bool,
+    ) -> Result<PulledArticle> {
+        check_pull_target(Path::new(markdown_path), overwrite).await?;
+        let draft = self.get_draft(media_id).await?;
+        let pulled = self
+            .pull_article(draft.content.news_item.clone(), Path::new(markdown_path))
+            .await?;
+
+        // The pulled file now matches the draft, so later uploads may overwrite it
+        SyncRecord::new(media_id, &draft.content.news_item[0])
+            .save(&pulled.markdown_path, self.account_key.as_deref())
+            .await?;
+        Ok(pulled)
+    }
+
+    /// Like [`pull_draft`](Self::pull_draft), for a published article.
+    pub async fn pull_published(
+        &self,
+        article_id: &str,
+        markdown_path: &str,
+        overwrite: bool,
+    ) -> Result<PulledArticle> {
+        check_pull_target(Path::new(markdown_path), overwrite).await?;
+        let articles = self.get_published_article(article_id).await?;
+        self.pull_article(articles, Path::new(markdown_path)).await
+    }
+
+    /// Records the permanent link of a published series part.
+    ///
+    /// Reads the link of `article_id` and stores it in the series record next to
+    /// `markdown_path`, so the navigation box of the other parts links to it on their
+    /// next render. Returns the link.
+    pub async fn record_series_published(
+        &self,
+        markdown_path: &str,
+        article_id: &str,
+    ) -> Result<String> {
+        let markdown_path = Path::new(markdown_path);
+        let content = self.parse_markdown_file(markdown_path).await?;
+        let info = SeriesInfo::from_metadata(&content.metadata)?.ok_or_else(|| {
+            WeChatError::config_error(format!(
+                "{} has no series in its frontmatter",
+                markdown_path.display()
+            ))
+        })?;
+
+        let article = self
+            .get_published_article(article_id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeChatError::Internal {
+                message: format!("Published article {article_id} has no content"),
+            })?;
+        let url =
+            article
+                .url
+                .filter(|url| !url.is_empty())
+                .ok_or_else(|| WeChatError::Internal {
+                    message: format!("Published article {article_id} has no permanent link"),
+                })?;
+
+        let dir = utils::get_base_directory(markdown_path).unwrap_or_else(|| Path::new("."));
+        let account = self.account_key.as_deref();
+        let mut record = SeriesRecord::load(dir, account).await?;
+        record.set_url(&info, &article.title, &url);
+        record.save(dir, account).await?;
+
+        Ok(url)
+    }
+
     /// Uploads a single image file and returns the WeChat URL.
     pub async fn upload_image(&self, image_path: &str) -> Result<String> {
         let image_path = Path::new(image_path);
@@ -417,12 +839,15 @@ impl WeChatClient {
 
     /// Gets access token information for debugging.
     pub async fn get_token_info(&self) -> Option<crate::auth::TokenInfo> {
-        self.token_manager.get_token_info().await
+        self.token_provider
+            .token_expires_at()
+            .await
+            .map(crate::auth::TokenInfo::from_expiry)
     }
 
     /// Forces a token refresh.
     pub async fn refresh_token(&self) -> Result<String> {
-        self.token_manager.force_refresh().await
+        self.token_provider.refresh_token().await
     }
 
     /// Gets the underlying HTTP client for advanced usage.
@@ -437,10 +862,220 @@ impl WeChatClient {
         &self.datacube_client
     }
 
-    async fn validate_upload_input(
+    /// Returns the API quota client
+    pub fn quota(&self) -> &QuotaClient {
+        &self.quota_client
+    }
+
+    /// Gets the first article of a draft.
+    async fn draft_article(&self, media_id: &str) -> Result<Article> {
+        let draft = self.get_draft(media_id).await?;
+        draft
+            .content
+            .news_item
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeChatError::Internal {
+                message: format!("Draft {media_id} has no articles"),
+            })
+    }
+
+    /// Fails if `media_id`, the draft about to be updated, was edited remotely since
+    /// the markdown file was last synced with it.
+    ///
+    /// Drafts the file was never synced with have no record to compare against.
+    async fn check_synced_draft(
         &self,
         markdown_path: &Path,
+        media_id: &str,
+        options: &UploadOptions,
+    ) -> Result<()> {
+        if options.force {
+            return Ok(());
+        }
+
+        match SyncRecord::load(markdown_path, self.account_key.as_deref()).await {
+            Some(record) if record.media_id == media_id => self.check_remote_changes(&record).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if the recorded draft was edited remotely since it was last synced.
+    ///
+    /// A draft that cannot be fetched cannot be checked, so that is an error too.
+    async fn check_remote_changes(&self, record: &SyncRecord) -> Result<()> {
+        let article = self.draft_article(&record.media_id).await?;
+        if record.matches(&article) {
+            Ok(())
+        } else {
+            Err(WeChatError::DraftConflict {
+                media_id: record.media_id.clone(),
+            })
+        }
+    }
+
+    /// Records the state of a draft after uploading a markdown file to it.
+    ///
+    /// The draft is read back rather than fingerprinted locally, since WeChat rewrites
+    /// some of the HTML it stores. Failures only lose conflict detection, so they are
+    /// logged instead of failing the upload.
+    async fn record_sync(&self, markdown_path: &Path, media_id: &str) {
+        let saved = match self.draft_article(media_id).await {
+            Ok(article) => {
+                SyncRecord::new(media_id, &article)
+                    .save(markdown_path, self.account_key.as_deref())
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = saved {
+            warn!("Could not record the state of draft {media_id}: {e}");
+        }
+    }
+
+    /// Maps the URLs and media IDs of permanent image materials to diff tokens.
+    ///
+    /// Materials uploaded by this crate are named by content hash. Pages of the material
+    /// list are fetched until every wanted URL and media ID is found.
+    async fn material_image_names(
+        &self,
+        urls: &[String],
+        media_id: Option<&str>,
+    ) -> Result<HashMap<String, String>> {
+        let mut wanted: HashSet<String> = urls
+            .iter()
+            .map(|url| diff::url_key(url).to_string())
+            .chain(media_id.map(str::to_string))
+            .collect();
+        let mut names = HashMap::new();
+        let mut offset = 0;
+
+        while !wanted.is_empty() {
+            let page = self
+                .image_uploader
+                .list_image_materials(offset, MATERIAL_PAGE_SIZE)
+                .await?;
+            if page.item.is_empty() {
+                break;
+            }
+            offset += page.item.len() as u32;
+
+            for item in page.item {
+                let token = diff::material_token(&item.name);
+                for key in [diff::url_key(&item.url).to_string(), item.media_id] {
+                    if wanted.remove(&key) {
+                        names.insert(key, token.clone());
+                    }
+                }
+            }
+            if offset >= page.total_count {
+                break;
+            }
+        }
+
+        Ok(names)
+    }
+
+    async fn pull_article(
+        &self,
+        articles: Vec<Article>,
+        markdown_path: &Path,
+    ) -> Result<PulledArticle> {
+        let count = articles.len();
+        let article = articles
+            .into_iter()
+            .next()
+            .ok_or_else(|| WeChatError::Internal {
+                message: "WeChat returned no articles".to_string(),
+            })?;
+        if count > 1 {
+            warn!("Only the first of {count} articles is pulled");
+        }
+
+        let base_dir = utils::get_base_directory(markdown_path).unwrap_or_else(|| Path::new("."));
+        let images_dir = base_dir.join("images");
+        tokio::fs::create_dir_all(&images_dir).await.map_err(|e| {
+            WeChatError::file_error(
+                images_dir.display().to_string(),
+                format!("Failed to create image directory: {e}"),
+            )
+        })?;
+
+        // Images are named by content hash, so pulling again leaves unchanged files as they are
+        let mut image_paths = HashMap::new();
+        let mut images = Vec::new();
+        let urls = reverse::image_urls(&article.content)
+            .into_iter()
+            .chain(article.thumb_url.clone());
+        for url in urls {
+            if image_paths.contains_key(&url) {
+                continue;
+            }
+
+            let data = self
+                .http_client
+                .download_with_limit(&url, MAX_DOWNLOAD_SIZE)
+                .await?;
+            let file_name = format!(
+                "{}.{}",
+                &blake3::hash(&data).to_hex()[..16],
+                image_extension(&url, &data)
+            );
+            let path = images_dir.join(&file_name);
+            tokio::fs::write(&path, &data).await.map_err(|e| {
+                WeChatError::file_error(
+                    path.display().to_string(),
+                    format!("Failed to write image: {e}"),
+                )
+            })?;
+
+            image_paths.insert(url, format!("images/{file_name}"));
+            if !images.contains(&path) {
+                images.push(path);
+            }
+        }
+
+        let cover = article
+            .thumb_url
+            .as_ref()
+            .and_then(|url| image_paths.get(url))
+            .map(String::as_str);
+        let markdown = format!(
+            "{}\n{}",
+            reverse::frontmatter(&article, cover),
+            reverse::html_to_markdown(&article.content, &image_paths)
+        );
+        tokio::fs::write(markdown_path, markdown)
+            .await
+            .map_err(|e| {
+                WeChatError::file_error(
+                    markdown_path.display().to_string(),
+                    format!("Failed to write markdown: {e}"),
+                )
+            })?;
+
+        info!(
+            "Pulled \"{}\" into {} with {} images",
+            article.title,
+            markdown_path.display(),
+            images.len()
+        );
+        Ok(PulledArticle {
+            title: article.title,
+            markdown_path: markdown_path.to_path_buf(),
+            images,
+        })
+    }
+
+    /// Checks that the markdown file and any cover image exist.
+    ///
+    /// A cover is only required when `require_cover` is set, as drafts need one but
+    /// HTML exports do not.
+    async fn validate_upload_input(
+        parser: &MarkdownParser,
+        markdown_path: &Path,
         options: &UploadOptions,
+        require_cover: bool,
     ) -> Result<()> {
         // Check if markdown file exists
         if !utils::file_exists(markdown_path).await {
@@ -459,13 +1094,13 @@ impl WeChatClient {
         // Theme validation will happen later when we determine the actual theme to use
 
         // Parse markdown to check for frontmatter cover
-        let content = self.parse_markdown_file(markdown_path).await?;
+        let content = parser.parse_file(markdown_path).await?;
 
         // Check that cover image is provided either via options or frontmatter
         let has_cover_option = options.cover_image.is_some();
         let has_cover_frontmatter = content.cover.is_some();
 
-        if !has_cover_option && !has_cover_frontmatter {
+        if require_cover && !has_cover_option && !has_cover_frontmatter {
             return Err(WeChatError::config_error(
                 "Cover image is required. Please provide via --cover-image option or 'cover:' in frontmatter",
             ));
@@ -522,6 +1157,45 @@ impl WeChatClient {
         Ok(())
     }
 
+    async fn build_plan(
+        markdown_path: &str,
+        options: UploadOptions,
+        media_id: Option<&str>,
+    ) -> Result<UploadPlan> {
+        let offline = OfflineArticle::load(Path::new(markdown_path), &options, true, None).await?;
+        let warnings = plan_warnings(
+            &offline.theme_manager,
+            &offline.content,
+            &options,
+            &offline.images,
+        );
+
+        let html_content = offline.render(&options, &ImageUrls::default())?;
+        let article = Self::create_article(&offline.content, &options, html_content, None);
+        article.validate()?;
+
+        let action = match media_id {
+            Some(media_id) => DraftAction::Update {
+                media_id: media_id.to_string(),
+            },
+            None => DraftAction::Upsert {
+                title: article.title.clone(),
+            },
+        };
+
+        Ok(UploadPlan {
+            source: PathBuf::from(markdown_path),
+            theme: offline.theme,
+            article,
+            images: offline.images,
+            cover: offline
+                .cover
+                .expect("Cover image should be available from validation"),
+            action,
+            warnings,
+        })
+    }
+
     async fn parse_markdown_file(&self, path: &Path) -> Result<MarkdownContent> {
         self.markdown_parser.parse_file(path).await
     }
@@ -537,19 +1211,77 @@ impl WeChatClient {
         self.image_uploader.upload_cover_material(&cover_path).await
     }
 
-    fn render_content(
+    /// Uploads the images of an article according to the image policy of the options.
+    async fn upload_article_images(
         &self,
         content: &MarkdownContent,
-        theme: &str,
+        base_dir: &Path,
         options: &UploadOptions,
-    ) -> Result<String> {
+    ) -> Result<ImageUploadReport> {
+        let images = self
+            .image_uploader
+            .upload_images_with_policy(content.images.clone(), base_dir, options.image_policy)
+            .await
+            .context(ErrorFrame::Stage(Stage::Image))?;
+        info!("Completed uploading {} images", images.outcomes.len());
+        Ok(images)
+    }
+
+    /// Loads the header and footer partials selected in frontmatter or options.
+    async fn load_partials(
+        &self,
+        content: &MarkdownContent,
+        options: &UploadOptions,
+        base_dir: &Path,
+    ) -> Result<Partials> {
+        let code_theme = content.code.as_deref().unwrap_or("vscode");
+        let header = select_partial(content.metadata.get("header"), options.header.as_ref());
+        let footer = select_partial(content.metadata.get("footer"), options.footer.as_ref());
+
+        let mut partials = Partials::default();
+        if let Some(path) = header {
+            partials.header = Some(self.render_partial(path, base_dir, code_theme).await?);
+        }
+        if let Some(path) = footer {
+            partials.footer = Some(self.render_partial(path, base_dir, code_theme).await?);
+        }
+        partials.series =
+            series::navigation(content, base_dir, self.account_key.as_deref()).await?;
+
+        Ok(partials)
+    }
+
+    /// Renders a markdown or HTML partial, uploading its local images.
+    async fn render_partial(
+        &self,
+        partial_path: &str,
+        base_dir: &Path,
+        code_theme: &str,
+    ) -> Result<String> {
+        let partial = PartialSource::read(&self.markdown_parser, partial_path, base_dir).await?;
+        let upload_results = self
+            .image_uploader
+            .upload_images(partial.images(), partial.dir())
+            .await?;
+        let url_mapping = self.draft_manager.create_url_mapping(&upload_results);
+
+        partial.render(&self.theme_manager, code_theme, &url_mapping)
+    }
+
+    fn render_content(
+        theme_manager: &ThemeManager,
+        content: &MarkdownContent,
+        theme: &str,
+        options: &UploadOptions,
+        partials: &Partials,
+    ) -> Result<String> {
         let mut metadata = content.metadata.clone();
 
         // Use frontmatter values as defaults, override with options if provided
         if let Some(title) = content.title.as_ref() {
             metadata.insert("title".to_string(), title.clone());
         }
-        if let Some(author) = content.author.as_ref() {
+        if let Some(author) = content.author.as_ref().or(options.default_author.as_ref()) {
             metadata.insert("author".to_string(), author.clone());
         }
 
@@ -561,51 +1293,74 @@ impl WeChatClient {
             metadata.insert("author".to_string(), author.clone());
         }
 
-        self.theme_manager.render(
+        theme_manager.render_with_partials(
             &content.content,
             theme,
             content.code.as_deref().unwrap_or("vscode"),
             &metadata,
+            partials,
         )
     }
 
+    /// Validates the article that would be created, before any images are uploaded.
+    ///
+    /// Images still have their local paths and partials are not rendered yet, so the
+    /// final article is validated again before the draft is saved.
+    fn preflight_check(
+        theme_manager: &ThemeManager,
+        content: &MarkdownContent,
+        theme: &str,
+        options: &UploadOptions,
+    ) -> Result<()> {
+        let html_content =
+            Self::render_content(theme_manager, content, theme, options, &Partials::default())?;
+        Self::create_article(content, options, html_content, None).validate()
+    }
+
     fn create_article(
-        &self,
         content: &MarkdownContent,
         options: &UploadOptions,
         html_content: String,
         cover_media_id: Option<String>,
     ) -> Article {
         // Determine title and author
-        let title = options
-            .title
-            .clone()
-            .or_else(|| content.title.clone())
-            .unwrap_or_else(|| "Untitled".to_string());
+        let title = article_title(content, options);
 
+        // Author is optional in WeChat and limited to 8 characters, so leave it blank
         let author = options
             .author
             .clone()
             .or_else(|| content.author.clone())
-            .unwrap_or_else(|| "Anonymous".to_string());
+            .or_else(|| options.default_author.clone())
+            .unwrap_or_default();
 
         // Use description from frontmatter if available, otherwise generate summary
         let digest = content
             .description
             .clone()
-            .unwrap_or_else(|| content.get_summary(120));
+            .unwrap_or_else(|| content.digest(&DigestOptions::wechat()));
+
+        // Flags set in frontmatter, as written by pulled articles, take precedence
+        let flag = |key: &str, default: bool| frontmatter_flag(content, key).unwrap_or(default);
+        let show_cover = flag("show_cover", options.show_cover);
+        let enable_comments = flag("comments", options.enable_comments);
+        let fans_only_comments = flag("fans_only_comments", options.fans_only_comments);
 
         // Create article
         let mut article = Article::new(title, author, html_content)
             .with_digest(digest)
-            .with_show_cover(options.show_cover)
-            .with_comments(options.enable_comments, options.fans_only_comments);
+            .with_show_cover(show_cover)
+            .with_comments(enable_comments, fans_only_comments);
 
         if let Some(media_id) = cover_media_id {
             article = article.with_cover_image(media_id);
         }
 
-        if let Some(source_url) = &options.source_url {
+        if let Some(source_url) = options
+            .source_url
+            .as_ref()
+            .or(content.metadata.get("source_url"))
+        {
             article = article.with_source_url(source_url.clone());
         }
 
@@ -613,9 +1368,439 @@ impl WeChatClient {
     }
 }
 
+/// A header or footer partial read from disk, before its images are uploaded.
+enum PartialSource {
+    Markdown {
+        content: Box<MarkdownContent>,
+        dir: PathBuf,
+    },
+    Html {
+        html: String,
+        dir: PathBuf,
+    },
+}
+
+impl PartialSource {
+    /// Reads a markdown or HTML partial, resolved relative to `base_dir`.
+    async fn read(parser: &MarkdownParser, partial_path: &str, base_dir: &Path) -> Result<Self> {
+        let path = utils::resolve_path(base_dir, partial_path).map_err(|e| {
+            WeChatError::config_error(format!("Invalid partial path '{partial_path}': {e}"))
+        })?;
+
+        if !utils::file_exists(&path).await {
+            return Err(WeChatError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+
+        let dir = utils::get_base_directory(&path)
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+
+        if utils::is_markdown_file(&path) {
+            let content = Box::new(parser.parse_file(&path).await?);
+            Ok(PartialSource::Markdown { content, dir })
+        } else {
+            let html = tokio::fs::read_to_string(&path).await.map_err(|e| {
+                WeChatError::file_error(
+                    path.display().to_string(),
+                    format!("Failed to read partial: {e}"),
+                )
+            })?;
+            Ok(PartialSource::Html { html, dir })
+        }
+    }
+
+    /// Gets the local images referenced by the partial.
+    fn images(&self) -> Vec<ImageRef> {
+        match self {
+            PartialSource::Markdown { content, .. } => content.images.clone(),
+            PartialSource::Html { html, .. } => local_html_images(html),
+        }
+    }
+
+    /// Gets the directory image paths are relative to.
+    fn dir(&self) -> &Path {
+        match self {
+            PartialSource::Markdown { dir, .. } | PartialSource::Html { dir, .. } => dir,
+        }
+    }
+
+    /// Renders the partial, replacing image paths found in `url_mapping`.
+    fn render(
+        &self,
+        theme_manager: &ThemeManager,
+        code_theme: &str,
+        url_mapping: &HashMap<String, String>,
+    ) -> Result<String> {
+        match self {
+            PartialSource::Markdown { content, .. } => {
+                let mut content = content.as_ref().clone();
+                content.replace_image_urls(url_mapping)?;
+                Ok(theme_manager.render_fragment(&content.content, code_theme))
+            }
+            PartialSource::Html { html, .. } => {
+                let mut html = html.clone();
+                for (original, url) in url_mapping {
+                    html =
+                        html.replace(&format!(r#"src="{original}""#), &format!(r#"src="{url}""#));
+                }
+                Ok(html)
+            }
+        }
+    }
+}
+
+/// An article loaded and checked for rendering without contacting WeChat.
+struct OfflineArticle {
+    theme_manager: ThemeManager,
+    content: MarkdownContent,
+    base_dir: PathBuf,
+    theme: String,
+    header: Option<PartialSource>,
+    footer: Option<PartialSource>,
+    /// Rendered series navigation, if the article belongs to a series
+    series: Option<String>,
+    /// Body and partial images, in document order
+    images: Vec<PlannedImage>,
+    /// Cover image, always present when loaded with `require_cover`
+    cover: Option<PlannedImage>,
+}
+
+/// Replacement URLs for local images, keyed by the path written in each source.
+#[derive(Default)]
+struct ImageUrls {
+    body: HashMap<String, String>,
+    header: HashMap<String, String>,
+    footer: HashMap<String, String>,
+}
+
+impl OfflineArticle {
+    /// Parses the article, renders Mermaid charts, and reads every local image.
+    ///
+    /// Fails without a cover image when `require_cover` is set. The series navigation
+    /// comes from the series record of `account`.
+    async fn load(
+        markdown_path: &Path,
+        options: &UploadOptions,
+        require_cover: bool,
+        account: Option<&str>,
+    ) -> Result<Self> {
+        let parser = MarkdownParser::new();
+        let theme_manager = ThemeManager::new();
+
+        WeChatClient::validate_upload_input(&parser, markdown_path, options, require_cover).await?;
+
+        let mut content = parser.parse_file(markdown_path).await?;
+        let base_dir = utils::get_base_directory(markdown_path).unwrap_or_else(|| Path::new("."));
+        render_mermaid_charts(&mut content, markdown_path, base_dir).await?;
+
+        let theme = content
+            .theme
+            .clone()
+            .unwrap_or_else(|| options.theme.clone());
+        if !theme_manager.has_theme(&theme) {
+            return Err(WeChatError::ThemeNotFound { theme });
+        }
+
+        let mut images = Vec::with_capacity(content.images.len());
+        for image in &content.images {
+            images.push(PlannedImage::inspect(image.clone(), base_dir).await?);
+        }
+
+        let (header, footer) = read_partials(&parser, &content, options, base_dir).await?;
+        for partial in header.iter().chain(footer.iter()) {
+            for image in partial.images() {
+                images.push(PlannedImage::inspect(image, partial.dir()).await?);
+            }
+        }
+
+        let cover = match options.cover_image.as_ref().or(content.cover.as_ref()) {
+            Some(cover_path) => {
+                let cover_ref = ImageRef::new(String::new(), cover_path.clone(), (0, 0));
+                let resolved_cover = if Path::new(cover_path).is_absolute() {
+                    PathBuf::from(cover_path)
+                } else {
+                    base_dir.join(cover_path)
+                };
+                Some(PlannedImage::inspect_path(cover_ref, resolved_cover).await?)
+            }
+            None => None,
+        };
+        let series = series::navigation(&content, base_dir, account).await?;
+
+        Ok(Self {
+            theme_manager,
+            content,
+            base_dir: base_dir.to_path_buf(),
+            theme,
+            header,
+            footer,
+            series,
+            images,
+            cover,
+        })
+    }
+
+    /// Renders the article; images not found in `urls` keep their local paths.
+    fn render(&self, options: &UploadOptions, urls: &ImageUrls) -> Result<String> {
+        let code_theme = self.content.code.as_deref().unwrap_or("vscode");
+        let partials = Partials {
+            header: self
+                .header
+                .as_ref()
+                .map(|partial| partial.render(&self.theme_manager, code_theme, &urls.header))
+                .transpose()?,
+            footer: self
+                .footer
+                .as_ref()
+                .map(|partial| partial.render(&self.theme_manager, code_theme, &urls.footer))
+                .transpose()?,
+            series: self.series.clone(),
+        };
+
+        let mut content = self.content.clone();
+        content.replace_image_urls(&urls.body)?;
+
+        WeChatClient::render_content(
+            &self.theme_manager,
+            &content,
+            &self.theme,
+            options,
+            &partials,
+        )
+    }
+
+    /// Reads every local image into a data URI.
+    async fn embedded_image_urls(&self) -> Result<ImageUrls> {
+        self.local_image_urls(export::data_uri).await
+    }
+
+    /// Replaces every local image with a token naming its content hash, for diffs.
+    async fn hashed_image_urls(&self) -> Result<ImageUrls> {
+        self.local_image_urls(|_, data| diff::image_token(blake3::hash(data).to_hex().as_str()))
+            .await
+    }
+
+    /// Reads every local image and maps it to the URL built by `to_url`.
+    async fn local_image_urls(&self, to_url: fn(&Path, &[u8]) -> String) -> Result<ImageUrls> {
+        Ok(ImageUrls {
+            body: local_image_urls(&self.content.images, &self.base_dir, to_url).await?,
+            header: match &self.header {
+                Some(partial) => local_image_urls(&partial.images(), partial.dir(), to_url).await?,
+                None => HashMap::new(),
+            },
+            footer: match &self.footer {
+                Some(partial) => local_image_urls(&partial.images(), partial.dir(), to_url).await?,
+                None => HashMap::new(),
+            },
+        })
+    }
+}
+
+/// Maps each local image to the URL that `to_url` builds from its path and content.
+async fn local_image_urls(
+    images: &[ImageRef],
+    base_dir: &Path,
+    to_url: fn(&Path, &[u8]) -> String,
+) -> Result<HashMap<String, String>> {
+    let mut urls = HashMap::new();
+    for image in images.iter().filter(|image| image.is_local) {
+        let path = image.resolve_path(base_dir)?;
+        let data = load_local_image(&path).await?;
+        urls.insert(image.original_url.clone(), to_url(&path, &data));
+    }
+    Ok(urls)
+}
+
+/// Collects the non-fatal problems reported by a dry run.
+fn plan_warnings(
+    theme_manager: &ThemeManager,
+    content: &MarkdownContent,
+    options: &UploadOptions,
+    images: &[PlannedImage],
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if options.title.is_none() && content.title.is_none() {
+        warnings.push("No title found; the draft would be titled 'Untitled'".to_string());
+    }
+
+    for image in images.iter().filter(|image| !image.image_ref.is_local) {
+        warnings.push(format!(
+            "Remote image is not checked offline: {}",
+            image.image_ref.original_url
+        ));
+    }
+
+    let code_overflow = match content.metadata.get("code_overflow") {
+        Some(value) => value.parse().unwrap_or_else(|e: WeChatError| {
+            warnings.push(format!("Ignoring frontmatter value: {e}"));
+            CodeOverflow::default()
+        }),
+        None => CodeOverflow::default(),
+    };
+    if code_overflow != CodeOverflow::Wrap {
+        let code_columns = content
+            .metadata
+            .get("code_columns")
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|columns| *columns > 0)
+            .unwrap_or(DEFAULT_CODE_COLUMNS);
+        for line in theme_manager.find_long_code_lines(&content.content, code_columns) {
+            warnings.push(format!(
+                "Code block {} line {} is {} columns wide (budget {code_columns}) and will scroll",
+                line.block_index + 1,
+                line.line_number,
+                line.width
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// Renders Mermaid charts to images, adding them to the content's image list.
+async fn render_mermaid_charts(
+    content: &mut MarkdownContent,
+    markdown_path: &Path,
+    base_dir: &Path,
+) -> Result<()> {
+    let document_slug = MermaidProcessor::extract_slug_from_path(markdown_path);
+    let mermaid_processor = MermaidProcessor::new(base_dir.to_path_buf(), document_slug);
+
+    let (modified_content, mermaid_images) = mermaid_processor
+        .process_mermaid_content_with_source_path(&content.content, base_dir, Some(markdown_path))
+        .await?;
+
+    content.content = modified_content;
+    content.images.extend(mermaid_images);
+    Ok(())
+}
+
+/// Reads the header and footer partials selected in frontmatter or options.
+async fn read_partials(
+    parser: &MarkdownParser,
+    content: &MarkdownContent,
+    options: &UploadOptions,
+    base_dir: &Path,
+) -> Result<(Option<PartialSource>, Option<PartialSource>)> {
+    let header = select_partial(content.metadata.get("header"), options.header.as_ref());
+    let footer = select_partial(content.metadata.get("footer"), options.footer.as_ref());
+
+    let header = match header {
+        Some(path) => Some(PartialSource::read(parser, path, base_dir).await?),
+        None => None,
+    };
+    let footer = match footer {
+        Some(path) => Some(PartialSource::read(parser, path, base_dir).await?),
+        None => None,
+    };
+
+    Ok((header, footer))
+}
+
+/// Records the draft a series part was uploaded as; failures are only logged.
+async fn record_series_draft(
+    content: &MarkdownContent,
+    dir: &Path,
+    account: Option<&str>,
+    title: &str,
+    media_id: &str,
+) {
+    let Ok(Some(info)) = SeriesInfo::from_metadata(&content.metadata) else {
+        return;
+    };
+    let saved = match SeriesRecord::load(dir, account).await {
+        Ok(mut record) => {
+            record.set_draft(&info, title, media_id);
+            record.save(dir, account).await
+        }
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
+        warn!(
+            "Could not record draft {media_id} as part {} of series '{}': {e}",
+            info.part, info.name
+        );
+    }
+}
+
+/// The title of an article, from the upload options or frontmatter.
+fn article_title(content: &MarkdownContent, options: &UploadOptions) -> String {
+    options
+        .title
+        .clone()
+        .or_else(|| content.title.clone())
+        .unwrap_or_else(|| "Untitled".to_string())
+}
+
+/// Refuses to pull into an existing markdown file unless `overwrite` is set.
+async fn check_pull_target(markdown_path: &Path, overwrite: bool) -> Result<()> {
+    if utils::file_exists(markdown_path).await {
+        if !overwrite {
+            return Err(WeChatError::config_error(format!(
+                "{} already exists, pass `overwrite` to replace it",
+                markdown_path.display()
+            )));
+        }
+        warn!(
+            "Overwriting {} with the pulled article",
+            markdown_path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Reads a boolean frontmatter value such as `comments: true`.
+fn frontmatter_flag(content: &MarkdownContent, key: &str) -> Option<bool> {
+    match content.metadata.get(key)?.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Picks the partial path from frontmatter, falling back to the upload options.
+///
+/// A frontmatter value of `none` disables the partial.
+fn select_partial<'a>(
+    frontmatter: Option<&'a String>,
+    option: Option<&'a String>,
+) -> Option<&'a str> {
+    match frontmatter.map(|value| value.trim()) {
+        Some(value) if value.eq_ignore_ascii_case("none") || value.is_empty() => None,
+        Some(value) => Some(value),
+        None => option.map(String::as_str),
+    }
+}
+
+/// Collects local `<img>` sources from an HTML partial.
+fn local_html_images(html: &str) -> Vec<ImageRef> {
+    let mut images: Vec<ImageRef> = Vec::new();
+
+    for caps in IMG_SRC_REGEX.captures_iter(html) {
+        let src = caps.get(1).expect("capture group 1 always exists");
+        let image = ImageRef::new(
+            String::new(),
+            src.as_str().to_string(),
+            (src.start(), src.end()),
+        );
+        let is_duplicate = images
+            .iter()
+            .any(|existing| existing.original_url == image.original_url);
+        if image.is_local && !image.original_url.starts_with("data:") && !is_duplicate {
+            images.push(image);
+        }
+    }
+
+    images
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::MockServer;
 
     #[test]
     fn test_upload_options_builder() {
@@ -625,7 +1810,8 @@ mod tests {
             .cover_image("cover.jpg")
             .show_cover(false)
             .comments(true, true)
-            .source_url("https://example.com");
+            .source_url("https://example.com")
+            .force(true);
 
         assert_eq!(options.theme, "github");
         assert_eq!(options.title, Some("Test Title".to_string()));
@@ -635,6 +1821,7 @@ mod tests {
         assert!(options.enable_comments);
         assert!(options.fans_only_comments);
         assert_eq!(options.source_url, Some("https://example.com".to_string()));
+        assert!(options.force);
     }
 
     #[test]
@@ -700,9 +1887,13 @@ Some article content here.
             .unwrap();
 
         let options = UploadOptions::with_theme("default");
-        let result = client
-            .validate_upload_input(temp_file.path(), &options)
-            .await;
+        let result = WeChatClient::validate_upload_input(
+            &client.markdown_parser,
+            temp_file.path(),
+            &options,
+            true,
+        )
+        .await;
         assert!(result.is_err());
         let error_msg = result.unwrap_err().to_string();
         assert!(error_msg.contains("Cover image is required"));
@@ -723,9 +1914,13 @@ Some article content here.
             .unwrap();
 
         let options2 = UploadOptions::with_theme("default");
-        let result2 = client
-            .validate_upload_input(temp_file2.path(), &options2)
-            .await;
+        let result2 = WeChatClient::validate_upload_input(
+            &client.markdown_parser,
+            temp_file2.path(),
+            &options2,
+            true,
+        )
+        .await;
         // This will fail because the cover file doesn't exist, but it should fail with file not found, not cover required
         assert!(result2.is_err());
         assert!(result2.unwrap_err().to_string().contains("02-cover.png"));
@@ -746,14 +1941,70 @@ Some article content here.
 
         let options3 =
             UploadOptions::with_theme("default").cover_image("../fixtures/images/02-cover.png");
-        let result3 = client
-            .validate_upload_input(temp_file3.path(), &options3)
-            .await;
+        let result3 = WeChatClient::validate_upload_input(
+            &client.markdown_parser,
+            temp_file3.path(),
+            &options3,
+            true,
+        )
+        .await;
         // This will fail because the cover file doesn't exist, but should not be the "cover required" error
         assert!(result3.is_err());
         assert!(result3.unwrap_err().to_string().contains("02-cover.png"));
     }
 
+    #[tokio::test]
+    async fn test_preflight_reports_all_violations() {
+        let client = WeChatClient::new("wx1234567890123456", "12345678901234567890123456789012")
+            .await
+            .unwrap();
+
+        let markdown =
+            "---\ntitle: 一个非常非常长的标题\nauthor: 一位名字特别长的作者\n---\n\n正文内容。\n";
+        let content = MarkdownParser::new().parse(markdown).unwrap();
+
+        let options = UploadOptions::default()
+            .title("标".repeat(65))
+            .source_url("not a url");
+        let error =
+            WeChatClient::preflight_check(&client.theme_manager, &content, "default", &options)
+                .unwrap_err();
+
+        match error {
+            WeChatError::Validation { violations } => {
+                let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+                assert_eq!(fields, ["title", "author", "content_source_url"]);
+            }
+            other => panic!("Expected Validation error, got {other}"),
+        }
+
+        let options = UploadOptions::default().author("作者");
+        assert!(
+            WeChatClient::preflight_check(&client.theme_manager, &content, "default", &options)
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_without_author() {
+        let client = WeChatClient::new("wx1234567890123456", "12345678901234567890123456789012")
+            .await
+            .unwrap();
+
+        let markdown = "---\ntitle: 标题\ncover: cover.png\n---\n\n正文内容。\n";
+        let content = MarkdownParser::new().parse(markdown).unwrap();
+        let options = UploadOptions::default();
+
+        // The check that runs before any image is uploaded accepts the missing author
+        WeChatClient::preflight_check(&client.theme_manager, &content, "default", &options)
+            .unwrap();
+
+        let article =
+            WeChatClient::create_article(&content, &options, "<p>正文内容。</p>".into(), None);
+        assert_eq!(article.author, "");
+        article.validate().unwrap();
+    }
+
     #[tokio::test]
     async fn test_fixture_file_parsing() {
         let client = WeChatClient::new("wx1234567890123456", "12345678901234567890123456789012")
@@ -775,12 +2026,411 @@ Some article content here.
 
         // Verify that validation works with the fixture (should pass because cover exists in frontmatter and file exists)
         let options = UploadOptions::with_theme("default");
-        let result = client
-            .validate_upload_input(std::path::Path::new("fixtures/example.md"), &options)
-            .await;
+        let result = WeChatClient::validate_upload_input(
+            &client.markdown_parser,
+            std::path::Path::new("fixtures/example.md"),
+            &options,
+            true,
+        )
+        .await;
         assert!(
             result.is_ok(),
             "Validation should pass for fixture file with cover in frontmatter"
         );
     }
+
+    #[test]
+    fn test_partial_selection() {
+        let from_options = "partials/footer.md".to_string();
+        let from_frontmatter = "partials/special.html".to_string();
+        let disabled = "none".to_string();
+
+        assert_eq!(
+            select_partial(None, Some(&from_options)),
+            Some("partials/footer.md")
+        );
+        assert_eq!(
+            select_partial(Some(&from_frontmatter), Some(&from_options)),
+            Some("partials/special.html")
+        );
+        assert_eq!(select_partial(Some(&disabled), Some(&from_options)), None);
+        assert_eq!(select_partial(None, None), None);
+    }
+
+    #[test]
+    fn test_article_flags_from_frontmatter() {
+        let markdown = "---\ntitle: Pulled\nsource_url: https://example.com/post\nshow_cover: false\ncomments: true\n---\n\nBody";
+        let content = MarkdownParser::new().parse(markdown).unwrap();
+
+        let article =
+            WeChatClient::create_article(&content, &UploadOptions::default(), String::new(), None);
+        assert_eq!(article.show_cover_pic, 0);
+        assert_eq!(article.need_open_comment, 1);
+        assert_eq!(article.only_fans_can_comment, 0);
+        assert_eq!(
+            article.content_source_url.as_deref(),
+            Some("https://example.com/post")
+        );
+
+        // An explicit source URL option still wins
+        let options = UploadOptions::default().source_url("https://example.com/other");
+        let article = WeChatClient::create_article(&content, &options, String::new(), None);
+        assert_eq!(
+            article.content_source_url.as_deref(),
+            Some("https://example.com/other")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_errors_carry_context() {
+        let client = WeChatClient::with_token_provider(
+            Arc::new(crate::token_provider::StaticTokenProvider::new("token")),
+            Config::default(),
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("article.md");
+        std::fs::write(
+            &path,
+            "---\ntitle: T\ntheme: missing\ncover: cover.png\n---\n\nBody",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("cover.png"), b"png").unwrap();
+        let path = path.to_str().unwrap();
+
+        let error = client.upload(path).await.unwrap_err();
+        assert!(matches!(error.kind(), WeChatError::ThemeNotFound { .. }));
+        assert_eq!(
+            error.frames(),
+            [
+                ErrorFrame::Article(path.to_string()),
+                ErrorFrame::Stage(Stage::Render)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_author() {
+        let parser = MarkdownParser::new();
+        let options = UploadOptions::default().default_author("编辑部");
+
+        let content = parser.parse("---\ntitle: T\n---\n\nBody").unwrap();
+        let article = WeChatClient::create_article(&content, &options, String::new(), None);
+        assert_eq!(article.author, "编辑部");
+
+        // Frontmatter and explicit authors take precedence
+        let content = parser
+            .parse("---\ntitle: T\nauthor: 陈小天\n---\n\nBody")
+            .unwrap();
+        let article = WeChatClient::create_article(&content, &options, String::new(), None);
+        assert_eq!(article.author, "陈小天");
+        let article =
+            WeChatClient::create_article(&content, &options.author("张三"), String::new(), None);
+        assert_eq!(article.author, "张三");
+    }
+
+    #[test]
+    fn test_local_html_images() {
+        let html = r#"<p><img alt="qr" src="qr.png"><img src="https://example.com/a.png"><img class="x" src="qr.png"><img src="data:image/png;base64,AA=="></p>"#;
+        let images = local_html_images(html);
+
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].original_url, "qr.png");
+    }
+
+    #[tokio::test]
+    async fn test_render_markdown_and_html_partials() {
+        let client = WeChatClient::new("wx1234567890123456", "12345678901234567890123456789012")
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("footer.md"), "**Follow us** for more").unwrap();
+        std::fs::write(
+            dir.path().join("header.html"),
+            r#"<p class="intro">Weekly notes</p>"#,
+        )
+        .unwrap();
+
+        let footer = client
+            .render_partial("footer.md", dir.path(), "github")
+            .await
+            .unwrap();
+        assert!(footer.contains("<strong>Follow us</strong>"));
+
+        let header = client
+            .render_partial("header.html", dir.path(), "github")
+            .await
+            .unwrap();
+        assert_eq!(header, r#"<p class="intro">Weekly notes</p>"#);
+
+        let missing = client.render_partial("nope.md", dir.path(), "github").await;
+        assert!(matches!(missing, Err(WeChatError::FileNotFound { .. })));
+
+        let html = client
+            .theme_manager
+            .render_with_partials(
+                "Body",
+                "default",
+                "github",
+                &std::collections::HashMap::new(),
+                &Partials {
+                    header: Some(header),
+                    footer: Some(footer),
+                    series: None,
+                },
+            )
+            .unwrap();
+        let header_pos = html.find("Weekly notes").unwrap();
+        let body_pos = html.find("Body").unwrap();
+        let footer_pos = html.find("Follow us").unwrap();
+        assert!(header_pos < body_pos && body_pos < footer_pos);
+        assert!(html.contains(r#"class="article-footer" style="#));
+    }
+
+    #[tokio::test]
+    async fn test_plan_runs_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cover.png"), b"cover").unwrap();
+        std::fs::write(dir.path().join("photo.png"), b"photo").unwrap();
+        std::fs::write(dir.path().join("same.png"), b"photo").unwrap();
+        std::fs::write(dir.path().join("footer.html"), r#"<img src="qr.png">"#).unwrap();
+        std::fs::write(dir.path().join("qr.png"), b"qr").unwrap();
+        let markdown = format!(
+            "---\ntitle: 计划\ncover: cover.png\nfooter: footer.html\n---\n\n正文。\n\n\
+             ![photo](photo.png)\n\n![same](same.png)\n\n![web](https://example.com/a.png)\n\n\
+             ```rust\nlet x = \"{}\";\n```\n",
+            "x".repeat(60)
+        );
+        let article_path = dir.path().join("article.md");
+        std::fs::write(&article_path, markdown).unwrap();
+        let article_path = article_path.to_str().unwrap();
+
+        let plan = WeChatClient::plan(article_path, UploadOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            plan.action,
+            DraftAction::Upsert {
+                title: "计划".to_string()
+            }
+        );
+        assert_eq!(plan.theme, "default");
+        assert!(plan.html().contains("photo.png"));
+        assert!(plan.html().contains(r#"src="qr.png""#));
+        let urls: Vec<&str> = plan
+            .images
+            .iter()
+            .map(|image| image.image_ref.original_url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "photo.png",
+                "same.png",
+                "https://example.com/a.png",
+                "qr.png"
+            ]
+        );
+        assert_eq!(plan.cover.size, Some(5));
+        // Identical files are uploaded once
+        assert_eq!(plan.total_image_bytes(), 5 + 5 + 2);
+        assert!(
+            plan.warnings
+                .iter()
+                .any(|w| w.contains("https://example.com/a.png"))
+        );
+        assert!(
+            plan.warnings
+                .iter()
+                .any(|w| w.contains("Code block 1 line 1"))
+        );
+
+        let plan = WeChatClient::plan_update("media-1", article_path, UploadOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.action,
+            DraftAction::Update {
+                media_id: "media-1".to_string()
+            }
+        );
+
+        std::fs::remove_file(dir.path().join("photo.png")).unwrap();
+        let missing = WeChatClient::plan(article_path, UploadOptions::default()).await;
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_export_html_embeds_images() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("partials")).unwrap();
+        std::fs::write(dir.path().join("图.png"), b"png").unwrap();
+        std::fs::write(dir.path().join("partials/qr.gif"), b"gif").unwrap();
+        std::fs::write(dir.path().join("partials/footer.md"), "![扫码](qr.gif)").unwrap();
+        let article_path = dir.path().join("article.md");
+        std::fs::write(
+            &article_path,
+            // Exports need no cover image, unlike drafts
+            "---\ntitle: 导出\nfooter: partials/footer.md\n---\n\n\
+             正文\n\n![图](图.png)\n\n![远程](https://example.com/a.png)\n",
+        )
+        .unwrap();
+
+        let export =
+            WeChatClient::export_html(article_path.to_str().unwrap(), UploadOptions::default())
+                .await
+                .unwrap();
+
+        let document = &export.document;
+        assert!(document.starts_with("<!DOCTYPE html>"));
+        assert!(!document.contains("<style"));
+        assert!(document.contains(r#"src="data:image/png;base64,cG5n""#));
+        assert!(document.contains(r#"src="data:image/gif;base64,Z2lm""#));
+        assert!(document.contains(r#"src="https://example.com/a.png""#));
+
+        assert!(export.fragment.starts_with(r#"<section id="wepub" style="#));
+        assert!(export.fragment.ends_with("</section>"));
+        assert!(export.fragment.contains("data:image/png;base64,cG5n"));
+        assert!(!export.fragment.contains("<head>"));
+    }
+
+    fn mock_client(base_url: String) -> WeChatClient {
+        use crate::config::HttpConfig;
+        use crate::token_provider::StaticTokenProvider;
+
+        WeChatClient::with_token_provider(
+            Arc::new(StaticTokenProvider::new("token")),
+            Config::builder()
+                .http(HttpConfig::builder().base_url(base_url).build())
+                .build(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_check_synced_draft() {
+        let dir = tempfile::tempdir().unwrap();
+        let markdown_path = dir.path().join("post.md");
+        let synced = Article::new("T".into(), String::new(), "<p>Synced</p>".into());
+        SyncRecord::new("m1", &synced)
+            .save(&markdown_path, None)
+            .await
+            .unwrap();
+
+        let draft = |content: &str| {
+            let article = serde_json::json!({
+                "title": "T",
+                "author": "",
+                "content": content,
+                "digest": "",
+                "show_cover_pic": 1,
+                "need_open_comment": 0,
+                "only_fans_can_comment": 0,
+            });
+            serde_json::json!({
+                "media_id": "m1",
+                "content": { "news_item": [article] },
+                "update_time": 0,
+            })
+            .to_string()
+        };
+        let server = MockServer::serve([
+            draft("<p>Synced</p>"),
+            draft("<p>Edited in console</p>"),
+            r#"{"errcode":40007,"errmsg":"invalid media_id"}"#.to_string(),
+        ])
+        .await;
+        let client = mock_client(server.url);
+        let options = UploadOptions::default();
+
+        // Drafts the file was never synced with are not fetched
+        client
+            .check_synced_draft(&markdown_path, "m2", &options)
+            .await
+            .unwrap();
+
+        client
+            .check_synced_draft(&markdown_path, "m1", &options)
+            .await
+            .unwrap();
+        let error = client
+            .check_synced_draft(&markdown_path, "m1", &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, WeChatError::DraftConflict { .. }));
+
+        // A draft that cannot be checked stops the upload unless forced
+        client
+            .check_synced_draft(&markdown_path, "m1", &options.clone().force(true))
+            .await
+            .unwrap();
+        let error = client
+            .check_synced_draft(&markdown_path, "m1", &options)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::WeChatApi { code: 40007, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pull_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let markdown_path = dir.path().join("post.md");
+        check_pull_target(&markdown_path, false).await.unwrap();
+
+        std::fs::write(&markdown_path, "# Local edits").unwrap();
+        let error = check_pull_target(&markdown_path, false).await.unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        check_pull_target(&markdown_path, true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hashed_render_matches_uploaded_materials() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("cover.png"), b"cover").unwrap();
+        std::fs::write(dir.path().join("a.png"), b"png").unwrap();
+        let article_path = dir.path().join("article.md");
+        std::fs::write(
+            &article_path,
+            "---\ntitle: 对比\ncover: cover.png\n---\n\n正文\n\n![图](a.png)\n",
+        )
+        .unwrap();
+
+        let options = UploadOptions::default();
+        let offline = OfflineArticle::load(&article_path, &options, true, None)
+            .await
+            .unwrap();
+        let html = offline
+            .render(&options, &offline.hashed_image_urls().await.unwrap())
+            .unwrap();
+
+        // A remote image uploaded from a.png is named after the same hash
+        let hash = blake3::hash(b"png").to_hex().to_string();
+        let remote_url = "https://mmbiz.qpic.cn/x/0";
+        let names = HashMap::from([(
+            remote_url.to_string(),
+            diff::material_token(&format!("{hash}.png")),
+        )]);
+        let local_image = diff::normalize_html(&html, &names)
+            .into_iter()
+            .find(|line| line.starts_with("<img"))
+            .unwrap();
+        let remote_image = diff::normalize_html(
+            &html.replace(
+                &diff::image_token(&hash),
+                &format!("{remote_url}?wx_fmt=png"),
+            ),
+            &names,
+        )
+        .into_iter()
+        .find(|line| line.starts_with("<img"))
+        .unwrap();
+
+        assert!(local_image.contains(&diff::image_token(&hash)));
+        assert_eq!(local_image, remote_image);
+    }
 }
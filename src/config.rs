@@ -39,6 +39,8 @@
 
 use crate::error::{Result, WeChatError};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Main configuration structure for the WeChat SDK.
@@ -54,6 +56,12 @@ pub struct Config {
     pub cache: CacheConfig,
     /// Retry configuration
     pub retry: RetryConfig,
+    /// Access token configuration
+    #[serde(default)]
+    pub auth: AuthConfig,
+    /// Client-side API rate limits
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
 }
 
 /// Security configuration settings.
@@ -108,6 +116,89 @@ pub struct CacheConfig {
     pub cleanup_interval_minutes: u64,
 }
 
+/// Access token configuration settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Endpoint used to obtain access tokens (default: classic)
+    pub token_strategy: TokenStrategy,
+    /// File that shares access tokens between processes (default: none, in memory)
+    #[serde(default)]
+    pub token_file: Option<PathBuf>,
+}
+
+/// How access tokens are obtained from WeChat.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenStrategy {
+    /// `cgi-bin/token`: every call issues a new token and invalidates the previous one
+    #[default]
+    Classic,
+    /// `cgi-bin/stable_token`: returns the current token until it expires, so services
+    /// sharing an AppID do not invalidate each other's tokens
+    Stable,
+}
+
+impl std::str::FromStr for TokenStrategy {
+    type Err = WeChatError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "classic" => Ok(TokenStrategy::Classic),
+            "stable" => Ok(TokenStrategy::Stable),
+            _ => Err(WeChatError::config_error(format!(
+                "Unknown token strategy '{s}', expected 'classic' or 'stable'"
+            ))),
+        }
+    }
+}
+
+/// Client-side rate limiting of WeChat API calls.
+///
+/// Each endpoint in `limits` gets a token bucket. A call waits for its bucket when the
+/// wait is at most `max_wait_secs`, and fails with [`WeChatError::QuotaExceeded`]
+/// otherwise. Endpoints without a limit are not throttled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Whether to throttle API calls (default: true)
+    pub enabled: bool,
+    /// Longest wait for a bucket before failing the call, in seconds (default: 60)
+    pub max_wait_secs: u64,
+    /// Limits by endpoint path, such as `/cgi-bin/draft/add`
+    pub limits: BTreeMap<String, RateLimit>,
+}
+
+/// A number of calls allowed per period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Calls allowed per period, also the burst size
+    pub calls: u32,
+    /// Length of the period in seconds
+    pub period_secs: u64,
+}
+
+impl RateLimit {
+    /// Allows `calls` calls per minute.
+    pub fn per_minute(calls: u32) -> Self {
+        Self {
+            calls,
+            period_secs: 60,
+        }
+    }
+
+    /// Allows `calls` calls per day.
+    pub fn per_day(calls: u32) -> Self {
+        Self {
+            calls,
+            period_secs: 24 * 60 * 60,
+        }
+    }
+
+    /// Gets the period as a Duration.
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.period_secs)
+    }
+}
+
 /// Retry configuration settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetryConfig {
@@ -194,6 +285,36 @@ impl Default for RetryConfig {
     }
 }
 
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        // Daily quotas of a verified official account, from the WeChat interface
+        // permission docs. Check the real values with `QuotaClient::get_quota`.
+        let limits = [
+            ("/cgi-bin/token", RateLimit::per_day(2_000)),
+            ("/cgi-bin/media/uploadimg", RateLimit::per_day(5_000)),
+            ("/cgi-bin/material/add_material", RateLimit::per_day(5_000)),
+            ("/cgi-bin/draft/add", RateLimit::per_day(1_000)),
+            ("/cgi-bin/draft/update", RateLimit::per_day(1_000)),
+            (
+                "/cgi-bin/clear_quota",
+                RateLimit {
+                    calls: 10,
+                    period_secs: 30 * 24 * 60 * 60,
+                },
+            ),
+        ];
+
+        Self {
+            enabled: true,
+            max_wait_secs: 60,
+            limits: limits
+                .into_iter()
+                .map(|(endpoint, limit)| (endpoint.to_string(), limit))
+                .collect(),
+        }
+    }
+}
+
 impl Config {
     /// Creates a new configuration builder.
     pub fn builder() -> ConfigBuilder {
@@ -241,6 +362,22 @@ impl Config {
             config.http.base_url = val;
         }
 
+        // Auth settings
+        if let Ok(val) = std::env::var("WECHAT_TOKEN_STRATEGY") {
+            config.auth.token_strategy = val.parse()?;
+        }
+
+        if let Ok(val) = std::env::var("WECHAT_TOKEN_FILE") {
+            config.auth.token_file = Some(PathBuf::from(val));
+        }
+
+        // Rate limit settings
+        if let Ok(val) = std::env::var("WECHAT_RATE_LIMIT") {
+            config.rate_limit.enabled = val
+                .parse()
+                .map_err(|_| WeChatError::config_error("Invalid WECHAT_RATE_LIMIT value"))?;
+        }
+
         // Retry settings
         if let Ok(val) = std::env::var("WECHAT_MAX_RETRIES") {
             config.retry.max_attempts = val
@@ -308,6 +445,15 @@ impl Config {
             return Err(WeChatError::config_error("backoff_factor must be >= 1.0"));
         }
 
+        // Validate rate limits
+        for (endpoint, limit) in &self.rate_limit.limits {
+            if limit.calls == 0 || limit.period_secs == 0 {
+                return Err(WeChatError::config_error(format!(
+                    "Rate limit for {endpoint} must allow at least one call per non-empty period"
+                )));
+            }
+        }
+
         Ok(())
     }
 
@@ -345,6 +491,8 @@ pub struct ConfigBuilder {
     http: Option<HttpConfig>,
     cache: Option<CacheConfig>,
     retry: Option<RetryConfig>,
+    auth: Option<AuthConfig>,
+    rate_limit: Option<RateLimitConfig>,
 }
 
 impl ConfigBuilder {
@@ -378,6 +526,18 @@ impl ConfigBuilder {
         self
     }
 
+    /// Sets the access token configuration.
+    pub fn auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Sets the rate limit configuration.
+    pub fn rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
     /// Builds the configuration.
     pub fn build(self) -> Config {
         Config {
@@ -386,6 +546,8 @@ impl ConfigBuilder {
             http: self.http.unwrap_or_default(),
             cache: self.cache.unwrap_or_default(),
             retry: self.retry.unwrap_or_default(),
+            auth: self.auth.unwrap_or_default(),
+            rate_limit: self.rate_limit.unwrap_or_default(),
         }
     }
 }
@@ -427,6 +589,20 @@ impl RetryConfig {
     }
 }
 
+impl AuthConfig {
+    /// Creates a new auth config builder.
+    pub fn builder() -> AuthConfigBuilder {
+        AuthConfigBuilder::default()
+    }
+}
+
+impl RateLimitConfig {
+    /// Creates a new rate limit config builder, starting from the default limits.
+    pub fn builder() -> RateLimitConfigBuilder {
+        RateLimitConfigBuilder::default()
+    }
+}
+
 /// Builder for SecurityConfig.
 #[derive(Debug, Default)]
 pub struct SecurityConfigBuilder {
@@ -659,6 +835,71 @@ impl RetryConfigBuilder {
     }
 }
 
+/// Builder for AuthConfig.
+#[derive(Debug, Default)]
+pub struct AuthConfigBuilder {
+    token_strategy: Option<TokenStrategy>,
+    token_file: Option<PathBuf>,
+}
+
+impl AuthConfigBuilder {
+    pub fn token_strategy(mut self, strategy: TokenStrategy) -> Self {
+        self.token_strategy = Some(strategy);
+        self
+    }
+
+    pub fn token_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.token_file = Some(path.into());
+        self
+    }
+
+    pub fn build(self) -> AuthConfig {
+        let default = AuthConfig::default();
+        AuthConfig {
+            token_strategy: self.token_strategy.unwrap_or(default.token_strategy),
+            token_file: self.token_file.or(default.token_file),
+        }
+    }
+}
+
+/// Builder for RateLimitConfig.
+#[derive(Debug, Default)]
+pub struct RateLimitConfigBuilder {
+    enabled: Option<bool>,
+    max_wait_secs: Option<u64>,
+    limits: BTreeMap<String, RateLimit>,
+}
+
+impl RateLimitConfigBuilder {
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = Some(enabled);
+        self
+    }
+
+    pub fn max_wait_secs(mut self, secs: u64) -> Self {
+        self.max_wait_secs = Some(secs);
+        self
+    }
+
+    /// Sets the limit of an endpoint, replacing its default.
+    pub fn limit(mut self, endpoint: impl Into<String>, limit: RateLimit) -> Self {
+        self.limits.insert(endpoint.into(), limit);
+        self
+    }
+
+    pub fn build(self) -> RateLimitConfig {
+        let mut config = RateLimitConfig::default();
+        if let Some(enabled) = self.enabled {
+            config.enabled = enabled;
+        }
+        if let Some(secs) = self.max_wait_secs {
+            config.max_wait_secs = secs;
+        }
+        config.limits.extend(self.limits);
+        config
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -717,6 +958,69 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_token_strategy() {
+        assert_eq!(
+            "Stable".parse::<TokenStrategy>().unwrap(),
+            TokenStrategy::Stable
+        );
+        assert!("oauth".parse::<TokenStrategy>().is_err());
+
+        let config = Config::builder()
+            .auth(
+                AuthConfig::builder()
+                    .token_strategy(TokenStrategy::Stable)
+                    .build(),
+            )
+            .build();
+        assert_eq!(config.auth.token_strategy, TokenStrategy::Stable);
+
+        // Configurations written before the auth section existed still load
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value.as_object_mut().unwrap().remove("auth");
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.auth.token_strategy, TokenStrategy::Classic);
+    }
+
+    #[test]
+    fn test_rate_limit_config() {
+        let config = Config::default();
+        assert!(config.rate_limit.enabled);
+        assert_eq!(
+            config.rate_limit.limits["/cgi-bin/draft/add"],
+            RateLimit::per_day(1_000)
+        );
+
+        let config = Config::builder()
+            .rate_limit(
+                RateLimitConfig::builder()
+                    .max_wait_secs(5)
+                    .limit("/cgi-bin/draft/add", RateLimit::per_minute(10))
+                    .build(),
+            )
+            .build();
+        assert_eq!(config.rate_limit.max_wait_secs, 5);
+        assert_eq!(
+            config.rate_limit.limits["/cgi-bin/draft/add"],
+            RateLimit::per_minute(10)
+        );
+        // Other defaults are kept
+        assert!(config.rate_limit.limits.contains_key("/cgi-bin/token"));
+
+        let mut config = Config::default();
+        config
+            .rate_limit
+            .limits
+            .insert("/cgi-bin/draft/add".to_string(), RateLimit::per_day(0));
+        assert!(config.validate().is_err());
+
+        // Configurations written before the rate_limit section existed still load
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value.as_object_mut().unwrap().remove("rate_limit");
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert!(config.rate_limit.enabled);
+    }
+
     #[test]
     fn test_duration_conversions() {
         let config = Config::default();
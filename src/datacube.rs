@@ -3,9 +3,9 @@
 //! This module provides methods to fetch statistical data about articles
 //! such as reads, shares, detailed statistics, and summary overviews.
 
-use crate::auth::TokenManager;
 use crate::error::Result;
 use crate::http::{WeChatHttpClient, WeChatResponse};
+use crate::traits::TokenProvider;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::debug;
@@ -168,15 +168,15 @@ pub struct ReadJumpPosition {
 #[derive(Debug, Clone)]
 pub struct DatacubeClient {
     http_client: Arc<WeChatHttpClient>,
-    token_manager: Arc<TokenManager>,
+    token_provider: Arc<dyn TokenProvider>,
 }
 
 impl DatacubeClient {
     /// Creates a new DatacubeClient.
-    pub fn new(http_client: Arc<WeChatHttpClient>, token_manager: Arc<TokenManager>) -> Self {
+    pub fn new(http_client: Arc<WeChatHttpClient>, token_provider: Arc<dyn TokenProvider>) -> Self {
         Self {
             http_client,
-            token_manager,
+            token_provider,
         }
     }
 
@@ -197,10 +197,13 @@ impl DatacubeClient {
             end_date: end_date.to_string(),
         };
 
-        let access_token = self.token_manager.get_access_token().await?;
         let res = self
             .http_client
-            .post_json_with_token("/cgi-bin/datacube/getarticleread", &access_token, &req)
+            .post_json_authorized(
+                "/cgi-bin/datacube/getarticleread",
+                self.token_provider.as_ref(),
+                &req,
+            )
             .await?;
 
         let wx_res: WeChatResponse<DatacubeResponse<ArticleReadTotal>> = res.json().await?;
@@ -224,10 +227,13 @@ impl DatacubeClient {
             end_date: end_date.to_string(),
         };
 
-        let access_token = self.token_manager.get_access_token().await?;
         let res = self
             .http_client
-            .post_json_with_token("/cgi-bin/datacube/getarticleshare", &access_token, &req)
+            .post_json_authorized(
+                "/cgi-bin/datacube/getarticleshare",
+                self.token_provider.as_ref(),
+                &req,
+            )
             .await?;
 
         let wx_res: WeChatResponse<DatacubeResponse<ArticleShareTotal>> = res.json().await?;
@@ -251,10 +257,13 @@ impl DatacubeClient {
             end_date: end_date.to_string(),
         };
 
-        let access_token = self.token_manager.get_access_token().await?;
         let res = self
             .http_client
-            .post_json_with_token("/cgi-bin/datacube/getbizsummary", &access_token, &req)
+            .post_json_authorized(
+                "/cgi-bin/datacube/getbizsummary",
+                self.token_provider.as_ref(),
+                &req,
+            )
             .await?;
 
         let wx_res: WeChatResponse<DatacubeResponse<ArticleSummary>> = res.json().await?;
@@ -278,12 +287,11 @@ impl DatacubeClient {
             end_date: end_date.to_string(),
         };
 
-        let access_token = self.token_manager.get_access_token().await?;
         let res = self
             .http_client
-            .post_json_with_token(
+            .post_json_authorized(
                 "/cgi-bin/datacube/getarticletotaldetail",
-                &access_token,
+                self.token_provider.as_ref(),
                 &req,
             )
             .await?;
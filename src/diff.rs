@@ -0,0 +1,602 @@
+//! Differences between a local article and its remote draft.
+//!
+//! [`WeChatClient::diff_draft`](crate::WeChatClient::diff_draft) renders the local
+//! markdown and compares it with the draft stored in WeChat, so edits made in the MP
+//! console are noticed before an upload overwrites them. Both HTML documents are
+//! normalised first: whitespace is collapsed, inline style declarations are sorted, and
+//! images are named by content hash. Uploaded images are named by hash too, so a remote
+//! image and the local file it came from compare equal.
+//!
+//! Uploads keep a [`SyncRecord`] next to the markdown file, holding a fingerprint of the
+//! draft as last uploaded or pulled. When the draft no longer matches it, someone edited
+//! it remotely and the upload stops with [`WeChatError::DraftConflict`] unless
+//! [`UploadOptions::force`](crate::UploadOptions::force) is set.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::WeChatClient;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let client = WeChatClient::new("app_id", "app_secret").await?;
+//! let diff = client.diff_draft("draft_media_id", "article.md").await?;
+//! if !diff.is_empty() {
+//!     print!("{diff}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, WeChatError};
+use crate::reverse;
+use crate::upload::Article;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+static WEPUB_SELECTOR: LazyLock<Selector> = LazyLock::new(|| Selector::parse("#wepub").unwrap());
+static BODY_SELECTOR: LazyLock<Selector> = LazyLock::new(|| Selector::parse("body").unwrap());
+
+/// Unchanged lines shown around each change in a printed diff.
+const CONTEXT_LINES: usize = 2;
+
+/// A line of the normalised article content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Present on both sides
+    Same(String),
+    /// Only in the remote draft
+    Remote(String),
+    /// Only in the local article
+    Local(String),
+}
+
+/// A metadata field whose value differs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Name of the field, as sent to WeChat
+    pub field: &'static str,
+    /// Value in the local article
+    pub local: String,
+    /// Value in the remote draft
+    pub remote: String,
+}
+
+/// Differences between a local article and a remote draft.
+///
+/// Printing it gives a unified-style diff from the remote draft to the local article,
+/// which is what an upload would change.
+#[derive(Debug, Clone)]
+pub struct DraftDiff {
+    /// Media ID of the compared draft
+    pub media_id: String,
+    /// Metadata fields that differ
+    pub fields: Vec<FieldChange>,
+    /// The normalised content, line by line
+    pub content: Vec<DiffLine>,
+}
+
+impl DraftDiff {
+    /// Compares two articles.
+    ///
+    /// `image_names` maps remote image URLs (without query strings) and cover media IDs
+    /// to [`image_token`]s; unmapped images are compared by URL.
+    pub fn new(
+        media_id: &str,
+        local: &Article,
+        remote: &Article,
+        image_names: &HashMap<String, String>,
+    ) -> Self {
+        let local_fields = article_fields(local, image_names);
+        let remote_fields = article_fields(remote, image_names);
+        let fields = local_fields
+            .into_iter()
+            .zip(remote_fields)
+            .filter(|((_, local), (_, remote))| local != remote)
+            .map(|((field, local), (_, remote))| FieldChange {
+                field,
+                local,
+                remote,
+            })
+            .collect();
+
+        let content = diff_lines(
+            &normalize_html(&remote.content, image_names),
+            &normalize_html(&local.content, image_names),
+        );
+
+        Self {
+            media_id: media_id.to_string(),
+            fields,
+            content,
+        }
+    }
+
+    /// Checks whether both sides are the same.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self
+                .content
+                .iter()
+                .all(|line| matches!(line, DiffLine::Same(_)))
+    }
+}
+
+impl fmt::Display for DraftDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- draft {}", self.media_id)?;
+        writeln!(f, "+++ local")?;
+
+        for change in &self.fields {
+            writeln!(f, "@@ {} @@", change.field)?;
+            writeln!(f, "-{}", change.remote)?;
+            writeln!(f, "+{}", change.local)?;
+        }
+
+        // Show changed lines with a little context, like a unified diff
+        let changed: Vec<usize> = self
+            .content
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+            .map(|(index, _)| index)
+            .collect();
+        let visible = |index: usize| {
+            changed
+                .iter()
+                .any(|&c| index + CONTEXT_LINES >= c && index <= c + CONTEXT_LINES)
+        };
+
+        let mut local_line = 0;
+        let mut in_hunk = false;
+        for (index, line) in self.content.iter().enumerate() {
+            if !matches!(line, DiffLine::Remote(_)) {
+                local_line += 1;
+            }
+            if !visible(index) {
+                in_hunk = false;
+                continue;
+            }
+            if !in_hunk {
+                writeln!(f, "@@ content, line {local_line} @@")?;
+                in_hunk = true;
+            }
+            match line {
+                DiffLine::Same(text) => writeln!(f, " {text}")?,
+                DiffLine::Remote(text) => writeln!(f, "-{text}")?,
+                DiffLine::Local(text) => writeln!(f, "+{text}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The token that stands for an image with the given BLAKE3 hash in normalised HTML.
+pub fn image_token(hash: &str) -> String {
+    format!("image:{hash}")
+}
+
+/// The token for a permanent material, which this crate names `{hash}.{extension}`.
+pub fn material_token(name: &str) -> String {
+    image_token(name.split('.').next().unwrap_or(name))
+}
+
+/// Strips the query string and fragment, which WeChat varies between copies of an image URL.
+pub fn url_key(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+/// Normalises article HTML into comparable lines.
+///
+/// Only the `#wepub` article element is kept, or the body when there is none. Block
+/// elements and line breaks start new lines, whitespace is collapsed, attributes and
+/// inline style declarations are sorted, and `data-*` attributes are dropped. Image
+/// sources found in `image_names` are replaced by their tokens.
+pub fn normalize_html(html: &str, image_names: &HashMap<String, String>) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let root = document
+        .select(&WEPUB_SELECTOR)
+        .next()
+        .or_else(|| document.select(&BODY_SELECTOR).next())
+        .unwrap_or_else(|| document.root_element());
+
+    let mut normalizer = Normalizer {
+        image_names,
+        lines: Vec::new(),
+        line: String::new(),
+    };
+    normalizer.children(root);
+    normalizer.break_line();
+    normalizer.lines
+}
+
+struct Normalizer<'a> {
+    image_names: &'a HashMap<String, String>,
+    lines: Vec<String>,
+    line: String,
+}
+
+impl Normalizer<'_> {
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => {
+                    let text = reverse::collapse_whitespace(text);
+                    let text = if self.line.is_empty() || self.line.ends_with(' ') {
+                        text.trim_start()
+                    } else {
+                        &text
+                    };
+                    self.line.push_str(text);
+                }
+                Node::Element(_) => self.element(ElementRef::wrap(child).unwrap()),
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let name = element.value().name();
+        if matches!(name, "script" | "style") {
+            return;
+        }
+
+        let open_tag = self.open_tag(element);
+        let starts_line = reverse::is_block(name) || matches!(name, "li" | "tr" | "figcaption");
+
+        if starts_line {
+            self.break_line();
+        }
+        self.line.push_str(&open_tag);
+        if is_void(name) {
+            if name == "br" {
+                self.break_line();
+            }
+            return;
+        }
+        if starts_line {
+            self.break_line();
+        }
+
+        self.children(element);
+
+        if starts_line {
+            self.break_line();
+        }
+        self.line.push_str(&format!("</{name}>"));
+        if starts_line {
+            self.break_line();
+        }
+    }
+
+    fn open_tag(&self, element: ElementRef) -> String {
+        let name = element.value().name();
+        let mut attributes: Vec<(String, String)> = element
+            .value()
+            .attrs()
+            .filter(|(attr, _)| !attr.starts_with("data-"))
+            .map(|(attr, value)| {
+                let value = match attr {
+                    "style" => normalize_style(value),
+                    _ => value.to_string(),
+                };
+                (attr.to_string(), value)
+            })
+            .filter(|(attr, value)| !(attr == "style" && value.is_empty()))
+            .collect();
+
+        // WeChat lazy-loads images through data-src
+        if name == "img"
+            && let Some(src) = element.attr("data-src").or_else(|| element.attr("src"))
+        {
+            let src = self
+                .image_names
+                .get(url_key(src))
+                .map_or(src, String::as_str);
+            attributes.retain(|(attr, _)| attr != "src");
+            attributes.push(("src".to_string(), src.to_string()));
+        }
+        attributes.sort();
+
+        let mut tag = format!("<{name}");
+        for (attr, value) in attributes {
+            tag.push_str(&format!(" {attr}=\"{}\"", value.replace('"', "&quot;")));
+        }
+        tag.push('>');
+        tag
+    }
+
+    fn break_line(&mut self) {
+        let line = self.line.trim();
+        if !line.is_empty() {
+            self.lines.push(line.to_string());
+        }
+        self.line.clear();
+    }
+}
+
+/// Sorts style declarations and normalises their spacing.
+fn normalize_style(style: &str) -> String {
+    let mut declarations: Vec<String> = style
+        .split(';')
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let property = property.trim().to_lowercase();
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!property.is_empty()).then(|| format!("{property}: {value}"))
+        })
+        .collect();
+    declarations.sort();
+    declarations.dedup();
+    declarations.join("; ")
+}
+
+fn is_void(name: &str) -> bool {
+    matches!(
+        name,
+        "area"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "wbr"
+    )
+}
+
+/// Diffs two sequences of lines through their longest common subsequence.
+fn diff_lines(remote: &[String], local: &[String]) -> Vec<DiffLine> {
+    let prefix = remote
+        .iter()
+        .zip(local)
+        .take_while(|(remote, local)| remote == local)
+        .count();
+    let suffix = remote[prefix..]
+        .iter()
+        .rev()
+        .zip(local[prefix..].iter().rev())
+        .take_while(|(remote, local)| remote == local)
+        .count();
+
+    let a = &remote[prefix..remote.len() - suffix];
+    let b = &local[prefix..local.len() - suffix];
+    let width = b.len() + 1;
+
+    // table[i * width + j] is the LCS length of a[i..] and b[j..]
+    let mut table = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i * width + j] = if a[i] == b[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<DiffLine> = remote[..prefix]
+        .iter()
+        .map(|line| DiffLine::Same(line.clone()))
+        .collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            lines.push(DiffLine::Same(a[i].clone()));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            lines.push(DiffLine::Remote(a[i].clone()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Local(b[j].clone()));
+            j += 1;
+        }
+    }
+    lines.extend(a[i..].iter().map(|line| DiffLine::Remote(line.clone())));
+    lines.extend(b[j..].iter().map(|line| DiffLine::Local(line.clone())));
+    lines.extend(
+        remote[remote.len() - suffix..]
+            .iter()
+            .map(|line| DiffLine::Same(line.clone())),
+    );
+
+    lines
+}
+
+/// The compared metadata fields, with the cover resolved through `image_names`.
+fn article_fields(
+    article: &Article,
+    image_names: &HashMap<String, String>,
+) -> [(&'static str, String); 7] {
+    let cover = article.thumb_media_id.as_deref().unwrap_or_default();
+    [
+        ("title", article.title.clone()),
+        ("author", article.author.clone()),
+        ("digest", article.digest.clone()),
+        (
+            "content_source_url",
+            article.content_source_url.clone().unwrap_or_default(),
+        ),
+        (
+            "thumb_media_id",
+            image_names
+                .get(cover)
+                .map_or(cover, String::as_str)
+                .to_string(),
+        ),
+        ("show_cover_pic", article.show_cover_pic.to_string()),
+        (
+            "comments",
+            format!(
+                "need_open_comment={}, only_fans_can_comment={}",
+                article.need_open_comment, article.only_fans_can_comment
+            ),
+        ),
+    ]
+}
+
+/// Computes a fingerprint of an article's normalised content and metadata.
+pub fn fingerprint(article: &Article) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for (field, value) in article_fields(article, &HashMap::new()) {
+        hasher.update(field.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(value.as_bytes());
+        hasher.update(b"\0");
+    }
+    for line in normalize_html(&article.content, &HashMap::new()) {
+        hasher.update(line.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// The state of a draft when a markdown file was last uploaded to it or pulled from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncRecord {
+    /// Media ID of the draft
+    pub media_id: String,
+    /// [`fingerprint`] of the draft at that time
+    pub fingerprint: String,
+}
+
+impl SyncRecord {
+    /// Records the current state of a draft article.
+    pub fn new(media_id: &str, article: &Article) -> Self {
+        Self {
+            media_id: media_id.to_string(),
+            fingerprint: fingerprint(article),
+        }
+    }
+
+    /// The record file for a markdown file: `.{file name}.wechat.json` next to it.
+    ///
+    /// Records kept per account, such as by an
+    /// [`AccountRegistry`](crate::accounts::AccountRegistry), are named
+    /// `.{file name}.{account}.wechat.json` instead.
+    pub fn path_for(markdown_path: &Path, account: Option<&str>) -> PathBuf {
+        let file_name = markdown_path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let account = account
+            .map(|account| format!(".{account}"))
+            .unwrap_or_default();
+        markdown_path.with_file_name(format!(".{file_name}{account}.wechat.json"))
+    }
+
+    /// Loads the record for a markdown file, if there is a readable one.
+    pub async fn load(markdown_path: &Path, account: Option<&str>) -> Option<Self> {
+        let data = tokio::fs::read(Self::path_for(markdown_path, account))
+            .await
+            .ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Saves the record next to a markdown file.
+    pub async fn save(&self, markdown_path: &Path, account: Option<&str>) -> Result<()> {
+        let path = Self::path_for(markdown_path, account);
+        let data = serde_json::to_vec_pretty(self)?;
+        tokio::fs::write(&path, data).await.map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to write sync record: {e}"),
+            )
+        })
+    }
+
+    /// Checks whether the draft still matches this record.
+    pub fn matches(&self, article: &Article) -> bool {
+        self.fingerprint == fingerprint(article)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(content: &str) -> Article {
+        Article::new("标题".to_string(), String::new(), content.to_string())
+    }
+
+    #[test]
+    fn test_normalize_html() {
+        let local = r#"<!DOCTYPE html><html><head><style>p { color: red }</style></head><body><article id="wepub" style="color: red;font-size: 16px">
+                <p style="margin: 1em;  color: #333;">Hello
+                <strong>world</strong></p><img src="image:abc" alt="a"></article></body></html>"#;
+        let remote = r#"<p style="color:#333;margin:1em" data-pm-slice="1">Hello <strong>world</strong></p><img data-src="https://mmbiz.qpic.cn/x/0?wx_fmt=png" src="" alt="a">"#;
+        let names = HashMap::from([(
+            "https://mmbiz.qpic.cn/x/0".to_string(),
+            "image:abc".to_string(),
+        )]);
+
+        let expected = vec![
+            r#"<p style="color: #333; margin: 1em">"#,
+            "Hello <strong>world</strong>",
+            "</p>",
+            r#"<img alt="a" src="image:abc">"#,
+        ];
+        assert_eq!(normalize_html(local, &names), expected);
+        assert_eq!(normalize_html(remote, &names), expected);
+    }
+
+    #[test]
+    fn test_draft_diff() {
+        let remote = article("<p>one</p><p>two</p><p>edited in console</p><p>four</p>");
+        let mut local = article("<p>one</p><p>two</p><p>three</p><p>four</p>");
+        local.digest = "摘要".to_string();
+
+        let diff = DraftDiff::new("m1", &local, &remote, &HashMap::new());
+        assert!(!diff.is_empty());
+        assert_eq!(diff.fields.len(), 1);
+        assert_eq!(diff.fields[0].field, "digest");
+        assert_eq!(
+            diff.to_string(),
+            "--- draft m1\n+++ local\n@@ digest @@\n-\n+摘要\n\
+             @@ content, line 6 @@\n </p>\n <p>\n-edited in console\n+three\n </p>\n <p>\n"
+        );
+
+        assert!(DraftDiff::new("m1", &remote, &remote, &HashMap::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let markdown_path = dir.path().join("post.md");
+        assert!(SyncRecord::load(&markdown_path, None).await.is_none());
+
+        let draft = article("<p>Hello</p>");
+        let record = SyncRecord::new("m1", &draft);
+        record.save(&markdown_path, None).await.unwrap();
+        assert!(dir.path().join(".post.md.wechat.json").exists());
+
+        // Records of other accounts are kept apart
+        assert!(
+            SyncRecord::load(&markdown_path, Some("wx1234567890abcdef"))
+                .await
+                .is_none()
+        );
+        SyncRecord::new("m2", &draft)
+            .save(&markdown_path, Some("wx1234567890abcdef"))
+            .await
+            .unwrap();
+        assert!(
+            dir.path()
+                .join(".post.md.wx1234567890abcdef.wechat.json")
+                .exists()
+        );
+
+        let loaded = SyncRecord::load(&markdown_path, None).await.unwrap();
+        assert_eq!(loaded, record);
+        assert!(loaded.matches(&article("<p>Hello </p>")));
+        assert!(!loaded.matches(&article("<p>Hello, edited</p>")));
+    }
+}
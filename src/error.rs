@@ -10,7 +10,18 @@
 //! - **File System Errors**: Missing files, read failures (not retryable)
 //! - **Parsing Errors**: Markdown, JSON parsing failures (not retryable)
 //! - **WeChat API Errors**: Server responses with error codes (situational)
+//! - **Quota Errors**: Daily API quotas used up, by WeChat or client-side limits (not retryable)
 //! - **Configuration Errors**: Invalid settings (not retryable)
+//! - **Validation Errors**: Article fields that break WeChat limits (not retryable)
+//!
+//! ## Context
+//!
+//! Failures inside the upload pipeline are wrapped in [`WeChatError::Context`], which adds
+//! [`ErrorFrame`]s naming the article, the pipeline [`Stage`], the image, the endpoint
+//! (with access tokens and AppSecrets redacted) and the attempt. Classification methods such as
+//! [`is_retryable`](WeChatError::is_retryable) look through the context, and
+//! [`kind`](WeChatError::kind) returns the underlying error for matching.
+//! [`report`](WeChatError::report) gives a serialisable [`ErrorReport`] for logs and CI.
 //!
 //! ## Usage
 //!
@@ -36,8 +47,18 @@
 //!         }
 //!     }
 //! }
+//!
+//! fn report_failure(error: &WeChatError) {
+//!     // Match on the failure itself, whatever context it carries
+//!     if let WeChatError::FileNotFound { path } = error.kind() {
+//!         error!("Missing file {path} at stage {:?}", error.stage());
+//!     }
+//!     println!("{}", serde_json::to_string(&error.report()).unwrap());
+//! }
 //! ```
 
+use crate::error_code::{ApiErrorCategory, ApiErrorCode};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Result type alias for WeChat SDK operations.
@@ -85,12 +106,31 @@ pub enum WeChatError {
 
     /// WeChat API errors (retryability depends on error code)
     #[error("WeChat API error [{code}]: {message}")]
-    WeChatApi { code: i32, message: String },
+    WeChatApi {
+        code: i32,
+        kind: ApiErrorCode,
+        message: String,
+    },
+
+    /// API call quota used up (never retried)
+    ///
+    /// `code` is the WeChat errcode (45009) when WeChat refused the call, and `None` when
+    /// a client-side [rate limit](crate::config::RateLimitConfig) stopped it.
+    #[error("API call quota exceeded: {message}")]
+    QuotaExceeded { code: Option<i32>, message: String },
 
     /// Configuration errors (not retryable)
     #[error("Configuration error: {message}")]
     Config { message: String },
 
+    /// Article validation errors, listing every violated rule (not retryable)
+    #[error("Article validation failed: {}", format_violations(violations))]
+    Validation { violations: Vec<FieldViolation> },
+
+    /// Draft edited remotely since the last upload or pull (not retryable)
+    #[error("Draft {media_id} was edited remotely since the last upload")]
+    DraftConflict { media_id: String },
+
     /// JSON serialization/deserialization errors
     #[error("JSON processing failed: {message}")]
     Json { message: String },
@@ -102,6 +142,13 @@ pub enum WeChatError {
     /// Generic errors for wrapping other error types
     #[error("Internal error: {message}")]
     Internal { message: String },
+
+    /// Another error with the context it happened in, outermost frame first
+    #[error("{}: {error}", format_frames(frames))]
+    Context {
+        error: Box<WeChatError>,
+        frames: Vec<ErrorFrame>,
+    },
 }
 
 impl WeChatError {
@@ -111,7 +158,7 @@ impl WeChatError {
     /// Authentication errors are retryable once (token might be expired).
     /// File system, parsing, and configuration errors are not retryable.
     pub fn is_retryable(&self) -> bool {
-        match self {
+        match self.kind() {
             // Network and timeout errors are always retryable
             WeChatError::Network { .. } | WeChatError::Timeout => true,
 
@@ -121,26 +168,41 @@ impl WeChatError {
             // Some image upload errors might be retryable (network issues)
             WeChatError::ImageUpload { .. } => true,
 
-            // WeChat API errors - check specific error codes
-            WeChatError::WeChatApi { code, .. } => match code {
-                // Access token related errors (retryable)
-                40001 | 40014 | 42001 | 42007 => true,
-                // Rate limiting (retryable with delay)
-                45009 | 45011 => true,
-                // Server errors (retryable)
-                -1 | 50001 | 50002 => true,
-                // All other API errors are not retryable
-                _ => false,
-            },
+            // WeChat API errors - as catalogued for the error code
+            WeChatError::WeChatApi { kind, .. } => kind.is_retryable(),
 
             // All other errors are not retryable
             _ => false,
         }
     }
 
+    /// Checks whether WeChat rejected the access token itself.
+    ///
+    /// These errors are resolved by fetching a fresh token and sending the request again,
+    /// rather than by retrying it unchanged.
+    pub fn is_token_rejection(&self) -> bool {
+        self.api_category() == Some(ApiErrorCategory::Token)
+    }
+
+    /// Gets the catalogued error code of a WeChat API error.
+    pub fn api_code(&self) -> Option<ApiErrorCode> {
+        match self.kind() {
+            WeChatError::WeChatApi { kind, .. } => Some(*kind),
+            WeChatError::QuotaExceeded {
+                code: Some(code), ..
+            } => Some(ApiErrorCode::from_code(*code)),
+            _ => None,
+        }
+    }
+
+    /// Gets the category of a WeChat API error.
+    pub fn api_category(&self) -> Option<ApiErrorCategory> {
+        self.api_code().map(ApiErrorCode::category)
+    }
+
     /// Gets the severity level of the error for logging purposes.
     pub fn severity(&self) -> ErrorSeverity {
-        match self {
+        match self.kind() {
             WeChatError::Network { .. }
             | WeChatError::Timeout
             | WeChatError::ImageUpload { .. } => ErrorSeverity::Warning,
@@ -151,27 +213,127 @@ impl WeChatError {
             | WeChatError::FileRead { .. }
             | WeChatError::MarkdownParse { .. }
             | WeChatError::ThemeNotFound { .. }
-            | WeChatError::Config { .. } => ErrorSeverity::Error,
-
-            WeChatError::WeChatApi { code, .. } => match code {
-                // Critical API errors
-                40013 | 48001 => ErrorSeverity::Critical,
-                // Regular API errors
+            | WeChatError::Config { .. }
+            | WeChatError::Validation { .. }
+            | WeChatError::DraftConflict { .. }
+            | WeChatError::QuotaExceeded { .. } => ErrorSeverity::Error,
+
+            WeChatError::WeChatApi { kind, .. } => match kind.category() {
+                // Nothing works until the account setup is fixed
+                ApiErrorCategory::Credentials | ApiErrorCategory::Permission => {
+                    ErrorSeverity::Critical
+                }
                 _ => ErrorSeverity::Error,
             },
 
             WeChatError::ThemeRender { .. }
             | WeChatError::Json { .. }
             | WeChatError::Io { .. }
-            | WeChatError::Internal { .. } => ErrorSeverity::Error,
+            | WeChatError::Internal { .. }
+            | WeChatError::Context { .. } => ErrorSeverity::Error,
         }
     }
 
-    /// Creates a WeChat API error from response data.
+    /// Creates a WeChat API error from response data, looking the code up in the
+    /// [`ApiErrorCode`] catalogue.
+    ///
+    /// A used-up daily quota (45009) becomes [`WeChatError::QuotaExceeded`].
     pub fn from_api_response(code: i32, message: impl Into<String>) -> Self {
-        WeChatError::WeChatApi {
-            code,
-            message: message.into(),
+        match ApiErrorCode::from_code(code) {
+            ApiErrorCode::DailyQuotaExceeded => WeChatError::QuotaExceeded {
+                code: Some(code),
+                message: message.into(),
+            },
+            kind => WeChatError::WeChatApi {
+                code,
+                kind,
+                message: message.into(),
+            },
+        }
+    }
+
+    /// Adds a context frame around this error, outside any frames it already has.
+    pub fn context(self, frame: ErrorFrame) -> Self {
+        match self {
+            WeChatError::Context { error, mut frames } => {
+                frames.insert(0, frame);
+                WeChatError::Context { error, frames }
+            }
+            error => WeChatError::Context {
+                error: Box::new(error),
+                frames: vec![frame],
+            },
+        }
+    }
+
+    /// Gets the underlying error, without its context.
+    pub fn kind(&self) -> &WeChatError {
+        match self {
+            WeChatError::Context { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Takes the underlying error, dropping its context.
+    pub fn into_kind(self) -> WeChatError {
+        match self {
+            WeChatError::Context { error, .. } => *error,
+            error => error,
+        }
+    }
+
+    /// Gets the context frames of this error, outermost first.
+    pub fn frames(&self) -> &[ErrorFrame] {
+        match self {
+            WeChatError::Context { frames, .. } => frames,
+            _ => &[],
+        }
+    }
+
+    /// Gets the pipeline stage this error happened in, if known.
+    pub fn stage(&self) -> Option<Stage> {
+        self.frames().iter().rev().find_map(|frame| match frame {
+            ErrorFrame::Stage(stage) => Some(*stage),
+            _ => None,
+        })
+    }
+
+    /// Gets a short name for the kind of the underlying error, such as `image_upload`.
+    pub fn kind_name(&self) -> &'static str {
+        match self.kind() {
+            WeChatError::Network { .. } => "network",
+            WeChatError::Timeout => "timeout",
+            WeChatError::InvalidToken => "invalid_token",
+            WeChatError::InvalidCredentials => "invalid_credentials",
+            WeChatError::FileNotFound { .. } => "file_not_found",
+            WeChatError::FileRead { .. } => "file_read",
+            WeChatError::MarkdownParse { .. } => "markdown_parse",
+            WeChatError::ImageUpload { .. } => "image_upload",
+            WeChatError::ThemeNotFound { .. } => "theme_not_found",
+            WeChatError::ThemeRender { .. } => "theme_render",
+            WeChatError::WeChatApi { .. } => "wechat_api",
+            WeChatError::Config { .. } => "config",
+            WeChatError::Validation { .. } => "validation",
+            WeChatError::DraftConflict { .. } => "draft_conflict",
+            WeChatError::QuotaExceeded { .. } => "quota_exceeded",
+            WeChatError::Json { .. } => "json",
+            WeChatError::Io { .. } => "io",
+            WeChatError::Internal { .. } | WeChatError::Context { .. } => "internal",
+        }
+    }
+
+    /// Creates a serialisable summary of this error for logs and CI output.
+    pub fn report(&self) -> ErrorReport {
+        let api_code = self.api_code();
+        ErrorReport {
+            kind: self.kind_name().to_string(),
+            message: self.kind().to_string(),
+            code: api_code.map(ApiErrorCode::code),
+            category: api_code.map(ApiErrorCode::category),
+            severity: self.severity(),
+            retryable: self.is_retryable(),
+            suggestion: self.recovery_suggestion().map(str::to_string),
+            frames: self.frames().to_vec(),
         }
     }
 
@@ -194,7 +356,7 @@ impl WeChatError {
     pub fn retry_delay(&self) -> std::time::Duration {
         use std::time::Duration;
 
-        match self {
+        match self.kind() {
             // Network errors - exponential backoff starting from 1s
             WeChatError::Network { .. } | WeChatError::Timeout => Duration::from_secs(1),
 
@@ -205,13 +367,13 @@ impl WeChatError {
             WeChatError::ImageUpload { .. } => Duration::from_millis(500),
 
             // WeChat API errors - depends on error code
-            WeChatError::WeChatApi { code, .. } => match code {
+            WeChatError::WeChatApi { kind, .. } => match kind.category() {
                 // Rate limiting - longer delay
-                45009 | 45011 => Duration::from_secs(10),
+                ApiErrorCategory::Quota => Duration::from_secs(10),
                 // Server errors - moderate delay
-                -1 | 50001 | 50002 => Duration::from_secs(2),
+                ApiErrorCategory::Server => Duration::from_secs(2),
                 // Token errors - quick retry
-                40001 | 40014 | 42001 | 42007 => Duration::from_millis(200),
+                ApiErrorCategory::Token => Duration::from_millis(200),
                 // Default delay
                 _ => Duration::from_secs(1),
             },
@@ -223,7 +385,7 @@ impl WeChatError {
 
     /// Gets the maximum number of retry attempts for this error type.
     pub fn max_retries(&self) -> u32 {
-        match self {
+        match self.kind() {
             // Network errors - many retries
             WeChatError::Network { .. } | WeChatError::Timeout => 5,
 
@@ -234,15 +396,11 @@ impl WeChatError {
             WeChatError::ImageUpload { .. } => 3,
 
             // WeChat API errors - depends on error code
-            WeChatError::WeChatApi { code, .. } => match code {
-                // Rate limiting - more retries with longer delays
-                45009 | 45011 => 10,
-                // Server errors - moderate retries
-                -1 | 50001 | 50002 => 3,
+            WeChatError::WeChatApi { kind, .. } if kind.is_retryable() => match kind.category() {
                 // Token errors - few retries
-                40001 | 40014 | 42001 | 42007 => 2,
-                // Other API errors - no retries
-                _ => 0,
+                ApiErrorCategory::Token => 2,
+                // Server errors and rate limiting - moderate retries
+                _ => 3,
             },
 
             // Non-retryable errors
@@ -252,44 +410,187 @@ impl WeChatError {
 
     /// Determines if this error indicates a temporary service issue.
     pub fn is_temporary(&self) -> bool {
-        match self {
+        match self.kind() {
             WeChatError::Network { .. } | WeChatError::Timeout => true,
-            WeChatError::WeChatApi { code, .. } => match code {
-                // Server errors are typically temporary
-                -1 | 50001 | 50002 => true,
-                // Rate limiting is temporary
-                45009 | 45011 => true,
-                // Other errors are typically permanent
-                _ => false,
-            },
+            // Server errors and rate limiting are temporary, others typically permanent
+            WeChatError::WeChatApi { kind, .. } => matches!(
+                kind.category(),
+                ApiErrorCategory::Server | ApiErrorCategory::Quota
+            ),
             _ => false,
         }
     }
 
     /// Gets recovery suggestions for this error.
     pub fn recovery_suggestion(&self) -> Option<&'static str> {
-        match self {
+        match self.kind() {
             WeChatError::InvalidToken => Some("Try refreshing the access token"),
             WeChatError::InvalidCredentials => Some("Check your app_id and app_secret"),
             WeChatError::FileNotFound { .. } => Some("Check if the file path is correct"),
             WeChatError::ImageUpload { .. } => Some("Check file size and format"),
             WeChatError::ThemeNotFound { .. } => Some("Use a valid theme name or 'default'"),
-            WeChatError::WeChatApi { code, .. } => match code {
-                40001 => Some("Access token expired, refresh and retry"),
-                40003 => Some("Check your openid parameter"),
-                45009 => Some("Rate limit exceeded, wait and retry"),
-                48001 => Some("API unauthorized, check permissions"),
-                _ => Some("Check WeChat API documentation for error code"),
-            },
+            WeChatError::Validation { .. } => {
+                Some("Fix the listed fields in frontmatter or upload options")
+            }
+            WeChatError::DraftConflict { .. } => {
+                Some("Review the changes with diff_draft, pull them, or upload with force")
+            }
+            WeChatError::QuotaExceeded { .. } => Some(
+                "Wait until the quota resets at midnight, or reset it with QuotaClient::clear_quota",
+            ),
+            WeChatError::WeChatApi { kind, .. } => Some(kind.remediation()),
             _ => None,
         }
     }
 }
 
+/// A single rule violated by an article field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldViolation {
+    /// Name of the offending field, as sent to WeChat
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl FieldViolation {
+    /// Creates a new field violation.
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// A step of the upload pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Reading and parsing the markdown file
+    Parse,
+    /// Rendering Mermaid charts to images
+    Mermaid,
+    /// Uploading the images of the article
+    Image,
+    /// Uploading the cover image
+    Cover,
+    /// Rendering the article and its partials to HTML
+    Render,
+    /// Creating or updating the draft
+    Draft,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Parse => "parse",
+            Stage::Mermaid => "mermaid",
+            Stage::Image => "image",
+            Stage::Cover => "cover",
+            Stage::Render => "render",
+            Stage::Draft => "draft",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Context describing where an error happened.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorFrame {
+    /// Path of the markdown file being processed
+    Article(String),
+    /// Step of the upload pipeline
+    Stage(Stage),
+    /// Image as referenced by the article
+    Image(String),
+    /// Request URL, with access tokens and AppSecrets redacted, or the API path when
+    /// WeChat answered with an errcode
+    Endpoint(String),
+    /// Attempt number of the failed request
+    Attempt(u32),
+}
+
+impl fmt::Display for ErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorFrame::Article(path) => write!(f, "article {path}"),
+            ErrorFrame::Stage(stage) => write!(f, "stage {stage}"),
+            ErrorFrame::Image(url) => write!(f, "image {url}"),
+            ErrorFrame::Endpoint(url) => write!(f, "endpoint {url}"),
+            ErrorFrame::Attempt(attempt) => write!(f, "attempt {attempt}"),
+        }
+    }
+}
+
+fn format_frames(frames: &[ErrorFrame]) -> String {
+    frames
+        .iter()
+        .map(|frame| frame.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Adds context frames to the error of a result.
+pub trait ErrorContext<T> {
+    /// Adds a frame to the error, if any.
+    fn context(self, frame: ErrorFrame) -> Result<T>;
+
+    /// Adds a frame built only when there is an error.
+    fn with_context<F: FnOnce() -> ErrorFrame>(self, frame: F) -> Result<T>;
+}
+
+impl<T> ErrorContext<T> for Result<T> {
+    fn context(self, frame: ErrorFrame) -> Result<T> {
+        self.map_err(|error| error.context(frame))
+    }
+
+    fn with_context<F: FnOnce() -> ErrorFrame>(self, frame: F) -> Result<T> {
+        self.map_err(|error| error.context(frame()))
+    }
+}
+
+/// Serialisable summary of an error, from [`WeChatError::report`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    /// Kind of the underlying error, see [`WeChatError::kind_name`]
+    pub kind: String,
+    /// Message of the underlying error
+    pub message: String,
+    /// WeChat errcode, for API errors
+    pub code: Option<i32>,
+    /// Category of the WeChat errcode, for API errors
+    pub category: Option<ApiErrorCategory>,
+    /// Severity of the error
+    pub severity: ErrorSeverity,
+    /// Whether retrying may succeed
+    pub retryable: bool,
+    /// How to recover, if known
+    pub suggestion: Option<String>,
+    /// Context frames, outermost first
+    pub frames: Vec<ErrorFrame>,
+}
+
+fn format_violations(violations: &[FieldViolation]) -> String {
+    violations
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl From<reqwest::Error> for WeChatError {
+    /// Request errors name the URL, so access tokens and AppSecrets in it are redacted.
     fn from(error: reqwest::Error) -> Self {
         WeChatError::Network {
-            message: error.to_string(),
+            message: crate::utils::redact_secrets(&error.to_string()),
         }
     }
 }
@@ -319,7 +620,8 @@ impl From<anyhow::Error> for WeChatError {
 }
 
 /// Error severity levels for logging and monitoring.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ErrorSeverity {
     /// Low impact errors that don't affect core functionality
     Warning,
@@ -358,6 +660,8 @@ mod tests {
         // Token errors should be retryable
         let token_err = WeChatError::from_api_response(40001, "invalid credential");
         assert!(token_err.is_retryable());
+        assert!(token_err.is_token_rejection());
+        assert!(!WeChatError::from_api_response(45009, "quota").is_token_rejection());
 
         // Invalid parameter errors should not be retryable
         let param_err = WeChatError::from_api_response(40003, "invalid openid");
@@ -374,6 +678,17 @@ mod tests {
 
         let critical_api_err = WeChatError::from_api_response(40013, "invalid appid");
         assert_eq!(critical_api_err.severity(), ErrorSeverity::Critical);
+
+        let whitelist_err = WeChatError::from_api_response(40164, "invalid ip");
+        assert_eq!(whitelist_err.severity(), ErrorSeverity::Critical);
+        assert_eq!(
+            whitelist_err.api_code(),
+            Some(ApiErrorCode::IpNotWhitelisted)
+        );
+        assert_eq!(
+            whitelist_err.api_category(),
+            Some(ApiErrorCategory::Credentials)
+        );
     }
 
     #[test]
@@ -405,7 +720,7 @@ mod tests {
         assert_eq!(network_err.retry_delay(), std::time::Duration::from_secs(1));
 
         // Rate limiting should have longer delay
-        let rate_limit_err = WeChatError::from_api_response(45009, "rate limit exceeded");
+        let rate_limit_err = WeChatError::from_api_response(45011, "api freq out of limit");
         assert_eq!(
             rate_limit_err.retry_delay(),
             std::time::Duration::from_secs(10)
@@ -427,9 +742,14 @@ mod tests {
         };
         assert_eq!(network_err.max_retries(), 5);
 
-        // Rate limiting should have more retries
-        let rate_limit_err = WeChatError::from_api_response(45009, "rate limit exceeded");
-        assert_eq!(rate_limit_err.max_retries(), 10);
+        // Rate limiting should have moderate retries
+        let rate_limit_err = WeChatError::from_api_response(45011, "api freq out of limit");
+        assert_eq!(rate_limit_err.max_retries(), 3);
+
+        // A used-up daily quota is never retried
+        let quota_err = WeChatError::from_api_response(45009, "reach max api daily quota limit");
+        assert!(!quota_err.is_retryable());
+        assert_eq!(quota_err.max_retries(), 0);
 
         // Non-retryable errors should have no retries
         let config_err = WeChatError::config_error("invalid config");
@@ -445,14 +765,66 @@ mod tests {
         assert!(network_err.is_temporary());
 
         // Server errors are temporary
-        let server_err = WeChatError::from_api_response(50001, "server error");
+        let server_err = WeChatError::from_api_response(-1, "system error");
         assert!(server_err.is_temporary());
 
+        // 50001 means the API isn't authorised, which waiting won't fix
+        let unauthorized_err = WeChatError::from_api_response(50001, "api unauthorized");
+        assert!(!unauthorized_err.is_temporary());
+
         // Configuration errors are not temporary
         let config_err = WeChatError::config_error("invalid config");
         assert!(!config_err.is_temporary());
     }
 
+    #[test]
+    fn test_error_context() {
+        let error = WeChatError::from_api_response(45009, "reach max api daily quota limit")
+            .context(ErrorFrame::Attempt(3))
+            .context(ErrorFrame::Endpoint(
+                "https://api.weixin.qq.com/cgi-bin/draft/add?access_token=***".to_string(),
+            ))
+            .context(ErrorFrame::Stage(Stage::Draft))
+            .context(ErrorFrame::Article("posts/hello.md".to_string()));
+
+        assert_eq!(
+            error.to_string(),
+            "article posts/hello.md, stage draft, endpoint https://api.weixin.qq.com/cgi-bin/draft/add?access_token=***, attempt 3: API call quota exceeded: reach max api daily quota limit"
+        );
+        assert_eq!(error.frames().len(), 4);
+        assert_eq!(error.stage(), Some(Stage::Draft));
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded {
+                code: Some(45009),
+                ..
+            }
+        ));
+        // Classification looks through the context
+        assert!(!error.is_retryable());
+        assert_eq!(error.api_code(), Some(ApiErrorCode::DailyQuotaExceeded));
+
+        let report = serde_json::to_value(error.report()).unwrap();
+        assert_eq!(report["kind"], "quota_exceeded");
+        assert_eq!(report["code"], 45009);
+        assert_eq!(report["category"], "quota");
+        assert_eq!(report["severity"], "error");
+        assert_eq!(report["frames"][0]["article"], "posts/hello.md");
+        assert_eq!(report["frames"][1]["stage"], "draft");
+        assert_eq!(report["frames"][3]["attempt"], 3);
+
+        let plain = WeChatError::Timeout;
+        assert!(plain.frames().is_empty());
+        assert_eq!(plain.report().kind, "timeout");
+        assert!(matches!(
+            Err::<(), _>(plain)
+                .context(ErrorFrame::Stage(Stage::Cover))
+                .unwrap_err()
+                .into_kind(),
+            WeChatError::Timeout
+        ));
+    }
+
     #[test]
     fn test_recovery_suggestion() {
         // Token errors should suggest refresh
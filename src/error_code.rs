@@ -0,0 +1,405 @@
+//! Catalogue of documented WeChat API error codes.
+//!
+//! WeChat reports failures as an `errcode` and a terse `errmsg`. [`ApiErrorCode`] maps the
+//! documented codes of the token, material, draft, publish, datacube and comment APIs to
+//! a variant carrying its [`ApiErrorCategory`], whether retrying can help, Chinese and
+//! English descriptions and a remediation hint. Codes outside the catalogue become
+//! [`ApiErrorCode::Unknown`].
+//!
+//! [`WeChatError::from_api_response`](crate::WeChatError::from_api_response) looks codes
+//! up here, so API errors can be matched by kind:
+//!
+//! ```rust
+//! use wechat_pub_rs::error_code::{ApiErrorCategory, ApiErrorCode};
+//! use wechat_pub_rs::WeChatError;
+//!
+//! let error = WeChatError::from_api_response(40164, "invalid ip 1.2.3.4");
+//! if let WeChatError::WeChatApi { kind: ApiErrorCode::IpNotWhitelisted, .. } = error {
+//!     println!("{}", ApiErrorCode::IpNotWhitelisted.remediation());
+//! }
+//! assert_eq!(ApiErrorCode::from_code(45009).category(), ApiErrorCategory::Quota);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Broad kinds of WeChat API errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCategory {
+    /// WeChat is busy or failed internally
+    Server,
+    /// The access token is invalid, expired or was replaced
+    Token,
+    /// The AppID, AppSecret or calling IP address was rejected
+    Credentials,
+    /// The account or user lacks permission for the API
+    Permission,
+    /// A call frequency limit or daily quota was reached
+    Quota,
+    /// A request parameter is missing or malformed
+    Parameter,
+    /// A media file or material is invalid, too large or missing
+    Media,
+    /// Article or comment content was rejected
+    Content,
+    /// Publishing a draft was refused
+    Publish,
+    /// The code is not in the catalogue
+    Unknown,
+}
+
+impl fmt::Display for ApiErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ApiErrorCategory::Server => "server",
+            ApiErrorCategory::Token => "token",
+            ApiErrorCategory::Credentials => "credentials",
+            ApiErrorCategory::Permission => "permission",
+            ApiErrorCategory::Quota => "quota",
+            ApiErrorCategory::Parameter => "parameter",
+            ApiErrorCategory::Media => "media",
+            ApiErrorCategory::Content => "content",
+            ApiErrorCategory::Publish => "publish",
+            ApiErrorCategory::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Generates [`ApiErrorCode`] and its lookups from the catalogue table.
+macro_rules! api_error_codes {
+    ($(
+        $variant:ident = $code:literal, $category:ident, $retryable:literal,
+        $zh:literal, $en:literal, $remediation:literal;
+    )*) => {
+        /// A documented WeChat API error code.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum ApiErrorCode {
+            $(
+                #[doc = concat!("`", stringify!($code), "`: ", $en)]
+                $variant,
+            )*
+            /// A code that is not in the catalogue
+            Unknown(i32),
+        }
+
+        impl ApiErrorCode {
+            /// Every catalogued code, in catalogue order.
+            pub const ALL: &'static [ApiErrorCode] = &[$(ApiErrorCode::$variant),*];
+
+            /// Looks up an `errcode` returned by WeChat.
+            pub fn from_code(code: i32) -> Self {
+                match code {
+                    $($code => ApiErrorCode::$variant,)*
+                    other => ApiErrorCode::Unknown(other),
+                }
+            }
+
+            /// The numeric `errcode`.
+            pub fn code(self) -> i32 {
+                match self {
+                    $(ApiErrorCode::$variant => $code,)*
+                    ApiErrorCode::Unknown(code) => code,
+                }
+            }
+
+            /// The kind of failure.
+            pub fn category(self) -> ApiErrorCategory {
+                match self {
+                    $(ApiErrorCode::$variant => ApiErrorCategory::$category,)*
+                    ApiErrorCode::Unknown(_) => ApiErrorCategory::Unknown,
+                }
+            }
+
+            /// Whether sending the request again, possibly with a new token, can succeed.
+            pub fn is_retryable(self) -> bool {
+                match self {
+                    $(ApiErrorCode::$variant => $retryable,)*
+                    ApiErrorCode::Unknown(_) => false,
+                }
+            }
+
+            /// Description in Chinese, as in the WeChat documentation.
+            pub fn description_zh(self) -> &'static str {
+                match self {
+                    $(ApiErrorCode::$variant => $zh,)*
+                    ApiErrorCode::Unknown(_) => "未收录的错误码",
+                }
+            }
+
+            /// Description in English.
+            pub fn description_en(self) -> &'static str {
+                match self {
+                    $(ApiErrorCode::$variant => $en,)*
+                    ApiErrorCode::Unknown(_) => "Error code not in the catalogue",
+                }
+            }
+
+            /// What to do about the error.
+            pub fn remediation(self) -> &'static str {
+                match self {
+                    $(ApiErrorCode::$variant => $remediation,)*
+                    ApiErrorCode::Unknown(_) => "Check WeChat API documentation for error code",
+                }
+            }
+        }
+    };
+}
+
+api_error_codes! {
+    // Server
+    SystemBusy = -1, Server, true,
+        "系统繁忙，此时请开发者稍候再试", "System busy",
+        "Wait a moment and retry";
+
+    // Access tokens
+    InvalidCredential = 40001, Token, true,
+        "获取 access_token 时 AppSecret 错误，或者 access_token 无效",
+        "Wrong AppSecret or invalid access token",
+        "Access token expired, refresh and retry";
+    InvalidAccessToken = 40014, Token, true,
+        "不合法的 access_token", "Invalid access token",
+        "Refresh the access token and retry";
+    AccessTokenExpired = 42001, Token, true,
+        "access_token 超时", "Access token expired",
+        "Refresh the access token and retry";
+    AccessTokenRevoked = 42007, Token, true,
+        "用户修改微信密码，access_token 和 refresh_token 失效",
+        "Access token revoked after a password change",
+        "Refresh the access token and retry";
+
+    // Credentials and IP whitelist
+    InvalidAppId = 40013, Credentials, false,
+        "不合法的 AppID", "Invalid AppID",
+        "Check that app_id is the AppID of this official account";
+    InvalidAppSecret = 40125, Credentials, false,
+        "无效的 AppSecret", "Invalid AppSecret",
+        "Check app_secret, or reset it in the official account console";
+    IpNotWhitelisted = 40164, Credentials, false,
+        "调用接口的 IP 地址不在白名单中", "Calling IP address is not whitelisted",
+        "Add this server's public IP to the IP whitelist under 设置与开发 > 基本配置";
+    AppSecretFrozen = 40243, Credentials, false,
+        "AppSecret 已被冻结", "AppSecret is frozen",
+        "Unfreeze the AppSecret in the official account console";
+    IpAwaitingConfirmation = 89501, Credentials, false,
+        "此 IP 正在等待管理员确认", "IP address awaits administrator confirmation",
+        "Ask an administrator to confirm the IP address in WeChat";
+    IpRequiresConfirmation = 89503, Credentials, false,
+        "此 IP 调用需要管理员确认", "IP address needs administrator confirmation",
+        "Ask an administrator to confirm the IP address, or add it to the IP whitelist";
+
+    // Permissions
+    ApiUnauthorized = 48001, Permission, false,
+        "api 功能未授权", "API not authorised for this account",
+        "API unauthorized, check permissions and account verification";
+    ApiBanned = 48004, Permission, false,
+        "api 接口被封禁", "API banned for this account",
+        "Check the notice in the official account console for the reason and appeal";
+    UserUnauthorized = 50001, Permission, false,
+        "用户未授权该 api", "API not authorised by the user",
+        "Check that the account has been granted this API";
+    UserRestricted = 50002, Permission, false,
+        "用户受限，可能是违规后接口被封禁", "User restricted after a violation",
+        "Check the notice in the official account console for the restriction";
+
+    // Quotas
+    DailyQuotaExceeded = 45009, Quota, false,
+        "接口调用超过限制", "Daily API call quota reached",
+        "Wait until the quota resets at midnight, or reset it with QuotaClient::clear_quota";
+    TooFrequent = 45011, Quota, true,
+        "API 调用太频繁，请稍候再试", "API called too frequently",
+        "Slow down and retry after a short wait";
+
+    // Parameters
+    InvalidOpenId = 40003, Parameter, false,
+        "不合法的 OpenID", "Invalid OpenID",
+        "Check your openid parameter";
+    InvalidGrantType = 40002, Parameter, false,
+        "不合法的凭证类型", "Invalid grant type",
+        "Use grant_type=client_credential";
+    InvalidParameter = 40035, Parameter, false,
+        "不合法的参数", "Invalid parameter",
+        "Check the request parameters against the API documentation";
+    InvalidArgument = 40097, Parameter, false,
+        "参数错误", "Invalid argument",
+        "Check the request parameters against the API documentation";
+    MissingAccessToken = 41001, Parameter, false,
+        "缺少 access_token 参数", "Missing access_token parameter",
+        "Send the access token with the request";
+    MissingAppId = 41002, Parameter, false,
+        "缺少 appid 参数", "Missing appid parameter",
+        "Set app_id";
+    MissingAppSecret = 41004, Parameter, false,
+        "缺少 secret 参数", "Missing secret parameter",
+        "Set app_secret";
+    RequiresPost = 43002, Parameter, false,
+        "需要 POST 请求", "POST request required",
+        "Send the request with POST";
+    EmptyPostData = 44002, Parameter, false,
+        "POST 的数据包为空", "Empty POST body",
+        "Send a request body";
+    InvalidJson = 47001, Parameter, false,
+        "解析 JSON/XML 内容错误", "Malformed JSON or XML body",
+        "Check the request body is valid JSON with the documented fields";
+    InvalidDateFormat = 61500, Parameter, false,
+        "日期格式错误", "Invalid date format",
+        "Use dates in YYYY-MM-DD format";
+    InvalidDateRange = 61501, Parameter, false,
+        "日期范围错误", "Invalid date range",
+        "Keep the range within the span the datacube endpoint allows, ending yesterday at the latest";
+
+    // Media and materials
+    InvalidMediaType = 40004, Media, false,
+        "不合法的媒体文件类型", "Invalid media type",
+        "Check the type parameter of the material upload";
+    InvalidFileType = 40005, Media, false,
+        "不合法的文件类型", "Invalid file type",
+        "Upload images as JPG or PNG, and other media in a supported format";
+    InvalidFileSize = 40006, Media, false,
+        "不合法的文件大小", "Invalid file size",
+        "Check the file is not empty and within the size limit";
+    InvalidMediaId = 40007, Media, false,
+        "不合法的媒体文件 id", "Invalid media ID",
+        "Check the media ID belongs to a permanent material of this account";
+    InvalidImageSize = 40009, Media, false,
+        "不合法的图片文件大小", "Invalid image size",
+        "Keep images under the size limit (1MB for article images, 10MB for materials)";
+    UnsupportedFileType = 40113, Media, false,
+        "不支持的文件类型", "Unsupported file type",
+        "Convert the file to a supported format";
+    EmptyMediaFile = 44001, Media, false,
+        "多媒体文件为空", "Empty media file",
+        "Check the file has content";
+    MediaTooLarge = 45001, Media, false,
+        "多媒体文件大小超过限制", "Media file too large",
+        "Compress or resize the file";
+    MediaNotFound = 46001, Media, false,
+        "不存在媒体数据", "Media not found",
+        "Check the media ID, or upload the material again";
+    MaterialInUse = 48005, Media, false,
+        "api 禁止删除被自动回复和自定义菜单引用的素材",
+        "Material is used by auto replies or the custom menu",
+        "Remove the material from auto replies and the menu before deleting it";
+
+    // Content
+    EmptyNewsContent = 44003, Content, false,
+        "图文消息内容为空", "Empty article content",
+        "Add content to the article";
+    ContentTooLong = 45002, Content, false,
+        "消息内容超过限制", "Content too long",
+        "Shorten the article content";
+    TitleTooLong = 45003, Content, false,
+        "标题字段超过限制", "Title too long",
+        "Shorten the title";
+    DescriptionTooLong = 45004, Content, false,
+        "描述字段超过限制", "Description too long",
+        "Shorten the digest";
+    LinkTooLong = 45005, Content, false,
+        "链接字段超过限制", "Link too long",
+        "Shorten the source URL";
+    TooManyArticles = 45008, Content, false,
+        "图文消息超过限制", "Too many articles",
+        "Put at most 8 articles in a draft";
+    CommentsNotAllowed = 88000, Permission, false,
+        "没有留言权限", "Comments not enabled for this account",
+        "Enable comments for the account in the official account console";
+    CommentArticleNotFound = 88001, Content, false,
+        "该图文不存在", "Article not found",
+        "Check the msg_data_id and index of the article";
+    SensitiveContent = 88002, Content, false,
+        "文章存在敏感信息", "Article contains sensitive content",
+        "Revise the article content";
+    FeaturedCommentLimit = 88003, Content, false,
+        "精选评论数已达上限", "Featured comment limit reached",
+        "Unfeature another comment first";
+    CommentDeletedByUser = 88004, Content, false,
+        "已被用户删除，无法精选", "Comment deleted by its author",
+        "Refresh the comment list";
+    CommentAlreadyReplied = 88005, Content, false,
+        "已经回复过了", "Comment already replied to",
+        "Delete the existing reply before replying again";
+    ReplyLengthInvalid = 88007, Content, false,
+        "回复超过长度限制或为0", "Reply empty or too long",
+        "Keep the reply non-empty and within the length limit";
+    CommentNotFound = 88008, Content, false,
+        "该评论不存在", "Comment not found",
+        "Refresh the comment list";
+    InvalidCommentCount = 88010, Content, false,
+        "获取评论数目不合法", "Invalid comment count",
+        "Request at most 50 comments per page";
+
+    // Publishing
+    PublishRestricted = 53404, Publish, false,
+        "账号已被限制发表", "Account is not allowed to publish",
+        "Check the notice in the official account console for the restriction";
+    DraftCheckFailed = 53503, Publish, false,
+        "该草稿未通过发布检查", "Draft failed the publish check",
+        "Open the draft in the official account console to see which check failed";
+    DraftRequiresConsole = 53504, Publish, false,
+        "需前往公众平台官网使用草稿", "Draft must be used in the official account console",
+        "Publish this draft from the official account console";
+    DraftNotSaved = 53505, Publish, false,
+        "请手动保存成功后再发表", "Save the draft manually before publishing",
+        "Open and save the draft in the official account console, then publish again";
+    InvalidArticleId = 53600, Publish, false,
+        "Article ID 无效", "Invalid article ID",
+        "Check the article_id returned by the publish status";
+}
+
+impl From<i32> for ApiErrorCode {
+    fn from(code: i32) -> Self {
+        ApiErrorCode::from_code(code)
+    }
+}
+
+impl fmt::Display for ApiErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.description_en(), self.description_zh())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalogue_round_trips() {
+        let mut codes = HashSet::new();
+        for &kind in ApiErrorCode::ALL {
+            assert!(codes.insert(kind.code()), "duplicate code {}", kind.code());
+            assert_eq!(ApiErrorCode::from_code(kind.code()), kind);
+            assert_ne!(kind.category(), ApiErrorCategory::Unknown);
+            assert!(!kind.description_zh().is_empty());
+            assert!(!kind.description_en().is_empty());
+            assert!(!kind.remediation().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_common_codes() {
+        let ip = ApiErrorCode::from_code(40164);
+        assert_eq!(ip, ApiErrorCode::IpNotWhitelisted);
+        assert_eq!(ip.category(), ApiErrorCategory::Credentials);
+        assert!(!ip.is_retryable());
+        assert!(ip.remediation().contains("whitelist"));
+
+        assert_eq!(
+            ApiErrorCode::from_code(53404).category(),
+            ApiErrorCategory::Publish
+        );
+        assert_eq!(ApiErrorCode::from_code(-1), ApiErrorCode::SystemBusy);
+        assert!(ApiErrorCode::SystemBusy.is_retryable());
+
+        let unknown = ApiErrorCode::from(99999);
+        assert_eq!(unknown, ApiErrorCode::Unknown(99999));
+        assert_eq!(unknown.code(), 99999);
+        assert_eq!(unknown.category(), ApiErrorCategory::Unknown);
+        assert_eq!(
+            ApiErrorCode::InvalidAppId.to_string(),
+            "Invalid AppID (不合法的 AppID)"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Standalone HTML exports for archiving and manual pasting.
+//!
+//! [`WeChatClient::export_html`](crate::WeChatClient::export_html) renders an article
+//! through the upload pipeline without contacting WeChat, embedding local images as
+//! data URIs. The result has two forms:
+//!
+//! - [`HtmlExport::document`]: a complete HTML file that opens anywhere, for archiving
+//! - [`HtmlExport::fragment`]: only the inline-styled `#wepub` section, ready to paste
+//!   into the WeChat web editor
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::{UploadOptions, WeChatClient};
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let export = WeChatClient::export_html("article.md", UploadOptions::default()).await?;
+//! export.save("article.html").await?;
+//! println!("{}", export.fragment);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, WeChatError};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::path::Path;
+
+/// A rendered article with every local image embedded.
+#[derive(Debug, Clone)]
+pub struct HtmlExport {
+    /// Self-contained HTML document
+    pub document: String,
+    /// The `#wepub` section alone, in the form the WeChat editor accepts on paste
+    pub fragment: String,
+}
+
+impl HtmlExport {
+    /// Creates an export from a rendered article document.
+    pub fn new(document: String) -> Self {
+        let fragment = clipboard_fragment(&document);
+        Self { document, fragment }
+    }
+
+    /// Writes the HTML document to a file.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        tokio::fs::write(path, &self.document).await.map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to write export: {e}"),
+            )
+        })
+    }
+}
+
+/// Encodes image data as a data URI, with the MIME type guessed from the file name.
+pub fn data_uri(path: &Path, data: &[u8]) -> String {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    format!("data:{mime};base64,{}", STANDARD.encode(data))
+}
+
+/// Extracts the `<article id="wepub">` element as a `<section>`.
+///
+/// The WeChat editor drops `<article>` tags on paste but keeps sections, together with
+/// their inline styles. Documents without the element are returned unchanged.
+fn clipboard_fragment(document: &str) -> String {
+    let Some(start) = document.find(r#"<article id="wepub""#) else {
+        return document.to_string();
+    };
+    let Some(end) = document.rfind("</article>") else {
+        return document.to_string();
+    };
+
+    let element = &document[start + "<article".len()..end];
+    format!("<section{element}</section>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clipboard_fragment() {
+        let document = r#"<!DOCTYPE html><html><head><title>t</title></head><body><article id="wepub" style="color: red"><p>正文</p><article>inner</article></article></body></html>"#;
+        let export = HtmlExport::new(document.to_string());
+
+        assert_eq!(
+            export.fragment,
+            r#"<section id="wepub" style="color: red"><p>正文</p><article>inner</article></section>"#
+        );
+        assert_eq!(export.document, document);
+    }
+
+    #[test]
+    fn test_data_uri() {
+        assert_eq!(
+            data_uri(Path::new("images/a.png"), b"png"),
+            "data:image/png;base64,cG5n"
+        );
+        assert_eq!(
+            data_uri(Path::new("a.unknown"), b""),
+            "data:application/octet-stream;base64,"
+        );
+    }
+}
@@ -4,17 +4,26 @@
 //! - Request size limits to prevent DoS attacks
 //! - Timeout configuration for reliability
 //! - Retry mechanisms with exponential backoff
+//! - Replay with a fresh access token when WeChat rejects the cached one
+//! - Client-side rate limits per endpoint, see [`crate::rate_limit`]
+//! - Access tokens and AppSecrets redacted from errors and logs
 //! - Safe download limits for external content
 
 use crate::config::{Config, RetryConfig, SecurityConfig};
-use crate::error::{Result, WeChatError};
-use crate::traits::HttpClient;
+use crate::error::{ErrorFrame, Result, WeChatError};
+use crate::rate_limit::RateLimiter;
+use crate::traits::{HttpClient, TokenProvider};
+use reqwest::header::HeaderMap;
 use reqwest::{Client, Response, multipart};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 use tracing::{debug, warn};
 
+/// Endpoint for uploading permanent materials.
+pub(crate) const MATERIAL_ENDPOINT: &str = "/cgi-bin/material/add_material";
+
 // Note: RetryConfig and SecurityConfig are re-exported from config module for backward compatibility
 
 /// HTTP client wrapper for WeChat API calls with automatic retry and token management.
@@ -22,6 +31,7 @@ use tracing::{debug, warn};
 pub struct WeChatHttpClient {
     client: Client,
     config: Config,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl WeChatHttpClient {
@@ -37,8 +47,13 @@ impl WeChatHttpClient {
             .connect_timeout(config.connect_timeout())
             .user_agent(&config.http.user_agent)
             .build()?;
+        let rate_limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
 
-        Ok(Self { client, config })
+        Ok(Self {
+            client,
+            config,
+            rate_limiter,
+        })
     }
 
     /// Creates a new client with custom retry configuration (legacy).
@@ -59,13 +74,61 @@ impl WeChatHttpClient {
         Self::with_config(config)
     }
 
+    /// Creates a client sharing this one's connection pool and configuration, with a rate
+    /// limiter of its own.
+    ///
+    /// WeChat counts quotas per official account, so clients for different accounts
+    /// should not draw on the same buckets.
+    pub fn with_own_rate_limiter(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            config: self.config.clone(),
+            rate_limiter: Arc::new(RateLimiter::new(self.config.rate_limit.clone())),
+        }
+    }
+
+    /// Gets the configuration this client was created with.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Gets the rate limiter shared by this client and its clones.
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Waits for the rate limit of `endpoint`, failing if its quota is used up.
+    ///
+    /// Called before every request to a WeChat endpoint.
+    pub async fn throttle(&self, endpoint: &str) -> Result<()> {
+        self.rate_limiter
+            .acquire(endpoint)
+            .await
+            .map_err(|error| error.context(ErrorFrame::Endpoint(endpoint.to_string())))
+    }
+
+    /// Makes a POST request with JSON body to an endpoint that takes no access token.
+    pub async fn post_json<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<Response> {
+        self.throttle(endpoint).await?;
+        let url = format!("{}{}", self.config.http.base_url, endpoint);
+        self.execute_with_retry(&url, || self.client.post(&url).json(body).send())
+            .await
+    }
+
+    /// Makes a GET request to an absolute URL with extra headers, such as an internal service.
+    pub async fn get_with_headers(&self, url: &str, headers: &HeaderMap) -> Result<Response> {
+        self.execute_with_retry(url, || self.client.get(url).headers(headers.clone()).send())
+            .await
+    }
+
     /// Makes a GET request with access token.
     pub async fn get_with_token(&self, endpoint: &str, access_token: &str) -> Result<Response> {
+        self.throttle(endpoint).await?;
         let url = format!(
             "{}{}?access_token={}",
             self.config.http.base_url, endpoint, access_token
         );
-        self.execute_with_retry(|| self.client.get(&url).send())
+        self.execute_with_retry(&url, || self.client.get(&url).send())
             .await
     }
 
@@ -75,15 +138,93 @@ impl WeChatHttpClient {
         endpoint: &str,
         access_token: &str,
         body: &T,
+    ) -> Result<Response> {
+        self.throttle(endpoint).await?;
+        self.send_json_with_token(endpoint, access_token, body)
+            .await
+    }
+
+    /// Sends a POST request like [`post_json_with_token`](Self::post_json_with_token),
+    /// without waiting for the rate limit.
+    ///
+    /// For requests run by [`execute_authorized`](Self::execute_authorized), which throttles.
+    async fn send_json_with_token<T: Serialize>(
+        &self,
+        endpoint: &str,
+        access_token: &str,
+        body: &T,
     ) -> Result<Response> {
         let url = format!(
             "{}{}?access_token={}",
             self.config.http.base_url, endpoint, access_token
         );
-        self.execute_with_retry(|| self.client.post(&url).json(body).send())
+        self.execute_with_retry(&url, || self.client.post(&url).json(body).send())
             .await
     }
 
+    /// Makes a POST request with JSON body, replaying it if the access token is rejected.
+    ///
+    /// See [`execute_authorized`](Self::execute_authorized).
+    pub async fn post_json_authorized<T: Serialize>(
+        &self,
+        endpoint: &str,
+        token_provider: &dyn TokenProvider,
+        body: &T,
+    ) -> Result<Response> {
+        self.execute_authorized(endpoint, token_provider, |access_token| async move {
+            self.send_json_with_token(endpoint, &access_token, body)
+                .await
+        })
+        .await
+    }
+
+    /// Runs a request to `endpoint` with a valid access token, replaying it once if WeChat
+    /// rejects the token.
+    ///
+    /// `request` is called with the token to send. When the response carries a
+    /// token-invalid errcode (40001, 40014, 42001 or 42007), for example because another
+    /// service refreshed the token, the provider replaces the token with
+    /// [`TokenProvider::refresh_rejected`] and the request is built and sent again. A
+    /// response with any other non-zero errcode becomes an error carrying `endpoint`, and
+    /// successful responses are returned unread. A used-up daily quota also empties the
+    /// rate limit bucket of `endpoint`, so later calls fail without being sent.
+    ///
+    /// The rate limit of `endpoint` is taken once, before the first attempt, so `request`
+    /// must send without throttling, and a replay costs no extra quota.
+    pub async fn execute_authorized<F, Fut>(
+        &self,
+        endpoint: &str,
+        token_provider: &dyn TokenProvider,
+        mut request: F,
+    ) -> Result<Response>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: std::future::Future<Output = Result<Response>>,
+    {
+        self.throttle(endpoint).await?;
+        let access_token = token_provider.get_token().await?;
+        let (mut response, mut errcode, mut errmsg) =
+            peek_errcode(request(access_token.clone()).await?).await?;
+
+        if WeChatError::from_api_response(errcode, "").is_token_rejection() {
+            warn!(
+                "Access token rejected with errcode {errcode}, refreshing and replaying the request"
+            );
+            let fresh_token = token_provider.refresh_rejected(&access_token).await?;
+            (response, errcode, errmsg) = peek_errcode(request(fresh_token).await?).await?;
+        }
+
+        if errcode != 0 {
+            let error = WeChatError::from_api_response(errcode, errmsg);
+            if matches!(error, WeChatError::QuotaExceeded { .. }) {
+                // WeChat won't take more calls today, so stop sending them
+                self.rate_limiter.exhaust(endpoint);
+            }
+            return Err(error.context(ErrorFrame::Endpoint(endpoint.to_string())));
+        }
+        Ok(response)
+    }
+
     /// Uploads a file using multipart form data with size validation.
     pub async fn upload_file(
         &self,
@@ -101,6 +242,8 @@ impl WeChatHttpClient {
         )
         .map_err(WeChatError::config_error)?;
 
+        self.throttle(endpoint).await?;
+
         // Sanitize filename for security
         let safe_filename = crate::utils::sanitize_filename(filename);
         let url = format!(
@@ -115,16 +258,16 @@ impl WeChatHttpClient {
 
         // Clone data for each retry attempt
         let field_name = field_name.to_string();
-        let url = url.clone();
+        let request_url = url.clone();
         let client = self.client.clone();
 
-        self.execute_with_retry(move || {
+        self.execute_with_retry(&url, move || {
             let part = multipart::Part::bytes(file_data.clone())
                 .file_name(safe_filename.clone())
                 .mime_str(&mime_type)
                 .unwrap();
             let form = multipart::Form::new().part(field_name.clone(), part);
-            client.post(&url).multipart(form).send()
+            client.post(&request_url).multipart(form).send()
         })
         .await
     }
@@ -136,6 +279,22 @@ impl WeChatHttpClient {
         material_type: &str,
         file_data: Vec<u8>,
         filename: &str,
+    ) -> Result<Response> {
+        self.throttle(MATERIAL_ENDPOINT).await?;
+        self.send_material(access_token, material_type, file_data, filename)
+            .await
+    }
+
+    /// Uploads a permanent material like [`upload_material`](Self::upload_material),
+    /// without waiting for the rate limit.
+    ///
+    /// For uploads run by [`execute_authorized`](Self::execute_authorized), which throttles.
+    pub(crate) async fn send_material(
+        &self,
+        access_token: &str,
+        material_type: &str,
+        file_data: Vec<u8>,
+        filename: &str,
     ) -> Result<Response> {
         // Validate file size
         crate::utils::validate_file_size(
@@ -149,10 +308,7 @@ impl WeChatHttpClient {
         let safe_filename = crate::utils::sanitize_filename(filename);
         let url = format!(
             "{}{}?access_token={}&type={}",
-            self.config.http.base_url,
-            "/cgi-bin/material/add_material",
-            access_token,
-            material_type
+            self.config.http.base_url, MATERIAL_ENDPOINT, access_token, material_type
         );
 
         // Guess MIME type from safe filename
@@ -161,10 +317,10 @@ impl WeChatHttpClient {
             .to_string();
 
         // Clone data for each retry attempt
-        let url = url.clone();
+        let request_url = url.clone();
         let client = self.client.clone();
 
-        self.execute_with_retry(move || {
+        self.execute_with_retry(&url, move || {
             let part = multipart::Part::bytes(file_data.clone())
                 .file_name(safe_filename.clone())
                 .mime_str(&mime_type)
@@ -172,13 +328,16 @@ impl WeChatHttpClient {
 
             let form = multipart::Form::new().part("media", part);
 
-            client.post(&url).multipart(form).send()
+            client.post(&request_url).multipart(form).send()
         })
         .await
     }
 
     /// Executes a request with intelligent retry logic.
-    async fn execute_with_retry<F, Fut>(&self, mut operation: F) -> Result<Response>
+    ///
+    /// Errors carry the request URL, with access tokens and AppSecrets redacted, and the
+    /// attempt that failed last.
+    async fn execute_with_retry<F, Fut>(&self, url: &str, mut operation: F) -> Result<Response>
     where
         F: FnMut() -> Fut,
         Fut: std::future::Future<Output = std::result::Result<Response, reqwest::Error>>,
@@ -201,13 +360,15 @@ impl WeChatHttpClient {
                             .unwrap_or_else(|_| "Unknown error".to_string());
 
                         let error = WeChatError::Internal {
-                            message: format!("HTTP {status}: {error_text}"),
+                            message: crate::utils::redact_secrets(&format!(
+                                "HTTP {status}: {error_text}"
+                            )),
                         };
 
                         // Use error-specific retry logic
                         let max_retries = error.max_retries().min(self.config.retry.max_attempts);
                         if attempt >= max_retries || !error.is_retryable() {
-                            return Err(error);
+                            return Err(request_error(error, url, attempt));
                         }
 
                         consecutive_failures += 1;
@@ -215,14 +376,12 @@ impl WeChatHttpClient {
                     }
                 }
                 Err(e) => {
-                    let error = WeChatError::Network {
-                        message: e.to_string(),
-                    };
+                    let error = WeChatError::from(e);
 
                     // Use error-specific retry logic
                     let max_retries = error.max_retries().min(self.config.retry.max_attempts);
                     if attempt >= max_retries || !error.is_retryable() {
-                        return Err(error);
+                        return Err(request_error(error, url, attempt));
                     }
 
                     consecutive_failures += 1;
@@ -266,15 +425,16 @@ impl WeChatHttpClient {
             }
         }
 
-        Err(last_error.unwrap_or_else(|| WeChatError::Internal {
+        let error = last_error.unwrap_or_else(|| WeChatError::Internal {
             message: "Retry loop completed without error".to_string(),
-        }))
+        });
+        Err(request_error(error, url, self.config.retry.max_attempts))
     }
 
     /// Downloads content from a URL.
     pub async fn download(&self, url: &str) -> Result<Vec<u8>> {
         let response = self
-            .execute_with_retry(|| self.client.get(url).send())
+            .execute_with_retry(url, || self.client.get(url).send())
             .await?;
 
         let bytes = response.bytes().await?;
@@ -288,7 +448,7 @@ impl WeChatHttpClient {
         use futures::StreamExt;
 
         let response = self
-            .execute_with_retry(|| self.client.get(url).send())
+            .execute_with_retry(url, || self.client.get(url).send())
             .await?;
 
         // Check content length if available
@@ -296,7 +456,7 @@ impl WeChatHttpClient {
             && content_length > effective_max_size
         {
             return Err(WeChatError::ImageUpload {
-                path: url.to_string(),
+                path: crate::utils::redact_secrets(url),
                 reason: format!(
                     "Content too large: {content_length} bytes (max: {effective_max_size} bytes)"
                 ),
@@ -313,7 +473,7 @@ impl WeChatHttpClient {
 
             if downloaded_size > effective_max_size {
                 return Err(WeChatError::ImageUpload {
-                    path: url.to_string(),
+                    path: crate::utils::redact_secrets(url),
                     reason: format!(
                         "Content too large during download: {downloaded_size} bytes (max: {effective_max_size} bytes)"
                     ),
@@ -323,11 +483,47 @@ impl WeChatHttpClient {
             data.extend_from_slice(&chunk);
         }
 
-        debug!("Downloaded {downloaded_size} bytes from {url}");
+        debug!(
+            "Downloaded {downloaded_size} bytes from {}",
+            crate::utils::redact_secrets(url)
+        );
         Ok(data)
     }
 }
 
+/// Adds the endpoint and attempt of a failed request to its error.
+fn request_error(error: WeChatError, url: &str, attempt: u32) -> WeChatError {
+    error
+        .context(ErrorFrame::Attempt(attempt))
+        .context(ErrorFrame::Endpoint(crate::utils::redact_secrets(url)))
+}
+
+/// Reads the `errcode` and `errmsg` of a JSON response, returning an equivalent unread
+/// response.
+///
+/// Responses that are not JSON objects report errcode 0.
+async fn peek_errcode(response: Response) -> Result<(Response, i32, String)> {
+    #[derive(Default, Deserialize)]
+    struct ErrCode {
+        #[serde(default)]
+        errcode: i32,
+        #[serde(default)]
+        errmsg: String,
+    }
+
+    let status = response.status();
+    let version = response.version();
+    let headers = response.headers().clone();
+    let body = response.bytes().await?;
+    let ErrCode { errcode, errmsg } = serde_json::from_slice(&body).unwrap_or_default();
+
+    let mut rebuilt = http::Response::new(body);
+    *rebuilt.status_mut() = status;
+    *rebuilt.version_mut() = version;
+    *rebuilt.headers_mut() = headers;
+    Ok((Response::from(rebuilt), errcode, errmsg))
+}
+
 // Implement the HttpClient trait for WeChatHttpClient
 #[async_trait::async_trait]
 impl HttpClient for WeChatHttpClient {
@@ -420,6 +616,7 @@ pub struct DraftResponse {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error_code::ApiErrorCode;
 
     #[tokio::test]
     async fn test_http_client_creation() {
@@ -427,6 +624,127 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_errors_redact_access_token() {
+        // Nothing listens on port 1, so every request fails with the URL in the message
+        let config = Config::builder()
+            .http(
+                crate::config::HttpConfig::builder()
+                    .base_url("http://127.0.0.1:1".to_string())
+                    .build(),
+            )
+            .retry(RetryConfig::builder().max_attempts(1).build())
+            .build();
+        let client = WeChatHttpClient::with_config(config).unwrap();
+
+        let error = client
+            .get_with_token("/cgi-bin/draft/get", "token_value_123")
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), WeChatError::Network { .. }));
+        for formatted in [error.to_string(), format!("{error:?}")] {
+            assert!(formatted.contains("access_token=***"), "{formatted}");
+            assert!(!formatted.contains("token_value_123"), "{formatted}");
+        }
+
+        let error = client
+            .upload_material("token_value_123", "image", vec![0; 16], "cover.png")
+            .await
+            .unwrap_err();
+        assert!(!format!("{error:?}").contains("token_value_123"));
+    }
+
+    #[tokio::test]
+    async fn test_replayed_request_is_throttled_once() {
+        use crate::auth::TokenManager;
+        use crate::config::{HttpConfig, RateLimit, RateLimitConfig};
+        use crate::test_support::MockServer;
+
+        // A token, its rejection, the replacement token, then the replayed request
+        let server = MockServer::serve([
+            r#"{"access_token":"token1","expires_in":7200}"#,
+            r#"{"errcode":40001,"errmsg":"invalid credential"}"#,
+            r#"{"access_token":"token2","expires_in":7200}"#,
+            r#"{"errcode":0,"media_id":"m1"}"#,
+        ])
+        .await;
+        let config = Config::builder()
+            .http(HttpConfig::builder().base_url(server.url.clone()).build())
+            .rate_limit(
+                RateLimitConfig::builder()
+                    .limit("/cgi-bin/draft/add", RateLimit::per_day(1))
+                    .build(),
+            )
+            .build();
+        let client = Arc::new(WeChatHttpClient::with_config(config).unwrap());
+        let manager = TokenManager::new("wx_app", "secret", client.clone());
+
+        let response = client
+            .post_json_authorized("/cgi-bin/draft/add", &manager, &serde_json::json!({}))
+            .await
+            .unwrap();
+
+        assert!(response.text().await.unwrap().contains("m1"));
+        let requests = server.requests();
+        assert!(requests[3].starts_with("POST /cgi-bin/draft/add?access_token=token2 "));
+        assert_eq!(
+            client.rate_limiter().remaining("/cgi-bin/draft/add"),
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quota_errcode_exhausts_rate_limit() {
+        use crate::config::{HttpConfig, RateLimit, RateLimitConfig};
+        use crate::token_provider::StaticTokenProvider;
+
+        // Nothing listens on port 1, so a call that reached the network would fail differently
+        let config = Config::builder()
+            .http(
+                HttpConfig::builder()
+                    .base_url("http://127.0.0.1:1".to_string())
+                    .build(),
+            )
+            .rate_limit(
+                RateLimitConfig::builder()
+                    .limit("/cgi-bin/draft/add", RateLimit::per_day(100))
+                    .build(),
+            )
+            .build();
+        let client = WeChatHttpClient::with_config(config).unwrap();
+        let provider = StaticTokenProvider::new("token");
+
+        let error = client
+            .execute_authorized("/cgi-bin/draft/add", &provider, |_| async {
+                Ok(Response::from(http::Response::new(
+                    r#"{"errcode":45009,"errmsg":"reach max api daily quota limit"}"#,
+                )))
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded {
+                code: Some(45009),
+                ..
+            }
+        ));
+        assert_eq!(
+            client.rate_limiter().remaining("/cgi-bin/draft/add"),
+            Some(0)
+        );
+
+        // Later calls are refused before being sent
+        let error = client
+            .post_json_authorized("/cgi-bin/draft/add", &provider, &serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded { code: None, .. }
+        ));
+    }
+
     #[test]
     fn test_retry_config() {
         let config = RetryConfig::default();
@@ -462,8 +780,14 @@ mod tests {
         let result = response.into_result();
         assert!(result.is_err());
 
-        if let Err(WeChatError::WeChatApi { code, message }) = result {
+        if let Err(WeChatError::WeChatApi {
+            code,
+            kind,
+            message,
+        }) = result
+        {
             assert_eq!(code, 40001);
+            assert_eq!(kind, ApiErrorCode::InvalidCredential);
             assert_eq!(message, "invalid credential");
         } else {
             panic!("Expected WeChatApi error");
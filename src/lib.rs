@@ -22,11 +22,24 @@
 //! The SDK is organized into several key modules:
 //!
 //! - [`WeChatClient`] - Main client for interacting with WeChat APIs
+//! - [`accounts`] - Named official accounts sharing one HTTP client
 //! - [`auth`] - Access token management with automatic refresh
+//! - [`token_store`] - Access token storage shared between processes
+//! - [`token_provider`] - Tokens from a central token service or a fixed value
 //! - [`upload`] - Image upload and draft management functionality
 //! - [`markdown`] - Markdown parsing and image extraction
+//! - [`plan`] - Offline dry runs of the upload pipeline
+//! - [`export`] - Standalone HTML exports with embedded images
+//! - [`diff`] - Comparing local articles with remote drafts
+//! - [`preview`] - Local preview server with live reload
+//! - [`reverse`] - Pulling published HTML back into markdown
+//! - [`series`] - Navigation boxes for multi-part article series
 //! - [`theme`] - Theme system for rendering HTML from Markdown
+//! - [`typography`] - Optional CJK spacing and punctuation normalisation
 //! - [`error`] - Comprehensive error types and handling
+//! - [`error_code`] - Catalogue of WeChat API error codes
+//! - [`rate_limit`] - Client-side rate limits per API endpoint
+//! - [`quota`] - Inspecting and resetting daily API quotas
 //!
 //! ## Quick Start
 //!
@@ -88,41 +101,68 @@
 //!
 //! match client.upload("article.md").await {
 //!     Ok(draft_id) => println!("Success: {}", draft_id),
-//!     Err(WeChatError::FileNotFound { path }) => {
-//!         eprintln!("File not found: {}", path);
-//!     }
-//!     Err(WeChatError::ThemeNotFound { theme }) => {
-//!         eprintln!("Theme not found: {}", theme);
-//!     }
-//!     Err(WeChatError::Network { message }) => {
-//!         eprintln!("Network error: {}", message);
-//!     }
-//!     Err(err) => eprintln!("Other error: {}", err),
+//!     // Upload errors carry context frames; match on the underlying error
+//!     Err(err) => match err.kind() {
+//!         WeChatError::FileNotFound { path } => {
+//!             eprintln!("File not found: {}", path);
+//!         }
+//!         WeChatError::ThemeNotFound { theme } => {
+//!             eprintln!("Theme not found: {}", theme);
+//!         }
+//!         WeChatError::Network { message } => {
+//!             eprintln!("Network error: {}", message);
+//!         }
+//!         _ => eprintln!("Other error: {}", err),
+//!     },
 //! }
 //! # Ok(())
 //! # }
 //! ```
 
+pub mod accounts;
 pub mod auth;
 pub mod client;
 pub mod config;
 pub mod css_vars;
+pub mod datacube;
+pub mod diff;
 pub mod error;
+pub mod error_code;
+pub mod export;
 pub mod http;
 pub mod markdown;
-pub mod datacube;
 pub mod mermaid;
+pub mod plan;
+pub mod preview;
+pub mod quota;
+pub mod rate_limit;
+pub mod reverse;
+pub mod series;
 pub mod theme;
+pub mod token_provider;
+pub mod token_store;
 pub mod traits;
+pub mod typography;
 pub mod upload;
 pub mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 // Re-export main types for convenience
-pub use client::{UploadOptions, WeChatClient};
-pub use config::Config;
+pub use accounts::AccountRegistry;
+pub use client::{UploadOptions, UploadReport, WeChatClient};
+pub use config::{Config, RateLimit, RateLimitConfig, TokenStrategy};
 pub use css_vars::CssVariableProcessor;
-pub use error::{ErrorSeverity, Result, WeChatError};
-pub use theme::BuiltinTheme;
+pub use diff::DraftDiff;
+pub use error::{ErrorSeverity, FieldViolation, Result, WeChatError};
+pub use error_code::{ApiErrorCategory, ApiErrorCode};
+pub use export::HtmlExport;
+pub use plan::UploadPlan;
+pub use quota::QuotaClient;
+pub use reverse::PulledArticle;
+pub use theme::{BuiltinTheme, CodeOverflow};
+pub use upload::ImagePolicy;
 
 #[cfg(test)]
 mod tests {
@@ -8,7 +8,8 @@
 //! - **Frontmatter Support**: YAML frontmatter parsing for metadata
 //! - **Image Extraction**: Automatic detection and extraction of image references
 //! - **Content Processing**: Clean markdown processing with CommonMark
-//! - **Summary Generation**: Automatic article summary extraction
+//! - **Summary Generation**: Character-aware digests within WeChat's length limit
+//! - **Callouts**: GitHub-style `> [!NOTE]` alerts and `:::tip` containers
 //! - **URL Resolution**: Relative path resolution for local images
 //!
 //! ## Frontmatter Format
@@ -21,6 +22,11 @@
 //! cover: "images/cover.jpg"       # Cover image path (required)
 //! theme: "lapis"                  # Theme name (optional, defaults to "default")
 //! code: "github"                  # Code highlighting theme (optional)
+//! source_url: "https://..."       # Original article link (optional)
+//! comments: true                  # Open comments (optional, also show_cover, fans_only_comments)
+//! series:                         # Series navigation (optional), read as series.name
+//!   name: "Rust 入门"              # and series.part
+//!   part: 2
 //! custom_field: "custom_value"    # Any additional metadata
 //! ---
 //! ```
@@ -62,15 +68,25 @@
 use crate::error::{Result, WeChatError};
 use crate::utils;
 use comrak::{Arena, ComrakOptions, nodes::NodeValue};
+use regex::Regex;
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::LazyLock;
+
+/// Matches `{{< include "path.md" >}}` directives.
+static INCLUDE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\{\{<\s*include\s+"([^"]+)"\s*>\}\}"#).unwrap());
 
 /// Represents an image reference found in markdown content.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ImageRef {
     /// Alt text for the image
     pub alt_text: String,
+    /// Optional title, as in `![alt](url "title")`
+    pub title: Option<String>,
     /// Original URL or file path
     pub original_url: String,
     /// Position in the markdown text (start, end)
@@ -85,12 +101,28 @@ impl ImageRef {
         let is_local = !url.starts_with("http://") && !url.starts_with("https://");
         Self {
             alt_text,
+            title: None,
             original_url: url,
             position,
             is_local,
         }
     }
 
+    /// Sets the image title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        let title = title.into();
+        self.title = (!title.is_empty()).then_some(title);
+        self
+    }
+
+    /// Gets the caption shown under the image: the title, or the alt text if there is none.
+    pub fn caption(&self) -> Option<&str> {
+        self.title
+            .as_deref()
+            .or(Some(self.alt_text.as_str()))
+            .filter(|caption| !caption.trim().is_empty())
+    }
+
     /// Resolves the image path relative to a base directory with security validation.
     pub fn resolve_path(&self, base_path: &Path) -> Result<PathBuf> {
         if self.is_local {
@@ -140,84 +172,232 @@ pub struct MarkdownContent {
     pub original_text: String,
 }
 
+/// A heading in the article outline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// Heading level (1-6)
+    pub level: u8,
+    /// Plain text of the heading
+    pub text: String,
+}
+
+/// WeChat's limit for article digests.
+pub const WECHAT_DIGEST_LIMIT: usize = 120;
+
+/// How text length is measured when building a digest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextMeasure {
+    /// Unicode characters
+    #[default]
+    Chars,
+    /// UTF-16 code units, as counted by the WeChat editor; most emoji count as two
+    Utf16,
+}
+
+impl TextMeasure {
+    /// Measures the length of `text`.
+    pub fn len(&self, text: &str) -> usize {
+        text.chars().map(|ch| self.char_len(ch)).sum()
+    }
+
+    fn char_len(&self, ch: char) -> usize {
+        match self {
+            TextMeasure::Chars => 1,
+            TextMeasure::Utf16 => ch.len_utf16(),
+        }
+    }
+}
+
+/// Options for generating an article digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestOptions {
+    /// Maximum digest length, including the ellipsis
+    pub max_length: usize,
+    /// How the length is measured
+    pub measure: TextMeasure,
+    /// Whether to keep adding paragraphs after the first one until the limit is reached
+    pub combine_paragraphs: bool,
+    /// Whether to end on a sentence boundary instead of an ellipsis where possible
+    pub sentence_boundary: bool,
+}
+
+impl DigestOptions {
+    /// Creates options for a first-paragraph digest of at most `max_length` characters.
+    pub fn new(max_length: usize) -> Self {
+        Self {
+            max_length,
+            measure: TextMeasure::Chars,
+            combine_paragraphs: false,
+            sentence_boundary: false,
+        }
+    }
+
+    /// Creates options matching WeChat's digest field, filling it with whole sentences.
+    pub fn wechat() -> Self {
+        Self::new(WECHAT_DIGEST_LIMIT)
+            .measure(TextMeasure::Utf16)
+            .combine_paragraphs(true)
+            .sentence_boundary(true)
+    }
+
+    /// Sets how the length is measured.
+    pub fn measure(mut self, measure: TextMeasure) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    /// Sets whether several paragraphs may be combined.
+    pub fn combine_paragraphs(mut self, combine: bool) -> Self {
+        self.combine_paragraphs = combine;
+        self
+    }
+
+    /// Sets whether the digest should end on a sentence boundary.
+    pub fn sentence_boundary(mut self, enabled: bool) -> Self {
+        self.sentence_boundary = enabled;
+        self
+    }
+}
+
 /// Helper struct for extracting summaries from markdown AST.
+///
+/// Collects the text of body paragraphs, skipping headings, code blocks, tables,
+/// raw HTML, footnotes and image alt text.
 struct SummaryExtractor {
-    summary: String,
-    text_length: usize,
-    max_length: usize,
-    found_paragraph: bool,
+    paragraphs: Vec<String>,
 }
 
 impl SummaryExtractor {
-    fn new(max_length: usize) -> Self {
+    fn new() -> Self {
         Self {
-            summary: String::new(),
-            text_length: 0,
-            max_length,
-            found_paragraph: false,
+            paragraphs: Vec::new(),
         }
     }
 
-    fn extract_from_node<'a>(&mut self, node: &'a comrak::nodes::AstNode<'a>) -> bool {
+    fn extract_from_node<'a>(&mut self, node: &'a comrak::nodes::AstNode<'a>) {
         match &node.data.borrow().value {
             NodeValue::Paragraph => {
-                if self.found_paragraph {
-                    // We already found a paragraph, stop here
-                    return true;
+                let mut text = String::new();
+                Self::collect_paragraph_text(node, &mut text);
+                let text = text.trim();
+                if !text.is_empty() {
+                    self.paragraphs.push(text.to_string());
                 }
-                self.found_paragraph = true;
+            }
+            NodeValue::Heading(_)
+            | NodeValue::CodeBlock(_)
+            | NodeValue::HtmlBlock(_)
+            | NodeValue::Table(_)
+            | NodeValue::FootnoteDefinition(_) => {}
+            _ => {
                 for child in node.children() {
-                    if self.extract_from_node(child) {
-                        break;
-                    }
-                    if self.text_length >= self.max_length {
-                        break;
-                    }
-                }
-                if !self.summary.is_empty() {
-                    return true; // Found first paragraph content, stop processing
+                    self.extract_from_node(child);
                 }
-                self.found_paragraph = false;
             }
-            NodeValue::Text(text) => {
-                if self.found_paragraph && self.text_length < self.max_length {
-                    let remaining = self.max_length - self.text_length;
-                    if text.len() <= remaining {
-                        self.summary.push_str(text);
-                        self.text_length += text.len();
-                    } else {
-                        // Find the nearest valid UTF-8 boundary
-                        let mut boundary = remaining;
-                        while boundary > 0 && !text.is_char_boundary(boundary) {
-                            boundary -= 1;
-                        }
+        }
+    }
 
-                        if boundary > 0 {
-                            self.summary.push_str(&text[..boundary]);
-                            self.summary.push_str("...");
-                        } else {
-                            // If we can't find a valid boundary, just add ellipsis
-                            self.summary.push_str("...");
-                        }
-                        self.text_length = self.max_length;
-                    }
+    fn collect_paragraph_text<'a>(node: &'a comrak::nodes::AstNode<'a>, text: &mut String) {
+        match &node.data.borrow().value {
+            NodeValue::Text(content) => text.push_str(content),
+            NodeValue::Code(code) => text.push_str(&code.literal),
+            NodeValue::SoftBreak | NodeValue::LineBreak => {
+                // Chinese lines wrap without a space; other scripts need one
+                if !text.chars().last().is_some_and(utils::is_wide_char) {
+                    text.push(' ');
                 }
             }
+            NodeValue::Image(_) | NodeValue::HtmlInline(_) | NodeValue::FootnoteReference(_) => {}
             _ => {
                 for child in node.children() {
-                    if self.extract_from_node(child) {
-                        return true;
-                    }
-                    if self.text_length >= self.max_length {
-                        break;
-                    }
+                    Self::collect_paragraph_text(child, text);
                 }
             }
         }
-        false
     }
 }
 
+/// Truncates text to a digest, never splitting a character or grapheme cluster.
+///
+/// With `sentence_boundary`, the digest ends after the last complete sentence that
+/// fits, as long as that keeps at least a third of the allowed length. Otherwise
+/// the text is cut and `…` is appended.
+fn truncate_digest(text: &str, options: &DigestOptions) -> String {
+    let text = text.trim();
+    let measure = options.measure;
+    if measure.len(text) <= options.max_length {
+        return text.to_string();
+    }
+
+    // Longest prefix that fits, as (byte offset, char) pairs
+    let mut kept: Vec<(usize, char)> = Vec::new();
+    let mut used = 0;
+    for (offset, ch) in text.char_indices() {
+        let width = measure.char_len(ch);
+        if used + width > options.max_length {
+            break;
+        }
+        used += width;
+        kept.push((offset, ch));
+    }
+    let end_of =
+        |kept: &[(usize, char)]| kept.last().map_or(0, |(offset, ch)| offset + ch.len_utf8());
+
+    if options.sentence_boundary {
+        let boundary = kept.iter().enumerate().rev().find(|(index, (offset, ch))| {
+            is_sentence_end(*ch, text[offset + ch.len_utf8()..].chars().next())
+                && measure.len(&text[..offset + ch.len_utf8()]) * 3 >= options.max_length
+                && !is_grapheme_extend(text[end_of(&kept[..=*index])..].chars().next())
+        });
+        if let Some((index, _)) = boundary {
+            return text[..end_of(&kept[..=index])].to_string();
+        }
+    }
+
+    // Make room for the ellipsis, then back off until no grapheme cluster is split
+    let ellipsis = measure.char_len('…');
+    while !kept.is_empty() && used + ellipsis > options.max_length {
+        if let Some((_, ch)) = kept.pop() {
+            used -= measure.char_len(ch);
+        }
+    }
+    while let Some(&(_, last)) = kept.last() {
+        let next = text[end_of(&kept)..].chars().next();
+        if last != '\u{200D}' && !is_grapheme_extend(next) {
+            break;
+        }
+        kept.pop();
+    }
+
+    format!("{}…", text[..end_of(&kept)].trim_end())
+}
+
+/// Checks whether a character ends a sentence.
+fn is_sentence_end(ch: char, next: Option<char>) -> bool {
+    match ch {
+        '。' | '！' | '？' | '；' | '…' => true,
+        '.' | '!' | '?' | ';' => next.is_none_or(char::is_whitespace),
+        _ => false,
+    }
+}
+
+/// Checks whether a character continues the preceding grapheme cluster.
+fn is_grapheme_extend(ch: Option<char>) -> bool {
+    ch.is_some_and(|ch| {
+        matches!(
+            ch as u32,
+            0x0300..=0x036F
+                | 0x1AB0..=0x1AFF
+                | 0x20D0..=0x20FF
+                | 0x200D
+                | 0xFE00..=0xFE0F
+                | 0x1F3FB..=0x1F3FF
+                | 0xE0020..=0xE007F
+                | 0xE0100..=0xE01EF
+        )
+    })
+}
+
 /// Helper struct for extracting plain text from markdown AST.
 struct PlainTextExtractor {
     text: String,
@@ -253,23 +433,20 @@ impl MarkdownContent {
 
         // Sort images by position in reverse order to avoid position shifting
         let mut sorted_images = self.images.clone();
-        sorted_images.sort_by(|a, b| b.position.0.cmp(&a.position.0));
+        sorted_images.sort_by_key(|image| std::cmp::Reverse(image.position.0));
 
         for image in &sorted_images {
             if let Some(new_url) = url_mapping.get(&image.original_url) {
-                // Find and replace the image URL in markdown
-                let old_markdown = format!("![{}]({})", image.alt_text, image.original_url);
-                let new_markdown = format!("![{}]({})", image.alt_text, new_url);
-
                 // Only convert to owned string when we actually need to modify
                 match content {
                     Cow::Borrowed(s) => {
-                        if s.contains(&old_markdown) {
-                            content = Cow::Owned(s.replace(&old_markdown, &new_markdown));
+                        let replaced = replace_image_url(s, image, new_url);
+                        if replaced != s {
+                            content = Cow::Owned(replaced);
                         }
                     }
                     Cow::Owned(ref mut s) => {
-                        *s = s.replace(&old_markdown, &new_markdown);
+                        *s = replace_image_url(s, image, new_url);
                     }
                 }
             }
@@ -283,26 +460,51 @@ impl MarkdownContent {
         Ok(())
     }
 
-    /// Gets a summary of the content (first paragraph or up to 200 characters).
+    /// Gets a summary of the content (the first paragraph, up to `max_length` characters).
     pub fn get_summary(&self, max_length: usize) -> String {
+        self.digest(&DigestOptions::new(max_length))
+    }
+
+    /// Generates a digest of the content according to `options`.
+    ///
+    /// Falls back to the document's plain text when it has no body paragraphs.
+    pub fn digest(&self, options: &DigestOptions) -> String {
         let arena = Arena::new();
-        let options = ComrakOptions::default();
-        let root = comrak::parse_document(&arena, &self.content, &options);
+        let options_md = ComrakOptions::default();
+        let root = comrak::parse_document(&arena, &self.content, &options_md);
 
-        let mut extractor = SummaryExtractor::new(max_length);
+        let mut extractor = SummaryExtractor::new();
         extractor.extract_from_node(root);
 
-        if extractor.summary.is_empty() {
-            // Fallback: take first characters of content
-            let content_text = self.extract_plain_text();
-            if content_text.len() > max_length {
-                format!("{}...", &content_text[..max_length])
-            } else {
-                content_text
+        let source = if extractor.paragraphs.is_empty() {
+            self.extract_plain_text()
+        } else if options.combine_paragraphs {
+            let mut combined = String::new();
+            for paragraph in &extractor.paragraphs {
+                if options.measure.len(&combined) > options.max_length {
+                    break;
+                }
+                if !combined.is_empty() && !combined.chars().last().is_some_and(utils::is_wide_char)
+                {
+                    combined.push(' ');
+                }
+                combined.push_str(paragraph);
             }
+            combined
         } else {
-            extractor.summary
-        }
+            extractor.paragraphs[0].clone()
+        };
+
+        truncate_digest(&source, options)
+    }
+
+    /// Gets all headings in document order.
+    pub fn headings(&self) -> Vec<Heading> {
+        let arena = Arena::new();
+        let options = ComrakOptions::default();
+        let root = comrak::parse_document(&arena, &self.content, &options);
+
+        collect_headings(root)
     }
 
     /// Extracts plain text from markdown content.
@@ -344,9 +546,28 @@ impl TitleExtractor {
         }
     }
 
+    fn collect_headings<'a>(node: &'a comrak::nodes::AstNode<'a>, headings: &mut Vec<Heading>) {
+        match &node.data.borrow().value {
+            NodeValue::Heading(heading) => {
+                let mut text = String::new();
+                Self::collect_heading_text(node, &mut text);
+                headings.push(Heading {
+                    level: heading.level,
+                    text: text.trim().to_string(),
+                });
+            }
+            _ => {
+                for child in node.children() {
+                    Self::collect_headings(child, headings);
+                }
+            }
+        }
+    }
+
     fn collect_heading_text<'a>(node: &'a comrak::nodes::AstNode<'a>, title: &mut String) {
         match &node.data.borrow().value {
             NodeValue::Text(text) => title.push_str(text),
+            NodeValue::Code(code) => title.push_str(&code.literal),
             _ => {
                 for child in node.children() {
                     Self::collect_heading_text(child, title);
@@ -356,6 +577,13 @@ impl TitleExtractor {
     }
 }
 
+/// Collects all headings below a parsed document node, in document order.
+pub(crate) fn collect_headings<'a>(root: &'a comrak::nodes::AstNode<'a>) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    TitleExtractor::collect_headings(root, &mut headings);
+    headings
+}
+
 /// Helper struct for extracting image references from markdown AST.
 struct ImageExtractor {
     images: Vec<ImageRef>,
@@ -379,16 +607,18 @@ impl ImageExtractor {
                 let url = link.url.clone();
 
                 // Calculate approximate position based on content search
-                let position = if let Some(start) =
-                    self.source_content.find(&format!("![{alt_text}]({url})"))
-                {
-                    let end = start + format!("![{alt_text}]({url})").len();
-                    (start, end)
-                } else {
-                    (0, 0) // Fallback if exact match not found
+                let prefix = format!("![{alt_text}]({url}");
+                let position = match self.source_content.find(&prefix) {
+                    Some(start) => {
+                        let end = self.source_content[start..]
+                            .find(')')
+                            .map_or(start + prefix.len(), |offset| start + offset + 1);
+                        (start, end)
+                    }
+                    None => (0, 0), // Fallback if exact match not found
                 };
 
-                let image_ref = ImageRef::new(alt_text, url, position);
+                let image_ref = ImageRef::new(alt_text, url, position).with_title(&link.title);
                 self.images.push(image_ref);
             }
             _ => {
@@ -426,6 +656,7 @@ impl MarkdownParser {
         options.extension.table = true;
         options.extension.footnotes = true;
         options.extension.tasklist = true;
+        options.extension.alerts = true;
         options.parse.smart = true;
 
         Self { options }
@@ -434,6 +665,8 @@ impl MarkdownParser {
     /// Parses markdown content from a string.
     pub fn parse(&self, markdown: &str) -> Result<MarkdownContent> {
         let (metadata, content_without_frontmatter) = self.extract_frontmatter(markdown)?;
+        let content_without_frontmatter =
+            convert_admonition_containers(&content_without_frontmatter);
         let title = self.extract_title(&content_without_frontmatter, &metadata);
         let author = metadata.get("author").cloned();
         let description = metadata.get("description").cloned();
@@ -457,17 +690,135 @@ impl MarkdownParser {
     }
 
     /// Parses markdown content from a file.
+    ///
+    /// `{{< include "path.md" >}}` directives are expanded relative to the including
+    /// file before parsing. Directives inside fenced code are left untouched.
     pub async fn parse_file<P: AsRef<Path>>(&self, path: P) -> Result<MarkdownContent> {
-        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+        let path = path.as_ref();
+        let content = tokio::fs::read_to_string(path).await.map_err(|e| {
             WeChatError::file_error(
-                path.as_ref().display().to_string(),
+                path.display().to_string(),
                 format!("Failed to read file: {e}"),
             )
         })?;
 
+        let base_dir = utils::get_base_directory(path).unwrap_or_else(|| Path::new("."));
+        let mut include_stack = vec![path.canonicalize().unwrap_or_else(|_| path.to_path_buf())];
+        let content = self
+            .resolve_includes(content, base_dir, &mut include_stack)
+            .await?;
+
         self.parse(&content)
     }
 
+    /// Expands include directives in `content`, resolving paths against `base_dir`.
+    ///
+    /// `include_stack` holds the files currently being expanded and is used to detect cycles.
+    fn resolve_includes<'a>(
+        &'a self,
+        content: String,
+        base_dir: &'a Path,
+        include_stack: &'a mut Vec<PathBuf>,
+    ) -> Pin<Box<dyn Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(async move {
+            if !INCLUDE_REGEX.is_match(&content) {
+                return Ok(content);
+            }
+
+            let mut output = Vec::new();
+            let mut fence = FenceTracker::default();
+
+            for line in content.split('\n') {
+                if fence.is_code(line) || !INCLUDE_REGEX.is_match(line) {
+                    output.push(line.to_string());
+                    continue;
+                }
+
+                let mut expanded = String::new();
+                let mut last_end = 0;
+                for caps in INCLUDE_REGEX.captures_iter(line) {
+                    let directive = caps.get(0).expect("capture group 0 always exists");
+                    expanded.push_str(&line[last_end..directive.start()]);
+                    expanded.push_str(&self.include_file(&caps[1], base_dir, include_stack).await?);
+                    last_end = directive.end();
+                }
+                expanded.push_str(&line[last_end..]);
+                output.push(expanded);
+            }
+
+            Ok(output.join("\n"))
+        })
+    }
+
+    /// Reads an included file, expands its own includes and rebases its image paths.
+    async fn include_file(
+        &self,
+        include_path: &str,
+        base_dir: &Path,
+        include_stack: &mut Vec<PathBuf>,
+    ) -> Result<String> {
+        let path = utils::resolve_path(base_dir, include_path).map_err(|e| {
+            WeChatError::MarkdownParse {
+                reason: format!("Invalid include path '{include_path}': {e}"),
+            }
+        })?;
+        let canonical = path.canonicalize().map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to resolve include: {e}"),
+            )
+        })?;
+
+        if include_stack.contains(&canonical) {
+            let chain = include_stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(WeChatError::MarkdownParse {
+                reason: format!("Include cycle detected: {chain}"),
+            });
+        }
+
+        let text = tokio::fs::read_to_string(&canonical).await.map_err(|e| {
+            WeChatError::file_error(
+                canonical.display().to_string(),
+                format!("Failed to read include: {e}"),
+            )
+        })?;
+        let (_, body) = self.extract_frontmatter(&text)?;
+        let include_dir = canonical.parent().unwrap_or_else(|| Path::new("."));
+
+        include_stack.push(canonical.clone());
+        let body = self
+            .resolve_includes(body, include_dir, include_stack)
+            .await;
+        include_stack.pop();
+
+        // Images in the included file are relative to its own directory
+        match Path::new(include_path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => Ok(self.rebase_image_paths(&body?, dir)),
+            _ => body,
+        }
+    }
+
+    /// Prefixes relative local image paths in `content` with `dir`.
+    fn rebase_image_paths(&self, content: &str, dir: &Path) -> String {
+        let mut rebased = content.to_string();
+        let images = self.extract_images(content).unwrap_or_default();
+
+        for image in images {
+            if !image.is_local || Path::new(&image.original_url).is_absolute() {
+                continue;
+            }
+            let new_url = dir.join(&image.original_url).display().to_string();
+            rebased = replace_image_url(&rebased, &image, &new_url);
+        }
+
+        rebased
+    }
+
     /// Extracts front matter (YAML) from markdown content.
     fn extract_frontmatter(&self, markdown: &str) -> Result<(HashMap<String, String>, String)> {
         let mut metadata = HashMap::new();
@@ -477,11 +828,21 @@ impl MarkdownParser {
                 let frontmatter = &stripped[..end_pos];
                 let content = &stripped[end_pos + 5..]; // skip "\n---\n"
 
-                // Parse YAML-like front matter (simple key: value pairs)
+                // Parse YAML-like front matter (simple key: value pairs). Indented
+                // lines under a key without a value become `parent.child` keys.
+                let mut parent: Option<String> = None;
                 for line in frontmatter.lines() {
                     if let Some((key, value)) = line.split_once(':') {
+                        let nested = line.starts_with([' ', '\t']);
                         let key = key.trim().to_string();
                         let value = value.trim().trim_matches('"').to_string();
+                        let key = match (&parent, nested) {
+                            (Some(parent), true) => format!("{parent}.{key}"),
+                            _ => {
+                                parent = value.is_empty().then(|| key.clone());
+                                key
+                            }
+                        };
                         metadata.insert(key, value);
                     }
                 }
@@ -522,6 +883,110 @@ impl MarkdownParser {
     }
 }
 
+/// Rewrites `:::type [title]` containers as GitHub-style alert blockquotes.
+///
+/// Containers close with a line of colons and may be nested. Fenced code is left
+/// untouched, as are containers whose type is not a known alert type.
+pub fn convert_admonition_containers(markdown: &str) -> String {
+    let mut output = Vec::new();
+    let mut depth = 0;
+    let mut fence = FenceTracker::default();
+
+    for line in markdown.split('\n') {
+        let trimmed = line.trim();
+        let prefix = "> ".repeat(depth);
+
+        if fence.is_code(line) {
+            // Fenced code is copied as-is
+        } else if let Some(rest) = trimmed.strip_prefix(":::") {
+            let rest = rest.trim_start_matches(':').trim();
+
+            if rest.is_empty() && depth > 0 {
+                // A blank line ends the blockquote so following text is not pulled in
+                depth -= 1;
+                output.push("> ".repeat(depth).trim_end().to_string());
+                continue;
+            }
+
+            let (kind, title) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if let Some(alert_type) = admonition_alert_type(kind) {
+                output.push(
+                    format!("{prefix}> [!{alert_type}] {}", title.trim())
+                        .trim_end()
+                        .to_string(),
+                );
+                depth += 1;
+                continue;
+            }
+        }
+
+        if depth == 0 {
+            output.push(line.to_string());
+        } else {
+            output.push(format!("{prefix}{line}").trim_end().to_string());
+        }
+    }
+
+    output.join("\n")
+}
+
+/// Replaces the URL of an image reference in markdown, with or without a title.
+fn replace_image_url(content: &str, image: &ImageRef, new_url: &str) -> String {
+    let alt = &image.alt_text;
+    let old_url = &image.original_url;
+
+    content
+        .replace(
+            &format!("![{alt}]({old_url})"),
+            &format!("![{alt}]({new_url})"),
+        )
+        .replace(
+            &format!("![{alt}]({old_url} "),
+            &format!("![{alt}]({new_url} "),
+        )
+}
+
+/// Tracks fenced code blocks while scanning markdown line by line.
+#[derive(Default)]
+struct FenceTracker {
+    marker: Option<String>,
+}
+
+impl FenceTracker {
+    /// Returns true if the line is a fence delimiter or lies inside fenced code.
+    fn is_code(&mut self, line: &str) -> bool {
+        let trimmed = line.trim();
+
+        if let Some(marker) = &self.marker {
+            if trimmed.starts_with(marker.as_str()) {
+                self.marker = None;
+            }
+            return true;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let fence_char = trimmed.chars().next().unwrap_or('`');
+            let fence_len = trimmed.chars().take_while(|c| *c == fence_char).count();
+            self.marker = Some(fence_char.to_string().repeat(fence_len));
+            return true;
+        }
+
+        false
+    }
+}
+
+/// Maps a container type such as `tip` or `danger` to its GitHub alert type.
+fn admonition_alert_type(kind: &str) -> Option<&'static str> {
+    match kind.to_lowercase().as_str() {
+        "note" | "info" => Some("NOTE"),
+        "tip" | "hint" | "success" => Some("TIP"),
+        "important" => Some("IMPORTANT"),
+        "warning" | "warn" | "attention" => Some("WARNING"),
+        "caution" | "danger" | "error" => Some("CAUTION"),
+        _ => None,
+    }
+}
+
 impl Default for MarkdownParser {
     fn default() -> Self {
         Self::new()
@@ -571,6 +1036,19 @@ This is the content."#;
         assert!(content.contains("# Content"));
     }
 
+    #[test]
+    fn test_nested_frontmatter_keys() {
+        let parser = MarkdownParser::new();
+        let markdown = "---\ntitle: Part Two\nseries:\n  name: \"Rust 入门\"\n  part: 2\ntheme: lapis\n---\n\nBody";
+
+        let (metadata, _) = parser.extract_frontmatter(markdown).unwrap();
+
+        assert_eq!(metadata.get("series.name"), Some(&"Rust 入门".to_string()));
+        assert_eq!(metadata.get("series.part"), Some(&"2".to_string()));
+        assert_eq!(metadata.get("theme"), Some(&"lapis".to_string()));
+        assert!(!metadata.contains_key("series.theme"));
+    }
+
     #[test]
     fn test_title_extraction() {
         let parser = MarkdownParser::new();
@@ -679,6 +1157,74 @@ This is the second paragraph.
         assert!(!summary.contains("This is the second paragraph"));
     }
 
+    #[test]
+    fn test_digest_counts_characters() {
+        let parser = MarkdownParser::new();
+        let content = parser.parse(&"微信公众号文章摘要".repeat(20)).unwrap();
+
+        let summary = content.get_summary(10);
+        assert_eq!(summary.chars().count(), 10);
+        assert!(summary.ends_with('…'));
+
+        // Plain-text fallback is also cut on character boundaries
+        let content = parser.parse("# 只有标题的文章内容").unwrap();
+        assert_eq!(content.get_summary(3), "只有…");
+    }
+
+    #[test]
+    fn test_digest_skips_noise() {
+        let parser = MarkdownParser::new();
+        let markdown = r#"# 标题
+
+![封面图片](cover.png)
+
+```rust
+fn main() {}
+```
+
+正文使用 `cargo` 构建，<b>效果</b>很好。
+"#;
+        let content = parser.parse(markdown).unwrap();
+        assert_eq!(content.get_summary(120), "正文使用 cargo 构建，效果很好。");
+    }
+
+    #[test]
+    fn test_digest_sentence_boundary_and_combining() {
+        let parser = MarkdownParser::new();
+        let markdown = "第一句话。第二句话比较长一些。\n\n第二段的内容。";
+        let content = parser.parse(markdown).unwrap();
+
+        let options = DigestOptions::new(12).sentence_boundary(true);
+        assert_eq!(content.digest(&options), "第一句话。");
+
+        let options = DigestOptions::new(40).combine_paragraphs(true);
+        assert_eq!(
+            content.digest(&options),
+            "第一句话。第二句话比较长一些。第二段的内容。"
+        );
+
+        let options = DigestOptions::new(40);
+        assert_eq!(content.digest(&options), "第一句话。第二句话比较长一些。");
+    }
+
+    #[test]
+    fn test_digest_does_not_split_graphemes() {
+        let options = DigestOptions::new(4);
+        // Family emoji joined with ZWJ must not be cut in the middle
+        assert_eq!(truncate_digest("ab👨\u{200D}👩\u{200D}👧", &options), "ab…");
+        assert_eq!(truncate_digest("abce\u{301}fg", &options), "abc…");
+
+        let options = DigestOptions::new(5).measure(TextMeasure::Utf16);
+        assert_eq!(TextMeasure::Utf16.len("a😀"), 3);
+        assert_eq!(truncate_digest("😀😀😀😀", &options), "😀😀…");
+
+        let wechat = DigestOptions::wechat();
+        let long = "这是一段很长的文字。".repeat(30);
+        let digest = truncate_digest(&long, &wechat);
+        assert!(wechat.measure.len(&digest) <= WECHAT_DIGEST_LIMIT);
+        assert!(digest.ends_with('。'));
+    }
+
     #[tokio::test]
     async fn test_file_parsing() {
         let parser = MarkdownParser::new();
@@ -871,4 +1417,125 @@ Article content with an image: ![Example](./example.jpg)
         assert_eq!(content.images.len(), 1);
         assert_eq!(content.images[0].original_url, "./example.jpg");
     }
+
+    #[test]
+    fn test_admonition_container_conversion() {
+        let markdown = "Intro\n\n:::tip 小贴士\nUse `cargo fmt`.\n\n```rust\n:::\n```\n:::\nAfter";
+        let converted = convert_admonition_containers(markdown);
+
+        assert_eq!(
+            converted,
+            "Intro\n\n> [!TIP] 小贴士\n> Use `cargo fmt`.\n>\n> ```rust\n> :::\n> ```\n\nAfter"
+        );
+
+        let nested = convert_admonition_containers(":::warning\n::::danger\nBoom\n::::\n:::");
+        assert_eq!(nested, "> [!WARNING]\n> > [!CAUTION]\n> > Boom\n>\n");
+
+        // Unknown container types are left alone
+        assert_eq!(
+            convert_admonition_containers(":::details\nx\n:::"),
+            ":::details\nx\n:::"
+        );
+    }
+
+    #[test]
+    fn test_admonitions_in_parsed_content() {
+        let parser = MarkdownParser::new();
+        let result = parser
+            .parse("---\ntitle: T\n---\n:::note\n![a](a.png)\n:::\n")
+            .unwrap();
+
+        assert!(result.content.starts_with("> [!NOTE]\n> ![a](a.png)"));
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].original_url, "a.png");
+    }
+
+    #[test]
+    fn test_heading_extraction() {
+        let parser = MarkdownParser::new();
+        let result = parser
+            .parse("# Title\n\n## Using `cargo`\n\ntext\n\n### Details\n\n> ## Quoted")
+            .unwrap();
+
+        let headings = result.headings();
+        assert_eq!(headings.len(), 4);
+        assert_eq!(headings[1].text, "Using cargo");
+        assert_eq!(headings[2].level, 3);
+        assert_eq!(headings[3].text, "Quoted");
+    }
+
+    #[tokio::test]
+    async fn test_include_directive() {
+        let dir = tempfile::tempdir().unwrap();
+        let partials = dir.path().join("partials");
+        std::fs::create_dir(&partials).unwrap();
+        std::fs::write(
+            partials.join("bio.md"),
+            "---\ntitle: ignored\n---\nAbout me ![qr](qr.png)\n{{< include \"links.md\" >}}",
+        )
+        .unwrap();
+        std::fs::write(partials.join("links.md"), "[site](https://example.com)").unwrap();
+        std::fs::write(
+            dir.path().join("article.md"),
+            "# Post\n\n{{< include \"partials/bio.md\" >}}\n\n```\n{{< include \"missing.md\" >}}\n```",
+        )
+        .unwrap();
+
+        let parser = MarkdownParser::new();
+        let result = parser
+            .parse_file(dir.path().join("article.md"))
+            .await
+            .unwrap();
+
+        assert!(result.content.contains("About me ![qr](partials/qr.png)"));
+        assert!(result.content.contains("[site](https://example.com)"));
+        assert!(!result.content.contains("ignored"));
+        // Directives inside fenced code stay as written
+        assert!(result.content.contains("{{< include \"missing.md\" >}}"));
+        assert_eq!(result.images[0].original_url, "partials/qr.png");
+    }
+
+    #[tokio::test]
+    async fn test_include_cycle_detection() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "A {{< include \"b.md\" >}}").unwrap();
+        std::fs::write(dir.path().join("b.md"), "B {{< include \"a.md\" >}}").unwrap();
+
+        let parser = MarkdownParser::new();
+        let err = parser
+            .parse_file(dir.path().join("a.md"))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            WeChatError::MarkdownParse { ref reason } if reason.contains("Include cycle detected")
+        ));
+    }
+
+    #[test]
+    fn test_image_titles() {
+        let parser = MarkdownParser::new();
+        let mut result = parser
+            .parse("![Sunset](a.png \"Evening at the lake\")\n\n![Dawn](b.png)\n\n![](c.png)")
+            .unwrap();
+
+        assert_eq!(
+            result.images[0].title.as_deref(),
+            Some("Evening at the lake")
+        );
+        assert_eq!(result.images[0].caption(), Some("Evening at the lake"));
+        assert_eq!(result.images[1].caption(), Some("Dawn"));
+        assert_eq!(result.images[2].caption(), None);
+        assert_eq!(result.images[0].position, (0, 38));
+
+        let mut mapping = HashMap::new();
+        mapping.insert("a.png".to_string(), "https://mmbiz.qpic.cn/a".to_string());
+        result.replace_image_urls(&mapping).unwrap();
+        assert!(
+            result
+                .content
+                .starts_with("![Sunset](https://mmbiz.qpic.cn/a \"Evening at the lake\")")
+        );
+    }
 }
@@ -0,0 +1,149 @@
+//! Offline upload plans for dry runs.
+//!
+//! [`WeChatClient::plan`](crate::WeChatClient::plan) runs the upload pipeline without
+//! contacting WeChat: markdown is parsed, Mermaid charts are rendered, local images are
+//! read and hashed, and the article is rendered and validated. No credentials are
+//! needed, so it can run in CI on every pull request to a content repository.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::{UploadOptions, WeChatClient};
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let plan = WeChatClient::plan("article.md", UploadOptions::default()).await?;
+//!
+//! println!("{:?}: {}", plan.action, plan.article.title);
+//! for image in &plan.images {
+//!     println!("{} ({:?} bytes)", image.image_ref.original_url, image.size);
+//! }
+//! for warning in &plan.warnings {
+//!     eprintln!("warning: {warning}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::markdown::ImageRef;
+use crate::upload::{Article, load_local_image};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What publishing the article would do to the drafts box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DraftAction {
+    /// Create a new draft, or update the recent draft with the same title
+    Upsert { title: String },
+    /// Update the given draft
+    Update { media_id: String },
+}
+
+/// An image that would be uploaded.
+#[derive(Debug, Clone)]
+pub struct PlannedImage {
+    /// Reference as written in the markdown
+    pub image_ref: ImageRef,
+    /// Resolved file path, for local images
+    pub path: Option<PathBuf>,
+    /// File size in bytes, for local images
+    pub size: Option<u64>,
+    /// BLAKE3 content hash used for deduplication, for local images
+    pub hash: Option<String>,
+}
+
+impl PlannedImage {
+    /// Resolves, reads and hashes an image relative to `base_dir`.
+    ///
+    /// Remote images are not downloaded, so their size and hash are unknown.
+    pub async fn inspect(image_ref: ImageRef, base_dir: &Path) -> Result<Self> {
+        if !image_ref.is_local {
+            return Ok(Self {
+                image_ref,
+                path: None,
+                size: None,
+                hash: None,
+            });
+        }
+
+        let path = image_ref.resolve_path(base_dir)?;
+        Self::inspect_path(image_ref, path).await
+    }
+
+    /// Reads and hashes an image at an already resolved path.
+    pub async fn inspect_path(image_ref: ImageRef, path: PathBuf) -> Result<Self> {
+        let data = load_local_image(&path).await?;
+
+        Ok(Self {
+            image_ref,
+            size: Some(data.len() as u64),
+            hash: Some(blake3::hash(&data).to_hex().to_string()),
+            path: Some(path),
+        })
+    }
+}
+
+/// Everything an upload would publish, computed offline.
+#[derive(Debug, Clone)]
+pub struct UploadPlan {
+    /// The markdown file the plan was built from
+    pub source: PathBuf,
+    /// Theme used for rendering
+    pub theme: String,
+    /// The draft article; its content is the rendered HTML with local image paths
+    pub article: Article,
+    /// Body and partial images, in document order
+    pub images: Vec<PlannedImage>,
+    /// Cover image
+    pub cover: PlannedImage,
+    /// Whether a draft would be created or updated
+    pub action: DraftAction,
+    /// Problems that would not stop the upload but deserve a look
+    pub warnings: Vec<String>,
+}
+
+impl UploadPlan {
+    /// Gets the rendered HTML.
+    pub fn html(&self) -> &str {
+        &self.article.content
+    }
+
+    /// Gets the total size of local images that would be uploaded, counting duplicates once.
+    pub fn total_image_bytes(&self) -> u64 {
+        let mut seen = HashSet::new();
+        self.images
+            .iter()
+            .chain(std::iter::once(&self.cover))
+            .filter(|image| image.hash.as_ref().is_some_and(|hash| seen.insert(hash)))
+            .filter_map(|image| image.size)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_inspect_images() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("a.png"), b"\x89PNG data")
+            .await
+            .unwrap();
+
+        let local = ImageRef::new(String::new(), "a.png".to_string(), (0, 0));
+        let planned = PlannedImage::inspect(local, dir.path()).await.unwrap();
+        assert_eq!(planned.size, Some(9));
+        assert_eq!(
+            planned.hash.as_deref(),
+            Some(blake3::hash(b"\x89PNG data").to_hex().as_str())
+        );
+
+        let remote = ImageRef::new(String::new(), "https://a.com/b.png".to_string(), (0, 0));
+        let planned = PlannedImage::inspect(remote, dir.path()).await.unwrap();
+        assert!(planned.path.is_none() && planned.hash.is_none());
+
+        let missing = ImageRef::new(String::new(), "missing.png".to_string(), (0, 0));
+        assert!(PlannedImage::inspect(missing, dir.path()).await.is_err());
+    }
+}
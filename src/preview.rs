@@ -0,0 +1,635 @@
+//! Local preview server with live reload.
+//!
+//! Serves a markdown article rendered by [`ThemeManager::render`] inside a phone-sized
+//! frame on localhost, so theme and content changes can be checked without creating a
+//! real draft.
+//!
+//! ## Features
+//!
+//! - **Phone Viewport**: The article is shown at a 375px wide viewport by default
+//! - **Local Images**: Images are served directly from the article's directory
+//! - **Live Reload**: Changes to markdown, CSS, HTML and image files are pushed to the
+//!   browser over server-sent events
+//! - **Theme Switchers**: Compare every built-in theme and code theme from the toolbar
+//! - **Custom Themes**: CSS files registered with [`PreviewServer::theme_file`] are
+//!   re-read on every render
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::preview::PreviewServer;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! PreviewServer::new("article.md")
+//!     .theme_file("brand", "themes/brand.css")
+//!     .serve()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, WeChatError};
+use crate::markdown::MarkdownParser;
+use crate::series;
+use crate::theme::{Partials, ThemeManager, ThemeTemplate};
+use crate::utils;
+use askama::Template;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Default address the preview server listens on
+const DEFAULT_PREVIEW_ADDR: ([u8; 4], u16) = ([127, 0, 0, 1], 4000);
+
+/// Default viewport width in CSS pixels (a typical phone)
+const DEFAULT_VIEWPORT_WIDTH: u32 = 375;
+
+/// Default interval between checks for file changes
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Interval between keep-alive comments on the event stream
+const EVENT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Maximum size of an HTTP request head
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// File extensions whose changes trigger a reload
+const WATCHED_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "css", "html", "png", "jpg", "jpeg", "gif", "webp", "svg",
+];
+
+/// Directories skipped when watching for changes
+const IGNORED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// Askama template for the preview page around the article frame.
+#[derive(Template)]
+#[template(path = "preview.html")]
+struct PreviewTemplate {
+    title: String,
+    viewport_width: u32,
+    themes: Vec<String>,
+    theme_name: String,
+    code_themes: Vec<String>,
+    code_theme: String,
+}
+
+/// Configuration for a local preview server.
+#[derive(Debug, Clone)]
+pub struct PreviewServer {
+    markdown_path: PathBuf,
+    addr: SocketAddr,
+    theme_files: Vec<(String, PathBuf)>,
+    viewport_width: u32,
+    poll_interval: Duration,
+}
+
+impl PreviewServer {
+    /// Creates a preview server for a markdown file, listening on `127.0.0.1:4000`.
+    pub fn new(markdown_path: impl Into<PathBuf>) -> Self {
+        Self {
+            markdown_path: markdown_path.into(),
+            addr: SocketAddr::from(DEFAULT_PREVIEW_ADDR),
+            theme_files: Vec::new(),
+            viewport_width: DEFAULT_VIEWPORT_WIDTH,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the address to listen on. Port 0 picks a free port.
+    pub fn addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+
+    /// Adds a theme loaded from a CSS file, re-read whenever the article is rendered.
+    ///
+    /// A file named like a built-in theme replaces it, which is handy when editing the
+    /// bundled themes.
+    pub fn theme_file(mut self, name: impl Into<String>, css_path: impl Into<PathBuf>) -> Self {
+        self.theme_files.push((name.into(), css_path.into()));
+        self
+    }
+
+    /// Sets the width of the phone viewport in CSS pixels.
+    pub fn viewport_width(mut self, width: u32) -> Self {
+        self.viewport_width = width;
+        self
+    }
+
+    /// Sets how often watched files are checked for changes.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Binds the listening socket without serving requests yet.
+    pub async fn bind(self) -> Result<Preview> {
+        if !utils::file_exists(&self.markdown_path).await {
+            return Err(WeChatError::FileNotFound {
+                path: self.markdown_path.display().to_string(),
+            });
+        }
+
+        let listener = TcpListener::bind(self.addr).await?;
+        let base_dir = utils::get_base_directory(&self.markdown_path)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let (reload, _) = broadcast::channel(16);
+
+        Ok(Preview {
+            listener,
+            state: Arc::new(PreviewState {
+                config: self,
+                base_dir,
+                reload,
+            }),
+        })
+    }
+
+    /// Binds and serves the preview until the process is stopped.
+    pub async fn serve(self) -> Result<()> {
+        self.bind().await?.run().await
+    }
+}
+
+/// A preview server bound to its listening address.
+#[derive(Debug)]
+pub struct Preview {
+    listener: TcpListener,
+    state: Arc<PreviewState>,
+}
+
+impl Preview {
+    /// Gets the address the server is listening on.
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Watches for file changes and serves requests until the process is stopped.
+    pub async fn run(self) -> Result<()> {
+        info!(
+            "Previewing {} at http://{}",
+            self.state.config.markdown_path.display(),
+            self.local_addr()?
+        );
+
+        tokio::spawn(watch_files(Arc::clone(&self.state)));
+
+        loop {
+            let (stream, peer) = self.listener.accept().await?;
+            let state = Arc::clone(&self.state);
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(stream, &state).await {
+                    debug!("Preview connection from {peer} failed: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// State shared by all connections.
+#[derive(Debug)]
+struct PreviewState {
+    config: PreviewServer,
+    base_dir: PathBuf,
+    reload: broadcast::Sender<()>,
+}
+
+impl PreviewState {
+    /// Creates a theme manager with the built-in themes and the registered theme files.
+    async fn theme_manager(&self) -> Result<ThemeManager> {
+        let mut manager = ThemeManager::new();
+        for (name, path) in &self.config.theme_files {
+            let css = tokio::fs::read_to_string(path).await.map_err(|e| {
+                WeChatError::file_error(
+                    path.display().to_string(),
+                    format!("Failed to read theme: {e}"),
+                )
+            })?;
+            manager.add_theme(
+                name.clone(),
+                ThemeTemplate::new(css, String::new(), name.clone()),
+            );
+        }
+        Ok(manager)
+    }
+
+    /// Renders the preview page with the theme switchers.
+    async fn render_page(&self) -> Result<String> {
+        let content = MarkdownParser::new()
+            .parse_file(&self.config.markdown_path)
+            .await?;
+        let manager = self.theme_manager().await?;
+
+        let mut themes: Vec<String> = manager.available_themes().into_iter().cloned().collect();
+        themes.sort();
+        let mut code_themes: Vec<String> = manager
+            .available_code_themes()
+            .into_iter()
+            .cloned()
+            .collect();
+        code_themes.sort();
+
+        let template = PreviewTemplate {
+            title: content.title.unwrap_or_else(|| "Untitled".to_string()),
+            viewport_width: self.config.viewport_width,
+            themes,
+            theme_name: content.theme.unwrap_or_else(|| "default".to_string()),
+            code_themes,
+            code_theme: content.code.unwrap_or_else(|| "vscode".to_string()),
+        };
+
+        template.render().map_err(|e| WeChatError::Internal {
+            message: format!("Preview page rendering failed: {e}"),
+        })
+    }
+
+    /// Renders the article with the theme and code theme chosen in the toolbar.
+    async fn render_article(&self, query: &HashMap<String, String>) -> Result<String> {
+        let content = MarkdownParser::new()
+            .parse_file(&self.config.markdown_path)
+            .await?;
+        let manager = self.theme_manager().await?;
+
+        let theme = query
+            .get("theme")
+            .or(content.theme.as_ref())
+            .map_or("default", String::as_str);
+        let code_theme = query
+            .get("code")
+            .or(content.code.as_ref())
+            .map_or("vscode", String::as_str);
+
+        let mut metadata = content.metadata.clone();
+        if let Some(title) = &content.title {
+            metadata.insert("title".to_string(), title.clone());
+        }
+        if let Some(author) = &content.author {
+            metadata.insert("author".to_string(), author.clone());
+        }
+
+        let partials = Partials {
+            series: series::navigation(&content, &self.base_dir, None).await?,
+            ..Partials::default()
+        };
+        let html = manager.render_with_partials(
+            &content.content,
+            theme,
+            code_theme,
+            &metadata,
+            &partials,
+        )?;
+
+        // Resolve relative image paths against the file route
+        Ok(html.replacen("<head>", r#"<head><base href="/files/">"#, 1))
+    }
+
+    /// Reads a file below the article's directory.
+    async fn read_file(&self, relative_path: &str) -> Option<(Vec<u8>, String)> {
+        let path = utils::resolve_path(&self.base_dir, relative_path).ok()?;
+        let data = tokio::fs::read(&path).await.ok()?;
+        let mime = mime_guess::from_path(&path).first_or_octet_stream();
+        Some((data, mime.to_string()))
+    }
+}
+
+/// Serves a single HTTP request.
+async fn handle_connection(mut stream: TcpStream, state: &PreviewState) -> Result<()> {
+    let Some((path, query)) = read_request(&mut stream).await? else {
+        return write_response(&mut stream, "405 Method Not Allowed", "text/plain", b"").await;
+    };
+    debug!("Preview request: {path}");
+
+    match path.as_str() {
+        "/" => match state.render_page().await {
+            Ok(html) => write_html(&mut stream, "200 OK", &html).await,
+            Err(e) => write_html(&mut stream, "500 Internal Server Error", &error_page(&e)).await,
+        },
+        "/article" => match state.render_article(&query).await {
+            Ok(html) => write_html(&mut stream, "200 OK", &html).await,
+            Err(e) => write_html(&mut stream, "500 Internal Server Error", &error_page(&e)).await,
+        },
+        "/events" => stream_events(stream, state).await,
+        _ => {
+            let file = match path.strip_prefix("/files/") {
+                Some(relative_path) => state.read_file(relative_path).await,
+                None => None,
+            };
+            match file {
+                Some((data, mime)) => write_response(&mut stream, "200 OK", &mime, &data).await,
+                None => write_response(&mut stream, "404 Not Found", "text/plain", b"").await,
+            }
+        }
+    }
+}
+
+/// Reads a request head, returning the decoded path and query for `GET` requests.
+async fn read_request(stream: &mut TcpStream) -> Result<Option<(String, HashMap<String, String>)>> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 || head.len() + read > MAX_REQUEST_HEAD {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (Some("GET"), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Some((percent_decode(path, false), parse_query(query))))
+}
+
+/// Keeps an event stream open, sending a `reload` event after each file change.
+async fn stream_events(mut stream: TcpStream, state: &PreviewState) -> Result<()> {
+    let mut reload = state.reload.subscribe();
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+              Cache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+
+    loop {
+        let event: &[u8] = tokio::select! {
+            received = reload.recv() => match received {
+                Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => b"data: reload\n\n",
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            },
+            _ = tokio::time::sleep(EVENT_KEEP_ALIVE) => b": keep-alive\n\n",
+        };
+        // A failed write means the browser went away
+        if stream.write_all(event).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Polls watched files and notifies connected browsers when any of them change.
+async fn watch_files(state: Arc<PreviewState>) {
+    let mut last = snapshot(&state).await;
+    loop {
+        tokio::time::sleep(state.config.poll_interval).await;
+        let current = snapshot(&state).await;
+        if current != last {
+            info!("Change detected, reloading preview");
+            last = current;
+            // No receivers just means no browser is connected
+            let _ = state.reload.send(());
+        }
+    }
+}
+
+/// Modification times and sizes of every watched file.
+type Snapshot = Vec<(PathBuf, Option<SystemTime>, u64)>;
+
+/// Takes a snapshot of the article directory and the registered theme files.
+async fn snapshot(state: &PreviewState) -> Snapshot {
+    let base_dir = state.base_dir.clone();
+    let theme_files: Vec<PathBuf> = state
+        .config
+        .theme_files
+        .iter()
+        .map(|(_, path)| path.clone())
+        .collect();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        collect_watched_files(&base_dir, &mut files);
+        files.extend(theme_files);
+
+        let mut snapshot: Snapshot = files
+            .into_iter()
+            .map(|path| {
+                let metadata = std::fs::metadata(&path).ok();
+                let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+                let size = metadata.map_or(0, |m| m.len());
+                (path, modified, size)
+            })
+            .collect();
+        snapshot.sort();
+        snapshot
+    })
+    .await;
+
+    result.unwrap_or_else(|e| {
+        warn!("Failed to scan preview files: {e}");
+        Vec::new()
+    })
+}
+
+/// Recursively collects files with watched extensions, skipping hidden and build directories.
+fn collect_watched_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            if !IGNORED_DIRS.contains(&name.as_ref()) {
+                collect_watched_files(&path, files);
+            }
+        } else if utils::get_file_extension(&path)
+            .is_some_and(|ext| WATCHED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+}
+
+/// Writes an HTML response.
+async fn write_html(stream: &mut TcpStream, status: &str, html: &str) -> Result<()> {
+    write_response(stream, status, "text/html; charset=utf-8", html.as_bytes()).await
+}
+
+/// Writes a complete response and closes the connection.
+async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<()> {
+    let head = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Renders an error so it shows up in the preview frame.
+fn error_page(error: &WeChatError) -> String {
+    let message = error
+        .to_string()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    format!(
+        r#"<!DOCTYPE html><html><body><pre style="white-space:pre-wrap;color:#c00">{message}</pre></body></html>"#
+    )
+}
+
+/// Parses a URL query string into decoded key/value pairs.
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key, true), percent_decode(value, true))
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes, and `+` as a space in query strings.
+fn percent_decode(input: &str, plus_as_space: bool) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| input.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], escaped) {
+            (_, Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (b'+', None) if plus_as_space => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            (byte, None) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn get(addr: SocketAddr, target: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.unwrap();
+        String::from_utf8_lossy(&response).into_owned()
+    }
+
+    #[test]
+    fn test_query_decoding() {
+        assert_eq!(
+            percent_decode("/files/%E5%9B%BE.png", false),
+            "/files/图.png"
+        );
+        assert_eq!(percent_decode("a+b%2", true), "a b%2");
+
+        let query = parse_query("theme=lapis&code=github-dark&empty");
+        assert_eq!(query["theme"], "lapis");
+        assert_eq!(query["code"], "github-dark");
+        assert_eq!(query["empty"], "");
+    }
+
+    #[tokio::test]
+    async fn test_preview_serves_and_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let article = dir.path().join("article.md");
+        std::fs::write(
+            &article,
+            "---\ntitle: 预览\ntheme: lapis\n---\n\n![图](img.png)\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("img.png"), b"png").unwrap();
+        std::fs::write(
+            dir.path().join("brand.css"),
+            "#wepub figcaption { color: #123456; }",
+        )
+        .unwrap();
+
+        let preview = PreviewServer::new(&article)
+            .addr(SocketAddr::from(([127, 0, 0, 1], 0)))
+            .theme_file("brand", dir.path().join("brand.css"))
+            .poll_interval(Duration::from_millis(20))
+            .bind()
+            .await
+            .unwrap();
+        let addr = preview.local_addr().unwrap();
+        tokio::spawn(preview.run());
+
+        let page = get(addr, "/").await;
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("width: 375px"));
+        assert!(page.contains(r#"<option value="lapis" selected>"#));
+        assert!(page.contains(r#"<option value="brand">"#));
+        assert!(page.contains(r#"<option value="github-dark">"#));
+
+        let html = get(addr, "/article?theme=brand&code=github").await;
+        assert!(html.contains(r#"<base href="/files/">"#));
+        assert!(html.contains("#123456"));
+        assert!(html.contains(r#"src="img.png""#));
+
+        let missing = get(addr, "/article?theme=missing").await;
+        assert!(missing.starts_with("HTTP/1.1 500"));
+        assert!(missing.contains("Theme not found: missing"));
+
+        let image = get(addr, "/files/img.png").await;
+        assert!(image.contains("Content-Type: image/png") && image.ends_with("png"));
+        assert!(
+            get(addr, "/files/../secret")
+                .await
+                .starts_with("HTTP/1.1 404")
+        );
+
+        let mut events = TcpStream::connect(addr).await.unwrap();
+        events
+            .write_all(b"GET /events HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let mut buffer = [0u8; 256];
+        let read = events.read(&mut buffer).await.unwrap();
+        assert!(String::from_utf8_lossy(&buffer[..read]).contains("text/event-stream"));
+
+        std::fs::write(&article, "# 已修改\n").unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut received = String::new();
+            while !received.contains("data: reload") {
+                let read = events.read(&mut buffer).await.unwrap();
+                received.push_str(&String::from_utf8_lossy(&buffer[..read]));
+            }
+        })
+        .await;
+        assert!(received.is_ok());
+    }
+}
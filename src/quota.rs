@@ -0,0 +1,185 @@
+//! API quota inspection and reset.
+//!
+//! WeChat counts the calls made to every API against a daily quota. This module wraps
+//! the APIs that report how much of a quota is used (`openapi/quota/get`) and that reset
+//! every quota of the account (`clear_quota`).
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::{WeChatClient, Result};
+//!
+//! # async fn example() -> Result<()> {
+//! let client = WeChatClient::new("app_id", "app_secret").await?;
+//!
+//! let info = client.quota().get_quota("/cgi-bin/draft/add").await?;
+//! println!("{} of {} calls left today", info.quota.remain, info.quota.daily_limit);
+//!
+//! if info.quota.remain == 0 {
+//!     // Limited to a few resets a month
+//!     client.quota().clear_quota("app_id").await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::http::{WeChatHttpClient, WeChatResponse};
+use crate::traits::TokenProvider;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Daily quota of an API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiQuota {
+    /// Calls allowed per day
+    pub daily_limit: u64,
+    /// Calls made today
+    pub used: u64,
+    /// Calls left today
+    pub remain: u64,
+}
+
+/// Short-term frequency limit of an API.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ApiRateLimit {
+    /// Calls allowed per window
+    pub call_count: u64,
+    /// Length of the window in seconds
+    pub refresh_second: u64,
+}
+
+/// Quota usage of an API, as returned by `openapi/quota/get`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct QuotaInfo {
+    /// Daily quota
+    pub quota: ApiQuota,
+    /// Frequency limit, for APIs that have one
+    #[serde(default)]
+    pub rate_limit: Option<ApiRateLimit>,
+}
+
+/// Client for the quota APIs.
+#[derive(Debug, Clone)]
+pub struct QuotaClient {
+    http_client: Arc<WeChatHttpClient>,
+    token_provider: Arc<dyn TokenProvider>,
+}
+
+impl QuotaClient {
+    /// Creates a new QuotaClient.
+    pub fn new(http_client: Arc<WeChatHttpClient>, token_provider: Arc<dyn TokenProvider>) -> Self {
+        Self {
+            http_client,
+            token_provider,
+        }
+    }
+
+    /// Fetches the daily quota of an API.
+    ///
+    /// `cgi_path` is the path of the API, such as `/cgi-bin/draft/add`.
+    ///
+    /// Endpoint: `/cgi-bin/openapi/quota/get`
+    pub async fn get_quota(&self, cgi_path: &str) -> Result<QuotaInfo> {
+        debug!("Fetching API quota of {cgi_path}");
+
+        let request = serde_json::json!({ "cgi_path": cgi_path });
+        let response = self
+            .http_client
+            .post_json_authorized(
+                "/cgi-bin/openapi/quota/get",
+                self.token_provider.as_ref(),
+                &request,
+            )
+            .await?;
+
+        let quota_response: WeChatResponse<QuotaInfo> = response.json().await?;
+        quota_response.into_result()
+    }
+
+    /// Resets the daily quotas of every API of the account.
+    ///
+    /// WeChat allows this only a few times a month. The client-side
+    /// [rate limits](crate::rate_limit) are reset as well.
+    ///
+    /// Endpoint: `/cgi-bin/clear_quota`
+    pub async fn clear_quota(&self, app_id: &str) -> Result<()> {
+        info!("Clearing API quotas of {app_id}");
+
+        let request = serde_json::json!({ "appid": app_id });
+        let response = self
+            .http_client
+            .post_json_authorized(
+                "/cgi-bin/clear_quota",
+                self.token_provider.as_ref(),
+                &request,
+            )
+            .await?;
+
+        let clear_response: WeChatResponse<serde_json::Value> = response.json().await?;
+        clear_response.into_result()?;
+
+        self.http_client.rate_limiter().reset();
+        info!("Cleared API quotas of {app_id}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeChatError;
+    use crate::config::{Config, HttpConfig, RateLimit, RateLimitConfig};
+    use crate::test_support::MockServer;
+    use crate::token_provider::StaticTokenProvider;
+
+    #[tokio::test]
+    async fn test_quota_apis() {
+        let server = MockServer::serve([
+            r#"{"errcode":0,"errmsg":"ok","quota":{"daily_limit":1000,"used":1000,"remain":0}}"#,
+            r#"{"errcode":45009,"errmsg":"reach max api daily quota limit"}"#,
+            r#"{"errcode":0,"errmsg":"ok"}"#,
+        ])
+        .await;
+        let config = Config::builder()
+            .http(HttpConfig::builder().base_url(server.url).build())
+            .rate_limit(
+                RateLimitConfig::builder()
+                    .limit("/cgi-bin/clear_quota", RateLimit::per_day(1))
+                    .build(),
+            )
+            .build();
+        let http_client = Arc::new(WeChatHttpClient::with_config(config).unwrap());
+        let quota = QuotaClient::new(
+            Arc::clone(&http_client),
+            Arc::new(StaticTokenProvider::new("token")),
+        );
+
+        let info = quota.get_quota("/cgi-bin/draft/add").await.unwrap();
+        assert_eq!(info.quota.daily_limit, 1000);
+        assert_eq!(info.quota.remain, 0);
+        assert_eq!(info.rate_limit, None);
+
+        // WeChat refusing the call is a quota error, not a retryable API error
+        let error = quota.clear_quota("wx1234567890").await.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded {
+                code: Some(45009),
+                ..
+            }
+        ));
+        assert!(!error.is_retryable());
+
+        // The client-side limit of one call a day is used up, so nothing is sent
+        let error = quota.clear_quota("wx1234567890").await.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::QuotaExceeded { code: None, .. }
+        ));
+
+        http_client.rate_limiter().reset();
+        quota.clear_quota("wx1234567890").await.unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+//! Client-side rate limiting of WeChat API calls.
+//!
+//! WeChat enforces daily call quotas per API (errcode 45009) and short-term frequency
+//! limits (errcode 45011). [`RateLimiter`] keeps a token bucket per endpoint, configured
+//! by [`RateLimitConfig`], so the SDK slows down before WeChat refuses calls:
+//!
+//! - A call whose bucket refills within `max_wait_secs` waits for it.
+//! - A call that would wait longer fails at once with [`WeChatError::QuotaExceeded`],
+//!   without being sent.
+//!
+//! Every [`WeChatHttpClient`](crate::http::WeChatHttpClient) has a limiter, shared by
+//! its clones and by every client built on it. Accounts of an
+//! [`AccountRegistry`](crate::accounts::AccountRegistry) each get their own limiter.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use wechat_pub_rs::config::{Config, RateLimit, RateLimitConfig};
+//!
+//! let config = Config::builder()
+//!     .rate_limit(
+//!         RateLimitConfig::builder()
+//!             .limit("/cgi-bin/media/uploadimg", RateLimit::per_minute(60))
+//!             .build(),
+//!     )
+//!     .build();
+//! ```
+
+use crate::config::{RateLimit, RateLimitConfig};
+use crate::error::{Result, WeChatError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::debug;
+
+/// Per-endpoint token buckets for WeChat API calls.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// Token bucket that refills continuously over its period.
+#[derive(Debug)]
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.calls as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        let rate = self.limit.calls as f64 / self.limit.period_secs as f64;
+        self.tokens = (self.tokens + elapsed * rate).min(self.limit.calls as f64);
+        self.updated = now;
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    fn take(&mut self, now: Instant) -> std::result::Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let rate = self.limit.calls as f64 / self.limit.period_secs as f64;
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+    }
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter with full buckets.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Gets the limit of an endpoint, if it is throttled.
+    pub fn limit(&self, endpoint: &str) -> Option<RateLimit> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.config.limits.get(endpoint).copied()
+    }
+
+    /// Takes a call from the bucket of `endpoint`, waiting up to `max_wait_secs` for it.
+    ///
+    /// Fails with [`WeChatError::QuotaExceeded`] when the bucket would take longer to
+    /// refill. Endpoints without a limit return at once.
+    pub async fn acquire(&self, endpoint: &str) -> Result<()> {
+        let Some(limit) = self.limit(endpoint) else {
+            return Ok(());
+        };
+        let max_wait = Duration::from_secs(self.config.max_wait_secs);
+
+        loop {
+            let wait = {
+                let mut buckets = self.buckets.lock().unwrap();
+                let bucket = buckets
+                    .entry(endpoint.to_string())
+                    .or_insert_with(|| Bucket::new(limit));
+                match bucket.take(Instant::now()) {
+                    Ok(()) => return Ok(()),
+                    Err(wait) => wait,
+                }
+            };
+
+            if wait > max_wait {
+                return Err(WeChatError::QuotaExceeded {
+                    code: None,
+                    message: format!(
+                        "client-side limit of {} calls per {}s to {endpoint} reached, next call allowed in {}s",
+                        limit.calls,
+                        limit.period_secs,
+                        wait.as_secs().max(1)
+                    ),
+                });
+            }
+
+            debug!("Rate limit reached for {endpoint}, waiting {wait:?}");
+            sleep(wait).await;
+        }
+    }
+
+    /// Gets the number of calls to `endpoint` that can be made without waiting.
+    ///
+    /// Returns `None` for endpoints without a limit.
+    pub fn remaining(&self, endpoint: &str) -> Option<u32> {
+        let limit = self.limit(endpoint)?;
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Bucket::new(limit));
+        bucket.refill(Instant::now());
+        Some(bucket.tokens as u32)
+    }
+
+    /// Empties the bucket of `endpoint`, such as after WeChat reported its quota used up.
+    pub fn exhaust(&self, endpoint: &str) {
+        if let Some(limit) = self.limit(endpoint) {
+            let mut buckets = self.buckets.lock().unwrap();
+            let bucket = buckets
+                .entry(endpoint.to_string())
+                .or_insert_with(|| Bucket::new(limit));
+            bucket.tokens = 0.0;
+            bucket.updated = Instant::now();
+        }
+    }
+
+    /// Refills every bucket, such as after the quotas were reset with `clear_quota`.
+    pub fn reset(&self) {
+        self.buckets.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit: RateLimit, max_wait_secs: u64) -> RateLimiter {
+        RateLimiter::new(
+            RateLimitConfig::builder()
+                .max_wait_secs(max_wait_secs)
+                .limit("/cgi-bin/draft/add", limit)
+                .build(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_quota_exceeded_without_waiting() {
+        let limiter = limiter(RateLimit::per_day(2), 60);
+
+        limiter.acquire("/cgi-bin/draft/add").await.unwrap();
+        assert_eq!(limiter.remaining("/cgi-bin/draft/add"), Some(1));
+        limiter.acquire("/cgi-bin/draft/add").await.unwrap();
+
+        // The bucket refills over 12 hours, far past the longest wait
+        let error = limiter.acquire("/cgi-bin/draft/add").await.unwrap_err();
+        assert!(matches!(
+            error,
+            WeChatError::QuotaExceeded { code: None, .. }
+        ));
+        assert!(!error.is_retryable());
+        assert!(error.to_string().contains("/cgi-bin/draft/add"));
+
+        // Endpoints without a limit are not throttled
+        assert_eq!(limiter.remaining("/cgi-bin/draft/get"), None);
+        limiter.acquire("/cgi-bin/draft/get").await.unwrap();
+
+        limiter.reset();
+        assert_eq!(limiter.remaining("/cgi-bin/draft/add"), Some(2));
+        limiter.exhaust("/cgi-bin/draft/add");
+        assert_eq!(limiter.remaining("/cgi-bin/draft/add"), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_short_refills() {
+        let limit = RateLimit {
+            calls: 20,
+            period_secs: 1,
+        };
+        let limiter = limiter(limit, 60);
+        for _ in 0..20 {
+            limiter.acquire("/cgi-bin/draft/add").await.unwrap();
+        }
+
+        // A call refills every 50ms, well within the longest wait
+        let started = Instant::now();
+        limiter.acquire("/cgi-bin/draft/add").await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_disabled() {
+        let limiter = RateLimiter::new(
+            RateLimitConfig::builder()
+                .enabled(false)
+                .limit("/cgi-bin/draft/add", RateLimit::per_day(1))
+                .build(),
+        );
+        for _ in 0..3 {
+            limiter.acquire("/cgi-bin/draft/add").await.unwrap();
+        }
+        assert_eq!(limiter.remaining("/cgi-bin/draft/add"), None);
+    }
+}
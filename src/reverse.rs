@@ -0,0 +1,722 @@
+//! Reverse conversion from WeChat article HTML back to markdown.
+//!
+//! Articles edited in the MP console drift away from their markdown source.
+//! [`WeChatClient::pull_draft`](crate::WeChatClient::pull_draft) and
+//! [`WeChatClient::pull_published`](crate::WeChatClient::pull_published) fetch an
+//! article, download its images into an `images/` folder and write the content back
+//! as markdown, so console edits can be committed alongside the original source.
+//!
+//! The conversion understands the HTML this crate renders: code blocks with their
+//! `<br/>` line breaks, titles, line numbers and highlighted lines are turned back into
+//! fenced blocks, and callouts, figures, footnotes and the table of contents are
+//! restored to their markdown syntax. Inline styles are dropped, since the theme
+//! recreates them on the next upload.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::WeChatClient;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let client = WeChatClient::new("app_id", "app_secret").await?;
+//! let pulled = client.pull_draft("draft_media_id", "articles/post.md", false).await?;
+//! println!("Wrote {} with {} images", pulled.markdown_path.display(), pulled.images.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::upload::Article;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+static IMG_SELECTOR: LazyLock<Selector> = LazyLock::new(|| Selector::parse("img").unwrap());
+
+/// Text of the scroll hint rendered below wide code blocks.
+const CODE_HINT_TEXT: &str = "左右滑动查看完整代码";
+
+/// Marker that starts a wrapped continuation row in a code block.
+const CODE_CONTINUATION_TEXT: &str = "↪ ";
+
+/// An article written back to disk as markdown.
+#[derive(Debug, Clone)]
+pub struct PulledArticle {
+    /// Article title
+    pub title: String,
+    /// Path of the written markdown file
+    pub markdown_path: PathBuf,
+    /// Downloaded images, including the cover
+    pub images: Vec<PathBuf>,
+}
+
+/// Returns the URLs of the images in article HTML, in document order and deduplicated.
+///
+/// WeChat lazy-loads images through `data-src`, which takes precedence over `src`.
+/// Embedded data URIs are skipped.
+pub fn image_urls(html: &str) -> Vec<String> {
+    let fragment = Html::parse_fragment(html);
+    let mut urls: Vec<String> = Vec::new();
+
+    for img in fragment.select(&IMG_SELECTOR) {
+        if let Some(url) = image_source(img)
+            && (url.starts_with("http://") || url.starts_with("https://"))
+            && !urls.iter().any(|existing| existing == url)
+        {
+            urls.push(url.to_string());
+        }
+    }
+
+    urls
+}
+
+/// Converts article HTML into markdown.
+///
+/// Image URLs found in `image_paths` are replaced by their local paths; others are
+/// kept as they are.
+pub fn html_to_markdown(html: &str, image_paths: &HashMap<String, String>) -> String {
+    let fragment = Html::parse_fragment(html);
+    let converter = Converter { image_paths };
+
+    let mut blocks = Vec::new();
+    converter.blocks(fragment.root_element(), &mut blocks);
+
+    let mut markdown = blocks.join("\n\n");
+    markdown.push('\n');
+    markdown
+}
+
+/// Builds the frontmatter for an article, using the keys the upload pipeline reads.
+///
+/// Flags are only written when they differ from the upload defaults.
+pub fn frontmatter(article: &Article, cover: Option<&str>) -> String {
+    let mut lines = vec!["---".to_string(), quoted("title", &article.title)];
+
+    if !article.author.is_empty() {
+        lines.push(quoted("author", &article.author));
+    }
+    if !article.digest.is_empty() {
+        lines.push(quoted("description", &article.digest));
+    }
+    if let Some(cover) = cover {
+        lines.push(format!("cover: {cover}"));
+    }
+    if let Some(url) = article.content_source_url.as_deref()
+        && !url.is_empty()
+    {
+        lines.push(format!("source_url: {url}"));
+    }
+    if article.show_cover_pic == 0 {
+        lines.push("show_cover: false".to_string());
+    }
+    if article.need_open_comment == 1 {
+        lines.push("comments: true".to_string());
+    }
+    if article.only_fans_can_comment == 1 {
+        lines.push("fans_only_comments: true".to_string());
+    }
+
+    lines.push("---".to_string());
+    lines.join("\n") + "\n"
+}
+
+/// Formats a quoted frontmatter value on a single line.
+fn quoted(key: &str, value: &str) -> String {
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    format!("{key}: \"{value}\"")
+}
+
+/// Walks the parsed HTML and emits markdown blocks.
+struct Converter<'a> {
+    image_paths: &'a HashMap<String, String>,
+}
+
+impl Converter<'_> {
+    /// Converts the children of an element into blocks, grouping loose inline
+    /// content into paragraphs.
+    fn blocks(&self, element: ElementRef, out: &mut Vec<String>) {
+        let mut inline = String::new();
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => inline.push_str(&escape_text(&collapse_whitespace(text))),
+                Node::Element(_) => {
+                    let child = ElementRef::wrap(child).unwrap();
+                    if is_block(child.value().name()) {
+                        push_paragraph(&mut inline, out);
+                        self.block(child, out);
+                    } else {
+                        inline.push_str(&self.inline(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        push_paragraph(&mut inline, out);
+    }
+
+    fn block(&self, element: ElementRef, out: &mut Vec<String>) {
+        let name = element.value().name();
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let text = self.inline_children(element);
+                let text = text.trim();
+                if !text.is_empty() {
+                    out.push(format!("{} {text}", "#".repeat(level)));
+                }
+            }
+            "p" => {
+                let text = self.inline_children(element);
+                let text = text.trim();
+                if !text.is_empty() {
+                    out.push(text.to_string());
+                }
+            }
+            "pre" => out.push(self.code_block(element)),
+            "blockquote" => {
+                let mut inner = Vec::new();
+                self.blocks(element, &mut inner);
+                out.push(prefix_lines(&inner.join("\n\n"), "> ", ">"));
+            }
+            "ul" | "ol" => out.push(self.list(element, name == "ol")),
+            "table" => out.push(self.table(element)),
+            "figure" => out.push(self.figure(element)),
+            "img" => out.push(self.image(element, None)),
+            "hr" => out.push("---".to_string()),
+            _ if has_class(element, "toc") => out.push("[TOC]".to_string()),
+            _ if has_class(element, "markdown-alert") => out.push(self.alert(element)),
+            _ if has_class(element, "footnotes") => out.extend(self.footnotes(element)),
+            _ if has_class(element, "image-grid") => {
+                let figures: Vec<String> = element
+                    .child_elements()
+                    .map(|child| self.figure(child))
+                    .collect();
+                out.push(figures.join(" "));
+            }
+            _ => self.blocks(element, out),
+        }
+    }
+
+    fn inline(&self, element: ElementRef) -> String {
+        if is_decoration(element) {
+            return String::new();
+        }
+
+        match element.value().name() {
+            "strong" | "b" => emphasize(&self.inline_children(element), "**"),
+            "em" | "i" => emphasize(&self.inline_children(element), "*"),
+            "del" | "s" | "strike" => emphasize(&self.inline_children(element), "~~"),
+            "code" => inline_code(&element.text().collect::<String>()),
+            "br" => "\\\n".to_string(),
+            "img" => self.image(element, None),
+            "sup" if has_class(element, "footnote-ref") => {
+                let label = element.text().collect::<String>();
+                let id = element
+                    .child_elements()
+                    .find_map(|link| link.attr("href"))
+                    .and_then(|href| href.strip_prefix("#fn-"))
+                    .unwrap_or(label.trim());
+                format!("[^{id}]")
+            }
+            "a" => {
+                let text = self.inline_children(element);
+                match element.attr("href").filter(|href| !href.is_empty()) {
+                    Some(href) => match element.attr("title").filter(|t| !t.is_empty()) {
+                        Some(title) => format!("[{text}]({href} \"{title}\")"),
+                        None => format!("[{text}]({href})"),
+                    },
+                    None => text,
+                }
+            }
+            _ => self.inline_children(element),
+        }
+    }
+
+    fn inline_children(&self, element: ElementRef) -> String {
+        let mut text = String::new();
+
+        for child in element.children() {
+            match child.value() {
+                Node::Text(t) => text.push_str(&escape_text(&collapse_whitespace(t))),
+                Node::Element(_) => {
+                    let child = ElementRef::wrap(child).unwrap();
+                    if is_block(child.value().name()) {
+                        // Block content inside an inline context, such as a paragraph
+                        // in a table cell, is flattened onto one line
+                        let mut blocks = Vec::new();
+                        self.block(child, &mut blocks);
+                        text.push_str(&blocks.join(" "));
+                    } else {
+                        text.push_str(&self.inline(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        text
+    }
+
+    fn image(&self, img: ElementRef, caption: Option<&str>) -> String {
+        let url = image_source(img).unwrap_or_default();
+        let path = self.image_paths.get(url).map_or(url, String::as_str);
+        let alt = img.attr("alt").unwrap_or_default().replace(['[', ']'], "");
+
+        match caption.or(img.attr("title")).map(str::trim) {
+            Some(caption) if !caption.is_empty() && caption != alt => {
+                format!("![{alt}]({path} \"{}\")", caption.replace('"', "'"))
+            }
+            _ => format!("![{alt}]({path})"),
+        }
+    }
+
+    /// Converts a figure back into an image, keeping a caption that differs from the
+    /// alt text as the image title.
+    fn figure(&self, figure: ElementRef) -> String {
+        let caption = figure
+            .child_elements()
+            .find(|child| child.value().name() == "figcaption")
+            .map(|caption| self.inline_children(caption));
+
+        figure
+            .descendent_elements()
+            .filter(|element| element.value().name() == "img")
+            .map(|img| self.image(img, caption.as_deref()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn list(&self, list: ElementRef, ordered: bool) -> String {
+        let start = list
+            .attr("start")
+            .and_then(|start| start.parse::<usize>().ok())
+            .unwrap_or(1);
+        let items: Vec<ElementRef> = list
+            .child_elements()
+            .filter(|child| child.value().name() == "li")
+            .collect();
+        let loose = items
+            .iter()
+            .any(|item| item.child_elements().any(|c| c.value().name() == "p"));
+
+        let mut rendered = Vec::new();
+        for (number, item) in (start..).zip(items) {
+            let marker = if ordered {
+                format!("{number}.")
+            } else {
+                "-".to_string()
+            };
+
+            let mut blocks = Vec::new();
+            self.blocks(item, &mut blocks);
+            let body = blocks.join(if loose { "\n\n" } else { "\n" });
+
+            let indent = " ".repeat(marker.len() + 1);
+            let body = prefix_lines(&body, &indent, "");
+            rendered.push(format!("{marker} {}", body.trim_start()));
+        }
+
+        rendered.join(if loose { "\n\n" } else { "\n" })
+    }
+
+    fn table(&self, table: ElementRef) -> String {
+        let rows: Vec<ElementRef> = table
+            .descendent_elements()
+            .filter(|element| element.value().name() == "tr")
+            .collect();
+        let Some(header) = rows.first() else {
+            return String::new();
+        };
+
+        let render_row = |row: &ElementRef| {
+            let cells: Vec<String> = table_cells(*row)
+                .iter()
+                .map(|cell| self.inline_children(*cell).trim().replace('|', "\\|"))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        };
+
+        let alignments: Vec<&str> = table_cells(*header)
+            .iter()
+            .map(|cell| match cell_alignment(*cell) {
+                Some("center") => ":-:",
+                Some("left") => ":--",
+                Some("right") => "--:",
+                _ => "---",
+            })
+            .collect();
+
+        let mut lines = vec![
+            render_row(header),
+            format!("| {} |", alignments.join(" | ")),
+        ];
+        lines.extend(rows[1..].iter().map(render_row));
+        lines.join("\n")
+    }
+
+    fn alert(&self, alert: ElementRef) -> String {
+        let kind = alert
+            .value()
+            .classes()
+            .find_map(|class| class.strip_prefix("markdown-alert-"))
+            .filter(|kind| *kind != "title")
+            .unwrap_or("note")
+            .to_uppercase();
+
+        let mut body = Vec::new();
+        for child in alert.child_elements() {
+            if !has_class(child, "markdown-alert-title") {
+                self.block(child, &mut body);
+            }
+        }
+
+        prefix_lines(&format!("[!{kind}]\n{}", body.join("\n\n")), "> ", ">")
+    }
+
+    fn footnotes(&self, section: ElementRef) -> Vec<String> {
+        section
+            .descendent_elements()
+            .filter(|element| element.value().name() == "li")
+            .map(|item| {
+                let id = item
+                    .attr("id")
+                    .and_then(|id| id.strip_prefix("fn-"))
+                    .unwrap_or_default();
+                let mut blocks = Vec::new();
+                self.blocks(item, &mut blocks);
+                let body = prefix_lines(&blocks.join("\n\n"), "    ", "");
+                format!("[^{id}]: {}", body.trim())
+            })
+            .collect()
+    }
+
+    /// Recovers a fenced code block, including its title, line numbers and
+    /// highlighted lines, from the `<br/>`-separated rows WeChat receives.
+    fn code_block(&self, pre: ElementRef) -> String {
+        let mut code = CodeText::default();
+        let mut language = "";
+        let mut title = None;
+
+        for child in pre.child_elements() {
+            if has_class(child, "code-title") {
+                title = Some(child.text().collect::<String>());
+            } else {
+                if child.value().name() == "code" {
+                    language = child
+                        .value()
+                        .classes()
+                        .find_map(|class| class.strip_prefix("language-"))
+                        .unwrap_or_default();
+                }
+                code.walk(child);
+            }
+        }
+
+        let text = code.text.trim_end_matches('\n');
+        let mut info = language.to_string();
+        if let Some(title) = title {
+            info.push_str(&format!(" title=\"{}\"", title.trim()));
+        }
+        if !code.highlighted.is_empty() {
+            info.push_str(&format!(" {{{}}}", line_ranges(&code.highlighted)));
+        }
+        if code.numbered {
+            info.push_str(" showLineNumbers");
+        }
+
+        let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest_run.max(2) + 1);
+
+        format!("{fence}{}\n{text}\n{fence}", info.trim_start())
+    }
+}
+
+/// Plain text of a code block, collected row by row.
+#[derive(Default)]
+struct CodeText {
+    text: String,
+    line: usize,
+    highlighted: Vec<usize>,
+    numbered: bool,
+}
+
+impl CodeText {
+    fn walk(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => {
+                    self.line += text.matches('\n').count();
+                    self.text.push_str(text);
+                }
+                Node::Element(el) => {
+                    let child = ElementRef::wrap(child).unwrap();
+                    let text: String = child.text().collect();
+
+                    if el.name() == "br" {
+                        self.text.push('\n');
+                        self.line += 1;
+                    } else if has_class(child, "code-line-number") {
+                        self.numbered = true;
+                    } else if text.contains(CODE_HINT_TEXT) && child.children().count() == 1 {
+                        // The scroll hint below wide code blocks is not code
+                    } else if text == CODE_CONTINUATION_TEXT {
+                        // A wrapped row continues the previous line
+                        if self.text.ends_with('\n') {
+                            self.text.pop();
+                            self.line -= 1;
+                        }
+                    } else {
+                        self.walk(child);
+                        if has_class(child, "code-line-highlight")
+                            && self.highlighted.last() != Some(&(self.line + 1))
+                        {
+                            self.highlighted.push(self.line + 1);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Formats sorted line numbers as ranges such as `2,4-6`.
+fn line_ranges(lines: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+
+    for &line in lines {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == line => *end = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The image URL, preferring WeChat's lazy-loading `data-src`.
+fn image_source(img: ElementRef<'_>) -> Option<&str> {
+    img.attr("data-src")
+        .or_else(|| img.attr("src"))
+        .filter(|src| !src.is_empty())
+}
+
+fn table_cells(row: ElementRef) -> Vec<ElementRef> {
+    row.child_elements()
+        .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+        .collect()
+}
+
+fn cell_alignment(cell: ElementRef<'_>) -> Option<&str> {
+    cell.attr("align").or_else(|| {
+        cell.attr("style")?.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            (property.trim() == "text-align").then(|| value.trim())
+        })
+    })
+}
+
+fn has_class(element: ElementRef, class: &str) -> bool {
+    element.value().classes().any(|c| c == class)
+}
+
+/// Elements added by the renderer that have no markdown counterpart.
+fn is_decoration(element: ElementRef) -> bool {
+    [
+        "heading-number",
+        "figure-number",
+        "markdown-alert-icon",
+        "footnote-backref",
+    ]
+    .iter()
+    .any(|class| has_class(element, class))
+}
+
+pub(crate) fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "address"
+            | "article"
+            | "aside"
+            | "blockquote"
+            | "div"
+            | "figure"
+            | "footer"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "header"
+            | "hr"
+            | "ol"
+            | "p"
+            | "pre"
+            | "section"
+            | "table"
+            | "ul"
+    )
+}
+
+fn push_paragraph(inline: &mut String, out: &mut Vec<String>) {
+    let text = inline.trim();
+    if !text.is_empty() {
+        out.push(text.to_string());
+    }
+    inline.clear();
+}
+
+/// Collapses whitespace runs the way HTML rendering does.
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_space = false;
+
+    for ch in text.chars() {
+        if ch.is_ascii_whitespace() {
+            if !in_space {
+                collapsed.push(' ');
+            }
+            in_space = true;
+        } else {
+            collapsed.push(ch);
+            in_space = false;
+        }
+    }
+
+    collapsed
+}
+
+/// Escapes characters that would otherwise start markdown syntax.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '`' | '*' | '_' | '[' | ']' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Wraps text in emphasis markers, keeping surrounding spaces outside them.
+fn emphasize(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+
+    let leading = if text.starts_with(' ') { " " } else { "" };
+    let trailing = if text.ends_with(' ') { " " } else { "" };
+    format!("{leading}{marker}{trimmed}{marker}{trailing}")
+}
+
+fn inline_code(code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let ticks = "`".repeat(longest_run + 1);
+
+    if longest_run > 0 {
+        format!("{ticks} {code} {ticks}")
+    } else {
+        format!("{ticks}{code}{ticks}")
+    }
+}
+
+/// Prefixes every line, using `empty_prefix` for blank lines.
+fn prefix_lines(text: &str, prefix: &str, empty_prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                empty_prefix.to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_block_recovery() {
+        let html = r#"<pre><span class="code-title">main.rs</span><code class="language-rust"><span style="display: block"><span class="code-line-number">1</span><span>fn </span><span>main</span><span>() {</span><br><span class="code-line-highlight"><span class="code-line-number">2</span><span>    </span><span>let</span><span> x = "&lt;a&gt;";</span></span><br><span class="code-line-number">3</span><span>}</span></span></code></pre>"#;
+
+        assert_eq!(
+            html_to_markdown(html, &HashMap::new()),
+            "```rust title=\"main.rs\" {2} showLineNumbers\nfn main() {\n    let x = \"<a>\";\n}\n```\n"
+        );
+
+        // Wrapped rows and the scroll hint are folded back into the original lines
+        let html = r#"<pre><code><span>let long = 1;<br/>let wrapped = </span><br/><span style="color: #999999; user-select: none;">↪ </span><span>value;<br/></span><span style="display: block">← 左右滑动查看完整代码 →</span></code></pre>"#;
+        assert_eq!(
+            html_to_markdown(html, &HashMap::new()),
+            "```\nlet long = 1;\nlet wrapped = value;\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_html_to_markdown() {
+        let html = r##"<section id="wepub" style="color: red">
+            <h2><span class="heading-number">1</span>标题</h2>
+            <p>Some <strong>bold </strong>and <a href="https://example.com" title="T">link</a> with <code>a_b</code>.<sup class="footnote-ref"><a href="#fn-1" id="fnref-1">1</a></sup></p>
+            <ul><li>one</li><li>two<ol><li>nested</li></ol></li></ul>
+            <div class="markdown-alert markdown-alert-tip"><p class="markdown-alert-title"><span class="markdown-alert-icon">💡</span>Tip</p><p>a tip</p></div>
+            <figure><img data-src="https://mmbiz.qpic.cn/a/640?wx_fmt=png" src="placeholder" alt="cat"><figcaption><span class="figure-number">图 1</span>A cat</figcaption></figure>
+            <table><thead><tr><th>a</th><th style="text-align: center">b</th></tr></thead><tbody><tr><td>1|2</td><td>3</td></tr></tbody></table>
+            <section class="footnotes"><ol><li id="fn-1"><p>Foot. <a class="footnote-backref" href="#fnref-1">↩</a></p></li></ol></section>
+        </section>"##;
+        let paths = HashMap::from([(
+            "https://mmbiz.qpic.cn/a/640?wx_fmt=png".to_string(),
+            "images/cat.png".to_string(),
+        )]);
+
+        let markdown = html_to_markdown(html, &paths);
+        assert_eq!(
+            markdown,
+            "## 标题\n\n\
+             Some **bold** and [link](https://example.com \"T\") with `a_b`.[^1]\n\n\
+             - one\n- two\n  1. nested\n\n\
+             > [!TIP]\n> a tip\n\n\
+             ![cat](images/cat.png \"A cat\")\n\n\
+             | a | b |\n| --- | :-: |\n| 1\\|2 | 3 |\n\n\
+             [^1]: Foot.\n"
+        );
+        assert_eq!(
+            image_urls(html),
+            vec!["https://mmbiz.qpic.cn/a/640?wx_fmt=png"]
+        );
+    }
+
+    #[test]
+    fn test_frontmatter() {
+        let mut article = Article::new(
+            "标题: 副标题".to_string(),
+            "作者".to_string(),
+            String::new(),
+        )
+        .with_digest("第一行\n第二行".to_string())
+        .with_comments(true, false);
+        article.show_cover_pic = 0;
+
+        assert_eq!(
+            frontmatter(&article, Some("images/cover.jpg")),
+            "---\ntitle: \"标题: 副标题\"\nauthor: \"作者\"\ndescription: \"第一行 第二行\"\ncover: images/cover.jpg\nshow_cover: false\ncomments: true\n---\n"
+        );
+    }
+}
@@ -0,0 +1,433 @@
+//! Navigation boxes for multi-part article series.
+//!
+//! An article joins a series with a `series` block in its frontmatter:
+//!
+//! ```markdown
+//! ---
+//! title: "所有权与借用"
+//! series:
+//!   name: "Rust 入门"
+//!   part: 2
+//! ---
+//! ```
+//!
+//! Rendering appends a navigation box below the article body that lists every part.
+//! Parts are found by scanning the markdown files next to the article for the same
+//! series name. Links come from a [`SeriesRecord`] kept in `.series.json` in the same
+//! directory, or `.series.{account}.json` for clients of an
+//! [`AccountRegistry`](crate::accounts::AccountRegistry): uploads record each part's draft, and
+//! [`WeChatClient::record_series_published`](crate::WeChatClient::record_series_published)
+//! adds the permanent link once a part is published. Unpublished parts are listed
+//! without a link.
+
+use crate::error::{Result, WeChatError};
+use crate::markdown::{MarkdownContent, MarkdownParser};
+use crate::theme::escape_html;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// File name of the series record, kept next to the articles.
+pub const SERIES_RECORD_FILE: &str = ".series.json";
+
+/// The series an article belongs to, from its `series.name` and `series.part` keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesInfo {
+    /// Name shared by every part of the series
+    pub name: String,
+    /// One-based part number
+    pub part: u32,
+}
+
+impl SeriesInfo {
+    /// Reads the series block from frontmatter metadata.
+    ///
+    /// Returns `Ok(None)` for articles without a series, and an error when the block
+    /// is incomplete or the part is not a positive number.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Result<Option<Self>> {
+        let name = metadata.get("series.name").map(|name| name.trim());
+        let part = metadata.get("series.part").map(|part| part.trim());
+
+        match (name, part) {
+            (None, None) => Ok(None),
+            (Some(name), Some(part)) if !name.is_empty() => match part.parse::<u32>() {
+                Ok(part) if part > 0 => Ok(Some(Self {
+                    name: name.to_string(),
+                    part,
+                })),
+                _ => Err(WeChatError::config_error(format!(
+                    "series.part must be a positive number, got '{part}'"
+                ))),
+            },
+            _ => Err(WeChatError::config_error(
+                "A series needs both series.name and series.part",
+            )),
+        }
+    }
+}
+
+/// One part of a series, as listed in the navigation box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesPart {
+    /// One-based part number
+    pub part: u32,
+    /// Article title
+    pub title: String,
+    /// Permanent link, once the part is published
+    pub url: Option<String>,
+}
+
+/// Every known part of a series, seen from one of its articles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesIndex {
+    /// Series name
+    pub name: String,
+    /// Part number of the article being rendered
+    pub current: u32,
+    /// Parts ordered by part number
+    pub parts: Vec<SeriesPart>,
+}
+
+impl SeriesIndex {
+    /// Builds the index for an article from its sibling markdown files and the series record.
+    ///
+    /// Parts in the record whose markdown lives elsewhere are listed too. The article's
+    /// own title always wins over another file claiming the same part.
+    pub async fn load(
+        content: &MarkdownContent,
+        dir: &Path,
+        account: Option<&str>,
+    ) -> Result<Option<Self>> {
+        let Some(info) = SeriesInfo::from_metadata(&content.metadata)? else {
+            return Ok(None);
+        };
+
+        let mut titles: BTreeMap<u32, String> = BTreeMap::new();
+        for (part, title, path) in scan_parts(dir, &info.name).await? {
+            if part == info.part {
+                continue;
+            }
+            if titles.contains_key(&part) {
+                warn!(
+                    "Series '{}' part {part} is claimed more than once, ignoring {}",
+                    info.name,
+                    path.display()
+                );
+                continue;
+            }
+            titles.insert(part, title);
+        }
+        titles.insert(info.part, content.title.clone().unwrap_or_default());
+
+        let record = SeriesRecord::load(dir, account).await?;
+        let mut parts: BTreeMap<u32, SeriesPart> = BTreeMap::new();
+        if let Some(published) = record.series.get(&info.name) {
+            for (&part, entry) in published {
+                parts.insert(
+                    part,
+                    SeriesPart {
+                        part,
+                        title: entry.title.clone(),
+                        url: entry.url.clone(),
+                    },
+                );
+            }
+        }
+        for (part, title) in titles {
+            let entry = parts.entry(part).or_insert_with(|| SeriesPart {
+                part,
+                title: String::new(),
+                url: None,
+            });
+            entry.title = title;
+        }
+
+        Ok(Some(Self {
+            name: info.name,
+            current: info.part,
+            parts: parts.into_values().collect(),
+        }))
+    }
+
+    /// Renders the navigation box, styled by the theme's `.series-nav` rules.
+    ///
+    /// The current part is highlighted, published parts link to their article and the
+    /// remaining parts are marked as upcoming.
+    pub fn render(&self) -> String {
+        let mut html = format!(
+            r#"<section class="series-nav"><p class="series-title">系列：{}（共 {} 篇）</p>"#,
+            escape_html(&self.name),
+            self.parts.len()
+        );
+        for part in &self.parts {
+            let label = format!("第 {} 篇：{}", part.part, escape_html(&part.title));
+            let item = if part.part == self.current {
+                format!(r#"<p class="series-item series-current">{label}（本篇）</p>"#)
+            } else if let Some(url) = &part.url {
+                format!(
+                    r#"<p class="series-item"><a href="{}">{label}</a></p>"#,
+                    escape_html(url)
+                )
+            } else {
+                format!(r#"<p class="series-item series-upcoming">{label}（待发布）</p>"#)
+            };
+            html.push_str(&item);
+        }
+        html.push_str("</section>\n");
+        html
+    }
+}
+
+/// Renders the navigation box for an article, if it belongs to a series.
+///
+/// `account` selects the series record of an account; see [`SeriesRecord::path_for`].
+pub async fn navigation(
+    content: &MarkdownContent,
+    dir: &Path,
+    account: Option<&str>,
+) -> Result<Option<String>> {
+    Ok(SeriesIndex::load(content, dir, account)
+        .await?
+        .map(|index| index.render()))
+}
+
+/// A part recorded in the series record.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedPart {
+    /// Article title when it was last uploaded
+    pub title: String,
+    /// Media ID of the draft it was uploaded as
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_id: Option<String>,
+    /// Permanent link of the published article
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Drafts and published links of the series in a directory, stored in `.series.json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesRecord {
+    /// Recorded parts by series name and part number
+    #[serde(default)]
+    pub series: BTreeMap<String, BTreeMap<u32, RecordedPart>>,
+}
+
+impl SeriesRecord {
+    /// The record file for a directory of articles.
+    ///
+    /// Drafts and links differ between official accounts, so records kept per account
+    /// are named `.series.{account}.json` instead.
+    pub fn path_for(dir: &Path, account: Option<&str>) -> PathBuf {
+        match account {
+            Some(account) => dir.join(format!(".series.{account}.json")),
+            None => dir.join(SERIES_RECORD_FILE),
+        }
+    }
+
+    /// Loads the record of a directory; a missing file gives an empty record.
+    pub async fn load(dir: &Path, account: Option<&str>) -> Result<Self> {
+        let path = Self::path_for(dir, account);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(WeChatError::file_error(
+                    path.display().to_string(),
+                    format!("Failed to read series record: {e}"),
+                ));
+            }
+        };
+        serde_json::from_slice(&data).map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Invalid series record: {e}"),
+            )
+        })
+    }
+
+    /// Saves the record into a directory.
+    pub async fn save(&self, dir: &Path, account: Option<&str>) -> Result<()> {
+        let path = Self::path_for(dir, account);
+        let data = serde_json::to_vec_pretty(self)?;
+        tokio::fs::write(&path, data).await.map_err(|e| {
+            WeChatError::file_error(
+                path.display().to_string(),
+                format!("Failed to write series record: {e}"),
+            )
+        })
+    }
+
+    /// Records the draft a part was uploaded as.
+    ///
+    /// A new draft for a part replaces the previous one.
+    pub fn set_draft(&mut self, info: &SeriesInfo, title: &str, media_id: &str) {
+        let entry = self.part_mut(info);
+        entry.title = title.to_string();
+        entry.media_id = Some(media_id.to_string());
+    }
+
+    /// Records the permanent link of a published part.
+    pub fn set_url(&mut self, info: &SeriesInfo, title: &str, url: &str) {
+        let entry = self.part_mut(info);
+        entry.title = title.to_string();
+        entry.url = Some(url.to_string());
+    }
+
+    /// Looks up a recorded part.
+    pub fn part(&self, name: &str, part: u32) -> Option<&RecordedPart> {
+        self.series.get(name)?.get(&part)
+    }
+
+    fn part_mut(&mut self, info: &SeriesInfo) -> &mut RecordedPart {
+        self.series
+            .entry(info.name.clone())
+            .or_default()
+            .entry(info.part)
+            .or_default()
+    }
+}
+
+/// Finds the markdown files in `dir` that belong to the named series.
+///
+/// Files that fail to parse or carry an invalid series block are skipped.
+async fn scan_parts(dir: &Path, name: &str) -> Result<Vec<(u32, String, PathBuf)>> {
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(|e| {
+        WeChatError::file_error(
+            dir.display().to_string(),
+            format!("Failed to list series directory: {e}"),
+        )
+    })?;
+
+    let mut paths = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "md") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let parser = MarkdownParser::new();
+    let mut parts = Vec::new();
+    for path in paths {
+        let content = match parser.parse_file(&path).await {
+            Ok(content) => content,
+            Err(e) => {
+                debug!("Skipping {} while scanning series: {e}", path.display());
+                continue;
+            }
+        };
+        if let Ok(Some(info)) = SeriesInfo::from_metadata(&content.metadata)
+            && info.name == name
+        {
+            let title = content.title.clone().unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+            parts.push((info.part, title, path));
+        }
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part_markdown(title: &str, part: u32) -> String {
+        format!(
+            "---\ntitle: \"{title}\"\nseries:\n  name: \"Rust 入门\"\n  part: {part}\n---\n\nBody\n"
+        )
+    }
+
+    #[test]
+    fn test_series_info_from_metadata() {
+        let metadata = HashMap::from([
+            ("series.name".to_string(), "Rust 入门".to_string()),
+            ("series.part".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(
+            SeriesInfo::from_metadata(&metadata).unwrap(),
+            Some(SeriesInfo {
+                name: "Rust 入门".to_string(),
+                part: 2,
+            })
+        );
+
+        assert_eq!(SeriesInfo::from_metadata(&HashMap::new()).unwrap(), None);
+
+        let zero = HashMap::from([
+            ("series.name".to_string(), "Rust 入门".to_string()),
+            ("series.part".to_string(), "0".to_string()),
+        ]);
+        assert!(SeriesInfo::from_metadata(&zero).is_err());
+
+        let unnamed = HashMap::from([("series.part".to_string(), "1".to_string())]);
+        assert!(SeriesInfo::from_metadata(&unnamed).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_series_navigation() {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, title, part) in [
+            ("01.md", "安装与工具链", 1),
+            ("02.md", "所有权与借用", 2),
+            ("03.md", "错误处理 & Result", 3),
+        ] {
+            tokio::fs::write(dir.path().join(file), part_markdown(title, part))
+                .await
+                .unwrap();
+        }
+        tokio::fs::write(dir.path().join("other.md"), "# Unrelated\n")
+            .await
+            .unwrap();
+
+        let mut record = SeriesRecord::default();
+        let first = SeriesInfo {
+            name: "Rust 入门".to_string(),
+            part: 1,
+        };
+        record.set_draft(&first, "安装与工具链", "draft_1");
+        record.set_url(&first, "安装与工具链", "https://mp.weixin.qq.com/s/abc");
+        record.save(dir.path(), None).await.unwrap();
+
+        let content = MarkdownParser::new()
+            .parse_file(dir.path().join("02.md"))
+            .await
+            .unwrap();
+        let index = SeriesIndex::load(&content, dir.path(), None)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(index.current, 2);
+        assert_eq!(
+            index.parts.iter().map(|part| part.part).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+
+        let html = index.render();
+        assert!(html.contains("系列：Rust 入门（共 3 篇）"));
+        assert!(
+            html.contains(r#"<a href="https://mp.weixin.qq.com/s/abc">第 1 篇：安装与工具链</a>"#)
+        );
+        assert!(html.contains(r#"series-current">第 2 篇：所有权与借用（本篇）"#));
+        assert!(html.contains(r#"series-upcoming">第 3 篇：错误处理 &amp; Result（待发布）"#));
+
+        let loaded = SeriesRecord::load(dir.path(), None).await.unwrap();
+        assert_eq!(
+            loaded.part("Rust 入门", 1).unwrap().media_id.as_deref(),
+            Some("draft_1")
+        );
+
+        // Another account has not published part 1
+        let html = navigation(&content, dir.path(), Some("wx1234567890abcdef"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(html.contains(r#"series-upcoming">第 1 篇：安装与工具链（待发布）"#));
+    }
+}
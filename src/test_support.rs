@@ -0,0 +1,97 @@
+//! Helpers shared by the unit tests.
+
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A local HTTP server that answers each connection with a canned JSON body.
+pub(crate) struct MockServer {
+    /// Base URL of the server, e.g. `http://127.0.0.1:8080`.
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockServer {
+    /// Serves `bodies` in order, one per connection, then stops accepting.
+    pub async fn serve<S: Into<String>>(bodies: impl IntoIterator<Item = S>) -> Self {
+        let mut bodies = bodies
+            .into_iter()
+            .map(Into::into)
+            .collect::<Vec<_>>()
+            .into_iter();
+        Self::start(move |_| bodies.next()).await
+    }
+
+    /// Serves the body `respond` builds from the 1-based request number, for every request.
+    pub async fn respond_with(mut respond: impl FnMut(usize) -> String + Send + 'static) -> Self {
+        Self::start(move |count| Some(respond(count))).await
+    }
+
+    /// Serves `token1`, `token2`, … as access tokens, numbered by request.
+    pub async fn tokens() -> Self {
+        Self::respond_with(|count| {
+            format!(r#"{{"access_token":"token{count}","expires_in":7200}}"#)
+        })
+        .await
+    }
+
+    /// Requests received so far, as raw text (request line, headers and body).
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    async fn start(mut respond: impl FnMut(usize) -> Option<String> + Send + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                // Read the whole request so closing the socket does not reset it
+                let request = read_request(&mut socket).await;
+                let count = {
+                    let mut requests = recorded.lock().unwrap();
+                    requests.push(request);
+                    requests.len()
+                };
+                let Some(body) = respond(count) else {
+                    break;
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        Self { url, requests }
+    }
+}
+
+/// Reads one request, including a body announced by `content-length`.
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut data = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let n = socket.read(&mut buffer).await.unwrap();
+        data.extend_from_slice(&buffer[..n]);
+        let text = String::from_utf8_lossy(&data);
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|value| value.trim().parse::<usize>().unwrap())
+                })
+                .unwrap_or(0);
+            if body.len() >= length || n == 0 {
+                return text.into_owned();
+            }
+        } else if n == 0 {
+            return text.into_owned();
+        }
+    }
+}
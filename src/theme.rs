@@ -56,13 +56,48 @@
 
 use crate::css_vars::CssVariableProcessor;
 use crate::error::{Result, WeChatError};
+use crate::markdown::{Heading, collect_headings};
+use crate::typography::apply_cjk_typography;
+use crate::utils::display_width;
 use askama::Template;
 use comrak::{
-    ComrakOptions, ComrakPlugins, markdown_to_html_with_plugins, plugins::syntect::SyntectAdapter,
+    Arena, ComrakOptions, ComrakPlugins, format_html_with_plugins,
+    nodes::{AstNode, NodeValue},
+    parse_document,
+    plugins::syntect::SyntectAdapter,
 };
+use regex::Regex;
 use std::collections::HashMap;
+use std::sync::LazyLock;
 use tracing::warn;
 
+/// Outline numbers marked on heading nodes, wrapped in private-use characters so they
+/// survive rendering and can be turned into spans afterwards.
+const HEADING_NUMBER_START: char = '\u{E000}';
+const HEADING_NUMBER_END: char = '\u{E001}';
+
+static HEADING_NUMBER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new("\u{E000}([0-9.]+)\u{E001}").unwrap());
+
+static ALERT_TITLE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(<div class="markdown-alert markdown-alert-(\w+)"[^>]*>\s*<p class="markdown-alert-title">)"#,
+    )
+    .unwrap()
+});
+
+static TOC_MARKER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<p>\[TOC\]</p>\n?").unwrap());
+
+static IMAGE_PARAGRAPH_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<p>((?:\s*<img [^>]*/>)+\s*)</p>").unwrap());
+static IMG_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<img [^>]*/>").unwrap());
+static IMG_TITLE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\stitle="([^"]*)""#).unwrap());
+static IMG_ALT_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\salt="([^"]*)""#).unwrap());
+static FIGURE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<figure>(.*?)</figure>").unwrap());
+
 // Embed all theme CSS files at compile time
 const DEFAULT_CSS: &str = include_str!("../themes/default.css");
 const LAPIS_CSS: &str = include_str!("../themes/lapis.css");
@@ -84,6 +119,18 @@ const SOLARIZED_DARK_CSS: &str = include_str!("../themes/highlight/solarized-dar
 const SOLARIZED_LIGHT_CSS: &str = include_str!("../themes/highlight/solarized-light.min.css");
 const XCODE_CSS: &str = include_str!("../themes/highlight/xcode.min.css");
 
+/// Default column budget for code lines at phone width.
+pub const DEFAULT_CODE_COLUMNS: usize = 48;
+
+// Inline styles for code overflow handling, applied before inlining so they reach WeChat
+const CODE_SCROLL_STYLE: &str =
+    "display: block; overflow-x: auto; white-space: pre; word-wrap: normal;";
+const CODE_WRAP_STYLE: &str = "display: block; white-space: pre-wrap; word-break: break-all;";
+const CODE_HINT_STYLE: &str = "display: block; margin-top: 6px; font-size: 12px; color: #999999; text-align: center; user-select: none;";
+const CODE_CONTINUATION_MARKER: &str =
+    r#"<span style="color: #999999; user-select: none;">↪ </span>"#;
+const CODE_CONTINUATION_WIDTH: usize = 2;
+
 /// Built-in theme options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BuiltinTheme {
@@ -155,6 +202,68 @@ impl std::str::FromStr for BuiltinTheme {
     }
 }
 
+/// How code lines wider than the phone screen are presented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CodeOverflow {
+    /// Keep lines intact inside a horizontally scrolling container
+    #[default]
+    Scroll,
+    /// Break long lines at the column budget and mark each continuation
+    Wrap,
+    /// Scroll, and add a swipe hint below blocks that exceed the column budget
+    Hint,
+}
+
+impl CodeOverflow {
+    /// Gets the mode name as used in frontmatter.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodeOverflow::Scroll => "scroll",
+            CodeOverflow::Wrap => "wrap",
+            CodeOverflow::Hint => "hint",
+        }
+    }
+}
+
+impl std::str::FromStr for CodeOverflow {
+    type Err = WeChatError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "scroll" => Ok(CodeOverflow::Scroll),
+            "wrap" => Ok(CodeOverflow::Wrap),
+            "hint" => Ok(CodeOverflow::Hint),
+            _ => Err(WeChatError::config_error(format!(
+                "Invalid code_overflow mode: {s} (expected scroll, wrap or hint)"
+            ))),
+        }
+    }
+}
+
+/// A code line that is wider than the configured column budget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LongCodeLine {
+    /// Zero-based index of the code block in the document
+    pub block_index: usize,
+    /// Fence language, if any
+    pub language: Option<String>,
+    /// One-based line number within the block
+    pub line_number: usize,
+    /// Width of the line in monospace columns
+    pub width: usize,
+}
+
+/// Rendered HTML fragments placed before and after the article body.
+#[derive(Debug, Clone, Default)]
+pub struct Partials {
+    /// HTML shown above the article body
+    pub header: Option<String>,
+    /// HTML shown below the article body, such as an author bio or QR code
+    pub footer: Option<String>,
+    /// Series navigation shown between the article body and the footer
+    pub series: Option<String>,
+}
+
 /// Askama template for rendering articles with themes.
 #[derive(Template)]
 #[template(path = "article.html")]
@@ -163,6 +272,9 @@ pub struct ArticleTemplate {
     pub description: String,
     pub author: String,
     pub content: String,
+    pub header: String,
+    pub series: String,
+    pub footer: String,
     pub theme_css: String,
     pub highlight_css: String,
 }
@@ -176,6 +288,10 @@ pub struct ThemeTemplate {
     pub code_css: String,
     /// Theme name
     pub name: String,
+    /// How code lines wider than `code_columns` are presented
+    pub code_overflow: CodeOverflow,
+    /// Column budget for code lines
+    pub code_columns: usize,
 }
 
 impl ThemeTemplate {
@@ -185,22 +301,45 @@ impl ThemeTemplate {
             theme_css,
             code_css,
             name,
+            code_overflow: CodeOverflow::default(),
+            code_columns: DEFAULT_CODE_COLUMNS,
         }
     }
 
     /// Creates a new theme template with static CSS references.
     pub fn from_static(theme_css: &'static str, code_css: &'static str, name: String) -> Self {
-        Self {
-            theme_css: theme_css.to_string(),
-            code_css: code_css.to_string(),
-            name,
-        }
+        Self::new(theme_css.to_string(), code_css.to_string(), name)
+    }
+
+    /// Sets the overflow mode for long code lines.
+    pub fn with_code_overflow(mut self, code_overflow: CodeOverflow) -> Self {
+        self.code_overflow = code_overflow;
+        self
+    }
+
+    /// Sets the column budget for code lines.
+    pub fn with_code_columns(mut self, code_columns: usize) -> Self {
+        self.code_columns = code_columns;
+        self
     }
 
     /// Renders content using this theme with inline styles for WeChat.
     ///
     /// This method processes CSS variables before inlining styles for better WeChat compatibility.
+    /// The `code_overflow` and `code_columns` metadata keys override the theme's settings.
     pub fn render(&self, content: &str, metadata: &HashMap<String, String>) -> Result<String> {
+        self.render_with_partials(content, metadata, &Partials::default())
+    }
+
+    /// Renders content like [`render`](Self::render), wrapped in header and footer partials.
+    ///
+    /// Partials are styled and inlined together with the article body.
+    pub fn render_with_partials(
+        &self,
+        content: &str,
+        metadata: &HashMap<String, String>,
+        partials: &Partials,
+    ) -> Result<String> {
         // Process CSS variables in both theme and highlight CSS
         let css_processor = CssVariableProcessor::new();
 
@@ -224,6 +363,9 @@ impl ThemeTemplate {
             description: metadata.get("description").cloned().unwrap_or_default(),
             author: metadata.get("author").cloned().unwrap_or_default(),
             content: content.to_string(),
+            header: partials.header.clone().unwrap_or_default(),
+            series: partials.series.clone().unwrap_or_default(),
+            footer: partials.footer.clone().unwrap_or_default(),
             theme_css: processed_theme_css,
             highlight_css: processed_highlight_css,
         };
@@ -234,7 +376,20 @@ impl ThemeTemplate {
         })?;
 
         // Post-process code blocks before CSS inlining to preserve their structure
-        let html_with_protected_code = self.post_process_code_blocks(html_with_css);
+        let code_overflow = match metadata.get("code_overflow") {
+            Some(value) => value.parse().unwrap_or_else(|e| {
+                warn!("Ignoring frontmatter value: {e}");
+                self.code_overflow
+            }),
+            None => self.code_overflow,
+        };
+        let code_columns = metadata
+            .get("code_columns")
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|columns| *columns > 0)
+            .unwrap_or(self.code_columns);
+        let html_with_protected_code =
+            self.post_process_code_blocks(html_with_css, code_overflow, code_columns);
 
         // Use css-inline to convert CSS to inline styles
         let inlined_html =
@@ -249,30 +404,141 @@ impl ThemeTemplate {
 
     /// Post-process HTML to preserve code block structure for WeChat.
     /// This function handles syntax-highlighted code blocks and preserves syntax highlighting while ensuring proper line breaks.
-    fn post_process_code_blocks(&self, html: String) -> String {
+    fn post_process_code_blocks(
+        &self,
+        html: String,
+        code_overflow: CodeOverflow,
+        code_columns: usize,
+    ) -> String {
         use regex::Regex;
 
         // Use regex to find and replace pre > code blocks while preserving syntax highlighting
         let pre_code_regex =
             Regex::new(r#"(?s)(<pre[^>]*>)(<code[^>]*>)(.*?)</code></pre>"#).unwrap();
+        let meta_regex = Regex::new(r#"\s*data-meta="([^"]*)""#).unwrap();
 
         let result = pre_code_regex.replace_all(&html, |caps: &regex::Captures| {
             let pre_tag = &caps[1];
             let code_tag = &caps[2];
             let content = &caps[3];
 
+            // Fence options (title, line numbers, highlighted lines) travel in data-meta
+            let meta = meta_regex
+                .captures(code_tag)
+                .map(|m| CodeBlockMeta::parse(&unescape_html(&m[1])))
+                .unwrap_or_default();
+            let code_tag = meta_regex.replace(code_tag, "");
+            let title_bar = meta
+                .title
+                .as_ref()
+                .map(|title| format!(r#"<span class="code-title">{}</span>"#, escape_html(title)))
+                .unwrap_or_default();
+
             // Process the content to fix newlines while preserving syntax highlighting
-            let processed_content = self.process_code_content(content);
+            let processed_content =
+                self.process_code_content(content, &meta, code_overflow, code_columns);
 
             // Create a properly formatted code block with inline styles that will survive CSS inlining
-            format!("{pre_tag}{code_tag}{processed_content}</code></pre>")
+            format!("{pre_tag}{title_bar}{code_tag}{processed_content}</code></pre>")
         });
 
         result.to_string()
     }
 
+    /// Renders code content line by line, adding line numbers and highlighted lines.
+    ///
+    /// Line numbers are emitted as real text spans rather than CSS counters, because
+    /// pseudo-elements do not survive CSS inlining or pasting into the WeChat editor.
+    /// In wrap mode, lines wider than `code_columns` are broken into continuation rows.
+    fn process_code_lines(
+        &self,
+        html_content: &str,
+        meta: &CodeBlockMeta,
+        code_overflow: CodeOverflow,
+        code_columns: usize,
+    ) -> String {
+        let lines = split_highlighted_lines(html_content);
+        let mut rows = Vec::new();
+
+        for (index, line) in lines.iter().enumerate() {
+            let line_number = index + 1;
+            let segments = if code_overflow == CodeOverflow::Wrap {
+                wrap_highlighted_line(line, code_columns)
+            } else {
+                vec![line.clone()]
+            };
+
+            for (segment_index, segment) in segments.iter().enumerate() {
+                let mut line_html = String::new();
+
+                if meta.show_line_numbers {
+                    let label = if segment_index == 0 {
+                        line_number.to_string()
+                    } else {
+                        " ".to_string()
+                    };
+                    line_html
+                        .push_str(&format!(r#"<span class="code-line-number">{label}</span>"#));
+                }
+                if segment_index > 0 {
+                    line_html.push_str(CODE_CONTINUATION_MARKER);
+                }
+                line_html.push_str(segment);
+
+                if meta.is_highlighted(line_number) {
+                    line_html = format!(r#"<span class="code-line-highlight">{line_html}</span>"#);
+                }
+
+                rows.push(line_html);
+            }
+        }
+
+        rows.join("<br/>")
+    }
+
     /// Process code content to preserve syntax highlighting while fixing newlines for WeChat.
-    fn process_code_content(&self, html_content: &str) -> String {
+    ///
+    /// The overflow mode is applied as inline styles on a wrapper span, so it holds up
+    /// regardless of which theme rules survive CSS inlining and the WeChat editor.
+    fn process_code_content(
+        &self,
+        html_content: &str,
+        meta: &CodeBlockMeta,
+        code_overflow: CodeOverflow,
+        code_columns: usize,
+    ) -> String {
+        let body = if meta.is_empty() && code_overflow != CodeOverflow::Wrap {
+            self.convert_code_newlines(html_content)
+        } else {
+            self.process_code_lines(html_content, meta, code_overflow, code_columns)
+        };
+
+        match code_overflow {
+            CodeOverflow::Scroll => format!(r#"<span style="{CODE_SCROLL_STYLE}">{body}</span>"#),
+            CodeOverflow::Wrap => format!(r#"<span style="{CODE_WRAP_STYLE}">{body}</span>"#),
+            CodeOverflow::Hint => {
+                let widest = code_text(html_content)
+                    .lines()
+                    .map(display_width)
+                    .max()
+                    .unwrap_or(0);
+                if widest > code_columns {
+                    warn!(
+                        "Code block has a line {widest} columns wide (budget {code_columns}); \
+                         consider reformatting it or using code_overflow: wrap"
+                    );
+                    format!(
+                        r#"<span style="{CODE_SCROLL_STYLE}">{body}</span><span style="{CODE_HINT_STYLE}">← 左右滑动查看完整代码 →</span>"#
+                    )
+                } else {
+                    format!(r#"<span style="{CODE_SCROLL_STYLE}">{body}</span>"#)
+                }
+            }
+        }
+    }
+
+    /// Converts newlines in highlighted code to `<br/>` tags for WeChat.
+    fn convert_code_newlines(&self, html_content: &str) -> String {
         // Convert newlines to <br/> tags for WeChat compatibility
         // Preserve existing HTML structure (like <span> tags for syntax highlighting)
         let mut result = html_content.to_string();
@@ -311,6 +577,455 @@ impl ThemeTemplate {
     }
 }
 
+/// Display options parsed from the info string of a fenced code block.
+///
+/// Supports ```` ```rust title="main.rs" {3-5} showLineNumbers ````: a title bar,
+/// highlighted line ranges and a line-number gutter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeBlockMeta {
+    /// Title shown in a header bar above the code (usually a file name)
+    pub title: Option<String>,
+    /// Inclusive, 1-based line ranges to highlight
+    pub highlight_lines: Vec<(usize, usize)>,
+    /// Whether to render a line-number gutter
+    pub show_line_numbers: bool,
+}
+
+impl CodeBlockMeta {
+    /// Parses the part of the info string that follows the language.
+    ///
+    /// Unknown tokens are ignored so that fences written for other renderers still work.
+    pub fn parse(info: &str) -> Self {
+        let mut meta = Self::default();
+        let mut chars = info.chars().peekable();
+
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() {
+                chars.next();
+            } else if ch == '{' {
+                chars.next();
+                let ranges: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                meta.highlight_lines.extend(Self::parse_ranges(&ranges));
+            } else {
+                let mut key = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '=' || c == '{' {
+                        break;
+                    }
+                    key.push(c);
+                    chars.next();
+                }
+
+                let value = if chars.peek() == Some(&'=') {
+                    chars.next();
+                    match chars.peek() {
+                        Some(&quote @ ('"' | '\'')) => {
+                            chars.next();
+                            Some(chars.by_ref().take_while(|&c| c != quote).collect())
+                        }
+                        _ => {
+                            let mut value = String::new();
+                            while let Some(&c) = chars.peek() {
+                                if c.is_whitespace() {
+                                    break;
+                                }
+                                value.push(c);
+                                chars.next();
+                            }
+                            Some(value)
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                match key.as_str() {
+                    "title" | "filename" => meta.title = value.filter(|v| !v.is_empty()),
+                    "showLineNumbers" | "linenos" => meta.show_line_numbers = true,
+                    _ => {}
+                }
+            }
+        }
+
+        meta
+    }
+
+    /// Parses comma-separated line numbers and ranges such as `1,3-5`.
+    fn parse_ranges(ranges: &str) -> Vec<(usize, usize)> {
+        ranges
+            .split(',')
+            .filter_map(|part| {
+                let part = part.trim();
+                match part.split_once('-') {
+                    Some((start, end)) => {
+                        let start = start.trim().parse().ok()?;
+                        let end = end.trim().parse().ok()?;
+                        (start <= end).then_some((start, end))
+                    }
+                    None => part.parse().ok().map(|line| (line, line)),
+                }
+            })
+            .collect()
+    }
+
+    /// Checks whether no display options were requested.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.highlight_lines.is_empty() && !self.show_line_numbers
+    }
+
+    /// Checks whether a 1-based line number falls into a highlighted range.
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight_lines
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&line))
+    }
+}
+
+/// Splits syntax-highlighted HTML into one balanced fragment per source line.
+///
+/// Highlighter spans may contain newlines, so open spans are closed at the end of each
+/// line and reopened at the start of the next one. A trailing empty line is dropped.
+fn split_highlighted_lines(html: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut open_tags: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut has_text = false;
+    let mut chars = html.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '<' => {
+                let tag = read_tag(&mut chars);
+                track_tag(&mut open_tags, &tag);
+                current.push_str(&tag);
+            }
+            '\n' => {
+                for tag in open_tags.iter().rev() {
+                    current.push_str(&closing_tag(tag));
+                }
+                lines.push(std::mem::take(&mut current));
+                has_text = false;
+                for tag in &open_tags {
+                    current.push_str(tag);
+                }
+            }
+            _ => {
+                has_text = true;
+                current.push(ch);
+            }
+        }
+    }
+
+    if has_text {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Breaks one balanced line of highlighted HTML into rows of at most `columns` columns.
+///
+/// Continuation rows leave room for the continuation marker. Open spans are closed at
+/// each break and reopened on the next row, so every row stays balanced.
+fn wrap_highlighted_line(line: &str, columns: usize) -> Vec<String> {
+    let mut rows = Vec::new();
+    let mut open_tags: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut width = 0;
+    let mut limit = columns.max(1);
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '<' {
+            let tag = read_tag(&mut chars);
+            track_tag(&mut open_tags, &tag);
+            current.push_str(&tag);
+            continue;
+        }
+
+        // An entity such as `&lt;` renders as a single column
+        let (text, ch_width) = if ch == '&' {
+            let mut entity = String::from('&');
+            for c in chars.by_ref() {
+                entity.push(c);
+                if c == ';' {
+                    break;
+                }
+            }
+            (entity, 1)
+        } else {
+            (ch.to_string(), display_width(ch.encode_utf8(&mut [0; 4])))
+        };
+
+        if width > 0 && width + ch_width > limit {
+            for tag in open_tags.iter().rev() {
+                current.push_str(&closing_tag(tag));
+            }
+            rows.push(std::mem::take(&mut current));
+            for tag in &open_tags {
+                current.push_str(tag);
+            }
+            width = 0;
+            limit = columns.saturating_sub(CODE_CONTINUATION_WIDTH).max(1);
+        }
+
+        current.push_str(&text);
+        width += ch_width;
+    }
+
+    rows.push(current);
+    rows
+}
+
+/// Reads the rest of an HTML tag after its opening `<`.
+fn read_tag(chars: &mut std::str::Chars<'_>) -> String {
+    let mut tag = String::from('<');
+    for c in chars.by_ref() {
+        tag.push(c);
+        if c == '>' {
+            break;
+        }
+    }
+    tag
+}
+
+/// Updates the stack of open tags with a tag that was just read.
+fn track_tag(open_tags: &mut Vec<String>, tag: &str) {
+    if tag.starts_with("</") {
+        open_tags.pop();
+    } else if !tag.ends_with("/>") {
+        open_tags.push(tag.to_string());
+    }
+}
+
+/// Extracts the plain source text from highlighted code HTML.
+fn code_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut chars = html.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '<' {
+            read_tag(&mut chars);
+        } else {
+            text.push(ch);
+        }
+    }
+
+    unescape_html(&text)
+}
+
+/// Prefixes each alert title with an icon for its alert type.
+///
+/// Icons are plain emoji text, so they survive pasting into the WeChat editor.
+fn add_admonition_icons(html: &str) -> String {
+    ALERT_TITLE_REGEX
+        .replace_all(html, |caps: &regex::Captures| {
+            let icon = match &caps[2] {
+                "tip" => "💡",
+                "important" => "📌",
+                "warning" => "⚠️",
+                "caution" => "⛔",
+                _ => "ℹ️",
+            };
+            format!(
+                r#"{}<span class="markdown-alert-icon">{icon}</span>"#,
+                &caps[1]
+            )
+        })
+        .to_string()
+}
+
+/// Turns paragraphs that hold only images into figures with captions.
+///
+/// Each line of such a paragraph becomes one figure, or an image grid when the line
+/// holds several images side by side. The caption is the image title, or its alt text.
+fn render_figures(html: &str) -> String {
+    IMAGE_PARAGRAPH_REGEX
+        .replace_all(html, |caps: &regex::Captures| {
+            caps[1]
+                .lines()
+                .filter(|row| !row.trim().is_empty())
+                .map(|row| {
+                    let figures: Vec<String> = IMG_TAG_REGEX
+                        .find_iter(row)
+                        .map(|img| figure(img.as_str()))
+                        .collect();
+                    if figures.len() == 1 {
+                        figures.concat()
+                    } else {
+                        format!(
+                            r#"<section class="image-grid">{}</section>"#,
+                            figures.concat()
+                        )
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .to_string()
+}
+
+/// Wraps a rendered `<img>` tag in a figure, captioned by its title or alt text.
+fn figure(img_tag: &str) -> String {
+    let attribute = |regex: &Regex| {
+        regex
+            .captures(img_tag)
+            .map(|caps| caps[1].to_string())
+            .filter(|value| !value.trim().is_empty())
+    };
+
+    match attribute(&IMG_TITLE_REGEX).or_else(|| attribute(&IMG_ALT_REGEX)) {
+        Some(caption) => format!("<figure>{img_tag}<figcaption>{caption}</figcaption></figure>"),
+        None => format!("<figure>{img_tag}</figure>"),
+    }
+}
+
+/// Prefixes each figure caption with a number such as `图 1`.
+fn number_figures(html: &str, label: &str) -> String {
+    let mut count = 0;
+
+    FIGURE_REGEX
+        .replace_all(html, |caps: &regex::Captures| {
+            count += 1;
+            let number = format!(
+                r#"<span class="figure-number">{} {count}</span>"#,
+                escape_html(label)
+            );
+            let inner = &caps[1];
+
+            if inner.contains("<figcaption>") {
+                let inner = inner.replacen("<figcaption>", &format!("<figcaption>{number}"), 1);
+                format!("<figure>{inner}</figure>")
+            } else {
+                format!("<figure>{inner}<figcaption>{number}</figcaption></figure>")
+            }
+        })
+        .to_string()
+}
+
+/// An h2/h3 heading with its outline number, such as `1.2`.
+struct OutlineEntry {
+    level: u8,
+    number: String,
+    text: String,
+}
+
+/// Numbers h2 headings as `1`, `2`, ... and h3 headings below them as `1.1`, `1.2`, ...
+fn number_outline(headings: &[Heading]) -> Vec<OutlineEntry> {
+    let mut section = 0;
+    let mut subsection = 0;
+
+    headings
+        .iter()
+        .filter(|heading| matches!(heading.level, 2 | 3))
+        .map(|heading| {
+            let number = if heading.level == 2 {
+                section += 1;
+                subsection = 0;
+                section.to_string()
+            } else {
+                subsection += 1;
+                if section == 0 {
+                    subsection.to_string()
+                } else {
+                    format!("{section}.{subsection}")
+                }
+            };
+
+            OutlineEntry {
+                level: heading.level,
+                number,
+                text: heading.text.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Marks each h2/h3 heading node with its outline number.
+///
+/// Works on the AST the outline is built from, so raw HTML headings and partials are
+/// never numbered. [`render_heading_numbers`] turns the marks into spans.
+fn mark_heading_numbers<'a>(arena: &'a Arena<AstNode<'a>>, root: &'a AstNode<'a>) {
+    let outline = number_outline(&collect_headings(root));
+    let headings: Vec<_> = root
+        .descendants()
+        .filter(|node| {
+            matches!(&node.data.borrow().value, NodeValue::Heading(heading) if matches!(heading.level, 2 | 3))
+        })
+        .collect();
+
+    for (node, entry) in headings.into_iter().zip(&outline) {
+        let mark = format!("{HEADING_NUMBER_START}{}{HEADING_NUMBER_END}", entry.number);
+        node.prepend(arena.alloc(NodeValue::Text(mark).into()));
+    }
+}
+
+/// Replaces the heading number marks in rendered HTML with number spans.
+fn render_heading_numbers(html: &str) -> String {
+    HEADING_NUMBER_REGEX
+        .replace_all(html, r#"<span class="heading-number">$1</span>"#)
+        .to_string()
+}
+
+/// Renders the outline as a non-clickable table of contents.
+fn render_toc(outline: &[OutlineEntry], title: &str) -> String {
+    if outline.is_empty() {
+        return String::new();
+    }
+
+    let mut toc = format!(
+        r#"<section class="toc"><p class="toc-title">{}</p>"#,
+        escape_html(title)
+    );
+    for entry in outline {
+        toc.push_str(&format!(
+            r#"<p class="toc-item toc-level-{}"><span class="toc-number">{}</span>{}</p>"#,
+            entry.level,
+            entry.number,
+            escape_html(&entry.text)
+        ));
+    }
+    toc.push_str("</section>\n");
+    toc
+}
+
+/// Interprets a frontmatter flag such as `toc: true`.
+fn is_enabled(value: Option<&String>) -> bool {
+    value.is_some_and(|value| {
+        matches!(
+            value.trim().to_lowercase().as_str(),
+            "true" | "yes" | "on" | "1"
+        )
+    })
+}
+
+/// Builds the closing tag for an opening tag such as `<span style="...">`.
+fn closing_tag(open_tag: &str) -> String {
+    let name: String = open_tag[1..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect();
+    format!("</{name}>")
+}
+
+/// Escapes text for safe inclusion in HTML.
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Reverses the entity escaping comrak applies to attribute values.
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
 /// Theme manager for rendering markdown with different styles.
 #[derive(Debug)]
 pub struct ThemeManager {
@@ -340,7 +1055,10 @@ impl ThemeManager {
         options.extension.table = true;
         options.extension.footnotes = true;
         options.extension.tasklist = true;
+        options.extension.alerts = true;
         options.parse.smart = true;
+        // Keep fence options (title, highlighted lines, ...) available as data-meta
+        options.render.full_info_string = true;
         options
     }
 
@@ -394,12 +1112,33 @@ impl ThemeManager {
     }
 
     /// Renders markdown content with the specified theme and code highlight theme.
+    ///
+    /// `:::` containers are expected to have been converted already, as
+    /// [`MarkdownParser::parse`](crate::markdown::MarkdownParser::parse) does.
     pub fn render(
         &self,
         markdown_content: &str,
         theme_name: &str,
         code_theme: &str,
         metadata: &HashMap<String, String>,
+    ) -> Result<String> {
+        self.render_with_partials(
+            markdown_content,
+            theme_name,
+            code_theme,
+            metadata,
+            &Partials::default(),
+        )
+    }
+
+    /// Renders markdown content like [`render`](Self::render), with header and footer partials.
+    pub fn render_with_partials(
+        &self,
+        markdown_content: &str,
+        theme_name: &str,
+        code_theme: &str,
+        metadata: &HashMap<String, String>,
+        partials: &Partials,
     ) -> Result<String> {
         let template =
             self.templates
@@ -411,6 +1150,53 @@ impl ThemeManager {
         // Get highlight CSS, defaulting to "vscode" if not specified or not found
         let highlight_css = self.get_highlight_css(code_theme);
 
+        let cjk_typography = is_enabled(metadata.get("cjk_typography"));
+        let number_headings = is_enabled(metadata.get("heading_numbers"));
+        let html_content = self.markdown_to_html(
+            markdown_content,
+            code_theme,
+            cjk_typography,
+            number_headings,
+        );
+        let html_content = self.apply_outline(markdown_content, html_content, metadata);
+        let html_content = if is_enabled(metadata.get("figure_numbers")) {
+            let label = metadata.get("figure_label").map_or("图", String::as_str);
+            number_figures(&html_content, label)
+        } else {
+            html_content
+        };
+
+        // Create a new template with the highlight CSS
+        let template_with_highlight = ThemeTemplate {
+            theme_css: template.theme_css.clone(),
+            code_css: highlight_css,
+            name: template.name.clone(),
+            code_overflow: template.code_overflow,
+            code_columns: template.code_columns,
+        };
+
+        // Apply theme template
+        template_with_highlight.render_with_partials(&html_content, metadata, partials)
+    }
+
+    /// Converts markdown to an HTML fragment without the article template.
+    ///
+    /// Used for the article body and for markdown header/footer partials; styles are
+    /// applied later when the fragment is placed in a themed template. Like
+    /// [`render`](Self::render), it expects `:::` containers to be converted already.
+    pub fn render_fragment(&self, markdown_content: &str, code_theme: &str) -> String {
+        self.markdown_to_html(markdown_content, code_theme, false, false)
+    }
+
+    /// Converts markdown to HTML, optionally running the CJK typography pass on text nodes
+    /// and numbering h2/h3 headings.
+    fn markdown_to_html(
+        &self,
+        markdown_content: &str,
+        code_theme: &str,
+        cjk_typography: bool,
+        number_headings: bool,
+    ) -> String {
         // Create syntect adapter for syntax highlighting
         // Map our CSS theme names to syntect theme names
         let syntect_theme_name = match code_theme {
@@ -433,18 +1219,95 @@ impl ThemeManager {
         plugins.render.codefence_syntax_highlighter = Some(&adapter);
 
         // Convert markdown to HTML using comrak with syntect
-        let html_content =
-            markdown_to_html_with_plugins(markdown_content, &self.markdown_options, &plugins);
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown_content, &self.markdown_options);
+        if cjk_typography {
+            apply_cjk_typography(root);
+        }
+        if number_headings {
+            mark_heading_numbers(&arena, root);
+        }
 
-        // Create a new template with the highlight CSS
-        let template_with_highlight = ThemeTemplate {
-            theme_css: template.theme_css.clone(),
-            code_css: highlight_css,
-            name: template.name.clone(),
-        };
+        let mut html = Vec::new();
+        format_html_with_plugins(root, &self.markdown_options, &mut html, &plugins)
+            .expect("writing HTML to a Vec cannot fail");
+        let mut html_content = add_admonition_icons(&String::from_utf8_lossy(&html));
+        if number_headings {
+            html_content = render_heading_numbers(&html_content);
+        }
+        render_figures(&html_content)
+    }
 
-        // Apply theme template
-        template_with_highlight.render(&html_content, metadata)
+    /// Finds code lines wider than `max_columns`, so preview tooling can flag them.
+    pub fn find_long_code_lines(
+        &self,
+        markdown_content: &str,
+        max_columns: usize,
+    ) -> Vec<LongCodeLine> {
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown_content, &self.markdown_options);
+        let code_blocks = root
+            .descendants()
+            .filter_map(|node| match &node.data.borrow().value {
+                NodeValue::CodeBlock(code) => Some((code.info.clone(), code.literal.clone())),
+                _ => None,
+            });
+
+        let mut long_lines = Vec::new();
+        for (block_index, (info, literal)) in code_blocks.enumerate() {
+            let language = info.split_whitespace().next().map(str::to_string);
+            for (index, line) in literal.lines().enumerate() {
+                let width = display_width(line);
+                if width > max_columns {
+                    long_lines.push(LongCodeLine {
+                        block_index,
+                        language: language.clone(),
+                        line_number: index + 1,
+                        width,
+                    });
+                }
+            }
+        }
+
+        long_lines
+    }
+
+    /// Expands the table of contents when requested.
+    ///
+    /// The TOC is placed at a `[TOC]` marker, or at the top of the article when only
+    /// `toc: true` is set. WeChat has no in-article anchors, so it is a plain overview.
+    fn apply_outline(
+        &self,
+        markdown_content: &str,
+        html: String,
+        metadata: &HashMap<String, String>,
+    ) -> String {
+        let has_marker = TOC_MARKER_REGEX.is_match(&html);
+        if !has_marker && !is_enabled(metadata.get("toc")) {
+            return html;
+        }
+
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown_content, &self.markdown_options);
+        let outline = number_outline(&collect_headings(root));
+
+        let toc_title = metadata.get("toc_title").map_or("目录", String::as_str);
+        let toc = render_toc(&outline, toc_title);
+        if has_marker {
+            // Only the first marker is expanded; any others are dropped
+            let mut expanded = false;
+            TOC_MARKER_REGEX
+                .replace_all(&html, |_: &regex::Captures| {
+                    if std::mem::replace(&mut expanded, true) {
+                        String::new()
+                    } else {
+                        toc.clone()
+                    }
+                })
+                .to_string()
+        } else {
+            format!("{toc}{html}")
+        }
     }
 
     /// Adds a custom theme.
@@ -457,6 +1320,11 @@ impl ThemeManager {
         self.templates.keys().collect()
     }
 
+    /// Gets the list of available code highlighting theme names.
+    pub fn available_code_themes(&self) -> Vec<&String> {
+        self.highlight_css.keys().collect()
+    }
+
     /// Checks if a theme exists.
     pub fn has_theme(&self, name: &str) -> bool {
         self.templates.contains_key(name)
@@ -483,6 +1351,7 @@ impl Default for ThemeManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::markdown::convert_admonition_containers;
 
     #[test]
     fn test_builtin_theme_parsing() {
@@ -1032,6 +1901,324 @@ And some inline `code` as well."#;
         );
     }
 
+    #[test]
+    fn test_code_block_meta_parsing() {
+        let meta = CodeBlockMeta::parse(r#"title="main.rs" {1,3-5} showLineNumbers"#);
+        assert_eq!(meta.title, Some("main.rs".to_string()));
+        assert_eq!(meta.highlight_lines, vec![(1, 1), (3, 5)]);
+        assert!(meta.show_line_numbers);
+        assert!(meta.is_highlighted(4));
+        assert!(!meta.is_highlighted(2));
+
+        let meta = CodeBlockMeta::parse("title='lib.rs' unknown=flag");
+        assert_eq!(meta.title, Some("lib.rs".to_string()));
+        assert!(!meta.show_line_numbers);
+
+        assert!(CodeBlockMeta::parse("").is_empty());
+        assert!(CodeBlockMeta::parse("{5-3}").is_empty());
+    }
+
+    #[test]
+    fn test_split_highlighted_lines() {
+        let html = "<span style=\"color:red;\">fn main() {\n    x</span>\n}\n";
+        let lines = split_highlighted_lines(html);
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "<span style=\"color:red;\">fn main() {</span>");
+        assert_eq!(lines[1], "<span style=\"color:red;\">    x</span>");
+        assert_eq!(lines[2], "}");
+    }
+
+    #[test]
+    fn test_code_block_enhancements_rendering() {
+        let manager = ThemeManager::new();
+        let markdown =
+            "```rust title=\"main.rs\" {2} showLineNumbers\nfn main() {\n    run();\n}\n```";
+
+        let html = manager
+            .render(markdown, "lapis", "github", &HashMap::new())
+            .unwrap();
+
+        assert!(html.contains("main.rs"));
+        assert!(html.contains("class=\"code-title\""));
+        assert!(html.contains("class=\"code-line-highlight\""));
+        assert_eq!(html.matches("class=\"code-line-number\"").count(), 3);
+        assert!(!html.contains("data-meta"));
+        // Theme styles must be inlined onto the generated elements
+        assert!(html.contains("user-select: none"));
+        assert!(html.contains("rgba(72, 112, 172, 0.15)"));
+    }
+
+    #[test]
+    fn test_code_block_without_meta_is_unchanged() {
+        let manager = ThemeManager::new();
+        let html = manager
+            .render(
+                "```rust\nfn main() {}\n```",
+                "default",
+                "github",
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert!(!html.contains("code-title"));
+        assert!(!html.contains("code-line-number"));
+        assert!(!html.contains("code-line-highlight"));
+    }
+
+    #[test]
+    fn test_code_overflow_parsing() {
+        assert_eq!("wrap".parse::<CodeOverflow>().unwrap(), CodeOverflow::Wrap);
+        assert_eq!(
+            " Hint ".parse::<CodeOverflow>().unwrap(),
+            CodeOverflow::Hint
+        );
+        assert_eq!(CodeOverflow::default().as_str(), "scroll");
+        assert!("fold".parse::<CodeOverflow>().is_err());
+    }
+
+    #[test]
+    fn test_wrap_highlighted_line() {
+        let line = r#"<span style="color:#a71d5d;">abcdefgh</span>&lt;ij"#;
+        let rows = wrap_highlighted_line(line, 6);
+
+        assert_eq!(
+            rows,
+            vec![
+                r#"<span style="color:#a71d5d;">abcdef</span>"#,
+                r#"<span style="color:#a71d5d;">gh</span>&lt;i"#,
+                "j",
+            ]
+        );
+        assert_eq!(wrap_highlighted_line("short", 10), vec!["short"]);
+    }
+
+    #[test]
+    fn test_code_overflow_rendering() {
+        let manager = ThemeManager::new();
+        let markdown = format!("```rust\nlet x = \"{}\";\n```", "a".repeat(80));
+
+        let scroll = manager
+            .render(&markdown, "default", "github", &HashMap::new())
+            .unwrap();
+        assert!(scroll.contains("overflow-x: auto"));
+        assert!(!scroll.contains("↪"));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("code_overflow".to_string(), "wrap".to_string());
+        metadata.insert("code_columns".to_string(), "40".to_string());
+        let wrapped = manager
+            .render(&markdown, "default", "github", &metadata)
+            .unwrap();
+        assert!(wrapped.contains("white-space: pre-wrap"));
+        assert_eq!(wrapped.matches("↪").count(), 2);
+
+        metadata.insert("code_overflow".to_string(), "hint".to_string());
+        let hinted = manager
+            .render(&markdown, "default", "github", &metadata)
+            .unwrap();
+        assert!(hinted.contains("左右滑动查看完整代码"));
+
+        let short = manager
+            .render("```rust\nfn main() {}\n```", "default", "github", &metadata)
+            .unwrap();
+        assert!(!short.contains("左右滑动查看完整代码"));
+    }
+
+    #[test]
+    fn test_find_long_code_lines() {
+        let manager = ThemeManager::new();
+        let markdown = format!(
+            "```sh\necho ok\n```\n\ntext\n\n```rust title=\"a.rs\"\nfn a() {{}}\n// {}\n```",
+            "中".repeat(30)
+        );
+
+        let long_lines = manager.find_long_code_lines(&markdown, 48);
+        assert_eq!(
+            long_lines,
+            vec![LongCodeLine {
+                block_index: 1,
+                language: Some("rust".to_string()),
+                line_number: 2,
+                width: 63,
+            }]
+        );
+        assert!(manager.find_long_code_lines(&markdown, 80).is_empty());
+    }
+
+    #[test]
+    fn test_admonition_rendering() {
+        let manager = ThemeManager::new();
+        let markdown = convert_admonition_containers(
+            "> [!WARNING]\n> Mind the gap\n\n:::tip 小贴士\nUse **fmt**.\n:::\n\nAfter",
+        );
+
+        for theme in BuiltinTheme::all() {
+            let html = manager
+                .render(&markdown, theme.as_str(), "github", &HashMap::new())
+                .unwrap();
+
+            assert!(html.contains("⚠️</span>Warning"), "{}", theme.as_str());
+            assert!(html.contains("💡</span>小贴士"), "{}", theme.as_str());
+            assert!(html.contains("Mind the gap"));
+            assert!(!html.contains(":::"));
+            // Theme variables are resolved into inline styles
+            assert!(
+                !html.contains("var(--alert-"),
+                "unresolved alert variable in {}",
+                theme.as_str()
+            );
+        }
+
+        let html = manager
+            .render(&markdown, "lapis", "github", &HashMap::new())
+            .unwrap();
+        assert!(html.contains("border-left-color: #9a6700"));
+        assert!(html.contains("border-left-color: #1a7f37"));
+    }
+
+    #[test]
+    fn test_number_outline() {
+        let heading = |level, text: &str| Heading {
+            level,
+            text: text.to_string(),
+        };
+        let outline = number_outline(&[
+            heading(1, "Title"),
+            heading(3, "Preface"),
+            heading(2, "Intro"),
+            heading(3, "Setup"),
+            heading(4, "Deep"),
+            heading(3, "Usage"),
+            heading(2, "Outro"),
+        ]);
+
+        let numbers: Vec<_> = outline.iter().map(|e| e.number.as_str()).collect();
+        assert_eq!(numbers, vec!["1", "1", "1.1", "1.2", "2"]);
+    }
+
+    #[test]
+    fn test_toc_rendering() {
+        let manager = ThemeManager::new();
+        let markdown = "# Title\n\n[TOC]\n\n## Intro\n\n### Setup & run\n\n## Outro";
+
+        let html = manager
+            .render(markdown, "lapis", "github", &HashMap::new())
+            .unwrap();
+        assert!(!html.contains("[TOC]"));
+        assert!(html.contains("目录"));
+        assert!(html.contains(
+            r#"toc-number" style="margin-right: .5em;color: #4870ac;">1.1</span>Setup &amp; run"#
+        ));
+        // The TOC replaces the marker below the title
+        assert!(html.find("目录").unwrap() > html.find("Title").unwrap());
+        assert!(!html.contains("heading-number"));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("toc".to_string(), "true".to_string());
+        metadata.insert("toc_title".to_string(), "Contents".to_string());
+        metadata.insert("heading_numbers".to_string(), "true".to_string());
+        let html = manager
+            .render("## Intro\n\n### Setup", "default", "github", &metadata)
+            .unwrap();
+        assert!(html.contains("Contents"));
+        assert!(html.contains(">1.1</span>Setup</h3>"));
+
+        // Raw HTML headings are not in the outline, so they do not shift the numbers
+        let html = manager
+            .render(
+                "<h2>Raw</h2>\n\n## **Intro**\n\n### `run`",
+                "default",
+                "github",
+                &metadata,
+            )
+            .unwrap();
+        assert!(html.contains(">1</span><strong>Intro</strong></h2>"));
+        assert!(html.contains(">1.1</span><code"));
+        assert_eq!(html.matches("heading-number").count(), 2);
+
+        // Without headings or opt-in, nothing is added
+        let html = manager
+            .render("Just text", "default", "github", &metadata)
+            .unwrap();
+        assert!(!html.contains("Contents"));
+    }
+
+    #[test]
+    fn test_series_navigation_partial() {
+        let manager = ThemeManager::new();
+        let partials = Partials {
+            series: Some(
+                r#"<section class="series-nav"><p class="series-title">系列：Rust</p></section>"#
+                    .to_string(),
+            ),
+            footer: Some("Follow us".to_string()),
+            ..Partials::default()
+        };
+
+        for theme in BuiltinTheme::all() {
+            let html = manager
+                .render_with_partials("Body", theme.as_str(), "github", &HashMap::new(), &partials)
+                .unwrap();
+            let series_pos = html.find("系列：Rust").unwrap();
+            assert!(html.find("Body").unwrap() < series_pos);
+            assert!(series_pos < html.find("Follow us").unwrap());
+            assert!(
+                html.contains(r#"<section class="series-nav" style="#),
+                "{theme:?} does not style the series navigation"
+            );
+        }
+    }
+
+    #[test]
+    fn test_figure_rendering() {
+        let manager = ThemeManager::new();
+        let markdown = "![Sunset](a.png \"Evening\")\n\n![left](b.png) ![](c.png)\n\nText with ![inline](d.png) image";
+
+        let html = manager.render_fragment(markdown, "github");
+        assert!(html.contains(r#"<figure><img src="a.png" alt="Sunset" title="Evening" /><figcaption>Evening</figcaption></figure>"#));
+        assert!(html.contains(r#"<section class="image-grid"><figure><img src="b.png" alt="left" /><figcaption>left</figcaption></figure><figure><img src="c.png" alt="" /></figure></section>"#));
+        // Images inside running text are left alone
+        assert!(html.contains(r#"Text with <img src="d.png" alt="inline" /> image"#));
+
+        let numbered = number_figures(&html, "图");
+        assert!(numbered.contains(
+            r#"<figcaption><span class="figure-number">图 1</span>Evening</figcaption>"#
+        ));
+        assert!(
+            numbered
+                .contains(r#"<figcaption><span class="figure-number">图 3</span></figcaption>"#)
+        );
+
+        let mut metadata = HashMap::new();
+        metadata.insert("figure_numbers".to_string(), "true".to_string());
+        metadata.insert("figure_label".to_string(), "Figure".to_string());
+        let html = manager
+            .render("![a](a.png)", "lapis", "github", &metadata)
+            .unwrap();
+        assert!(html.contains("Figure 1</span>a</figcaption>"));
+    }
+
+    #[test]
+    fn test_cjk_typography_opt_in() {
+        let manager = ThemeManager::new();
+        let markdown = "我用Rust写了`cargo`插件,很好用!";
+
+        let html = manager
+            .render(markdown, "default", "github", &HashMap::new())
+            .unwrap();
+        assert!(html.contains("我用Rust写了"));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("cjk_typography".to_string(), "true".to_string());
+        let html = manager
+            .render(markdown, "default", "github", &metadata)
+            .unwrap();
+        assert!(html.contains("我用 Rust 写了"));
+        assert!(html.contains("插件，很好用！"));
+        assert!(html.contains(">cargo</code>"));
+    }
+
     // Helper function to count CSS variables in a theme
     fn theme_css_var_count(css: &str) -> usize {
         css.matches("var(--").count()
@@ -0,0 +1,302 @@
+//! Token providers that do not hold the AppSecret.
+//!
+//! [`WeChatClient::with_token_provider`](crate::WeChatClient::with_token_provider) accepts
+//! any [`TokenProvider`]. Besides [`TokenManager`](crate::auth::TokenManager), which
+//! calls WeChat with the AppSecret, this module provides:
+//!
+//! - [`HttpTokenProvider`] - Fetches tokens from a central token service
+//! - [`StaticTokenProvider`] - Always returns the same token, for tests and one-off scripts
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::config::Config;
+//! use wechat_pub_rs::http::WeChatHttpClient;
+//! use wechat_pub_rs::token_provider::HttpTokenProvider;
+//! use wechat_pub_rs::WeChatClient;
+//! use std::sync::Arc;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let provider = HttpTokenProvider::new(
+//!     "https://tokens.internal/wechat/wx1234567890abcdef",
+//!     Arc::new(WeChatHttpClient::new()?),
+//! )
+//! .header("Authorization", "Bearer service-credential")?;
+//!
+//! let client = WeChatClient::with_token_provider(Arc::new(provider), Config::default())?;
+//! let draft_id = client.upload("article.md").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::auth::AccessToken;
+use crate::error::{Result, WeChatError};
+use crate::http::{AccessTokenResponse, WeChatHttpClient, WeChatResponse};
+use crate::traits::TokenProvider;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Gets tokens from an internal service that holds the AppSecret.
+///
+/// The service answers a GET request with the same JSON as WeChat's token endpoint,
+/// `{"access_token": "...", "expires_in": 7200}`, or with an `errcode` and `errmsg`.
+/// Tokens are cached until shortly before they expire. When a refresh is needed because
+/// WeChat rejected the token, the request carries `force_refresh=true` in its query so
+/// the service can replace the token it hands out.
+pub struct HttpTokenProvider {
+    url: String,
+    headers: HeaderMap,
+    http_client: Arc<WeChatHttpClient>,
+    token_cache: RwLock<Option<AccessToken>>,
+    refresh_lock: Mutex<()>,
+}
+
+impl HttpTokenProvider {
+    /// Creates a provider for the token service at `url`.
+    pub fn new(url: impl Into<String>, http_client: Arc<WeChatHttpClient>) -> Self {
+        Self {
+            url: url.into(),
+            headers: HeaderMap::new(),
+            http_client,
+            token_cache: RwLock::new(None),
+            refresh_lock: Mutex::new(()),
+        }
+    }
+
+    /// Adds a header sent with every token request, such as credentials for the service.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| WeChatError::config_error(format!("Invalid header name: {e}")))?;
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|e| WeChatError::config_error(format!("Invalid header value: {e}")))?;
+        value.set_sensitive(true);
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Gets a cached token if it's still valid.
+    async fn get_cached_token(&self) -> Option<String> {
+        let cache = self.token_cache.read().await;
+        cache
+            .as_ref()
+            .filter(|token| !token.is_expired(60))
+            .map(|token| token.token.clone())
+    }
+
+    /// Fetches a token from the service and caches it. Callers must hold the refresh lock.
+    async fn fetch_token(&self, force: bool) -> Result<String> {
+        let url = if force {
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+            format!("{}{separator}force_refresh=true", self.url)
+        } else {
+            self.url.clone()
+        };
+
+        info!("Fetching access token from the token service");
+        let response: WeChatResponse<AccessTokenResponse> = self
+            .http_client
+            .get_with_headers(&url, &self.headers)
+            .await?
+            .json()
+            .await?;
+        let response = response.into_result()?;
+
+        let token = AccessToken::new(response.access_token, response.expires_in);
+        let token_string = token.token.clone();
+        *self.token_cache.write().await = Some(token);
+        Ok(token_string)
+    }
+}
+
+#[async_trait]
+impl TokenProvider for HttpTokenProvider {
+    async fn get_token(&self) -> Result<String> {
+        if let Some(token) = self.get_cached_token().await {
+            return Ok(token);
+        }
+
+        let _guard = self.refresh_lock.lock().await;
+        if let Some(token) = self.get_cached_token().await {
+            return Ok(token);
+        }
+        self.fetch_token(false).await
+    }
+
+    async fn refresh_token(&self) -> Result<String> {
+        let _guard = self.refresh_lock.lock().await;
+        self.fetch_token(true).await
+    }
+
+    async fn refresh_rejected(&self, rejected: &str) -> Result<String> {
+        let _guard = self.refresh_lock.lock().await;
+        if let Some(token) = self.get_cached_token().await
+            && token != rejected
+        {
+            return Ok(token);
+        }
+
+        warn!("Access token from the token service was rejected by WeChat, fetching a new one");
+        let token = self.fetch_token(false).await?;
+        if token != rejected {
+            return Ok(token);
+        }
+        self.fetch_token(true).await
+    }
+
+    async fn is_token_expired(&self) -> bool {
+        self.get_cached_token().await.is_none()
+    }
+
+    async fn token_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.token_cache
+            .read()
+            .await
+            .as_ref()
+            .map(|token| token.expires_at)
+    }
+}
+
+impl fmt::Debug for HttpTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Header values usually carry credentials for the token service
+        f.debug_struct("HttpTokenProvider")
+            .field("url", &self.url)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Always returns the same token, which is never refreshed.
+///
+/// Useful in tests against a mock server and for short scripts given a token by hand.
+/// When WeChat rejects the token, the request fails.
+#[derive(Clone)]
+pub struct StaticTokenProvider {
+    token: String,
+}
+
+impl StaticTokenProvider {
+    /// Creates a provider for a fixed token.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticTokenProvider {
+    async fn get_token(&self) -> Result<String> {
+        Ok(self.token.clone())
+    }
+
+    async fn refresh_token(&self) -> Result<String> {
+        Ok(self.token.clone())
+    }
+
+    async fn refresh_rejected(&self, _rejected: &str) -> Result<String> {
+        // There is no other token to replay the request with
+        Err(WeChatError::InvalidToken)
+    }
+
+    async fn is_token_expired(&self) -> bool {
+        false
+    }
+
+    async fn token_expires_at(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+}
+
+impl fmt::Debug for StaticTokenProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticTokenProvider")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockServer;
+
+    #[tokio::test]
+    async fn test_http_token_provider() {
+        let server = MockServer::tokens().await;
+        let url = format!("{}/wechat/wx_app", server.url);
+        let provider = HttpTokenProvider::new(url, Arc::new(WeChatHttpClient::new().unwrap()))
+            .header("Authorization", "Bearer service-secret")
+            .unwrap();
+
+        assert_eq!(provider.get_token().await.unwrap(), "token1");
+        assert_eq!(provider.get_token().await.unwrap(), "token1");
+        assert!(!provider.is_token_expired().await);
+
+        // A rejected token is replaced with a forced refresh only if the service repeats it
+        assert_eq!(provider.refresh_rejected("token1").await.unwrap(), "token2");
+        assert_eq!(provider.refresh_token().await.unwrap(), "token3");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0].starts_with("GET /wechat/wx_app HTTP/1.1"));
+        assert!(requests[0].contains("authorization: Bearer service-secret"));
+        assert!(requests[1].starts_with("GET /wechat/wx_app HTTP/1.1"));
+        assert!(requests[2].starts_with("GET /wechat/wx_app?force_refresh=true HTTP/1.1"));
+
+        let debug = format!("{provider:?}");
+        assert!(debug.contains("authorization"));
+        assert!(!debug.contains("service-secret"));
+    }
+
+    #[tokio::test]
+    async fn test_static_token_provider() {
+        let provider = StaticTokenProvider::new("fixed");
+        assert_eq!(provider.get_token().await.unwrap(), "fixed");
+        assert!(matches!(
+            provider.refresh_rejected("fixed").await,
+            Err(WeChatError::InvalidToken)
+        ));
+        assert!(provider.token_expires_at().await.is_none());
+        assert!(!format!("{provider:?}").contains("fixed"));
+    }
+
+    #[tokio::test]
+    async fn test_static_token_is_not_replayed() {
+        use crate::config::{Config, HttpConfig};
+
+        let server =
+            MockServer::serve([r#"{"errcode":40001,"errmsg":"invalid credential"}"#]).await;
+        let config = Config::builder()
+            .http(HttpConfig::builder().base_url(server.url.clone()).build())
+            .build();
+        let http_client = WeChatHttpClient::with_config(config).unwrap();
+
+        let error = http_client
+            .post_json_authorized(
+                "/cgi-bin/draft/add",
+                &StaticTokenProvider::new("fixed"),
+                &serde_json::json!({}),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error.kind(), WeChatError::InvalidToken));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_with_token_provider() {
+        let client = crate::WeChatClient::with_token_provider(
+            Arc::new(StaticTokenProvider::new("fixed")),
+            crate::Config::default(),
+        )
+        .unwrap();
+        assert_eq!(client.refresh_token().await.unwrap(), "fixed");
+        assert!(client.get_token_info().await.is_none());
+    }
+}
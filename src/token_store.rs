@@ -0,0 +1,308 @@
+//! Storage for access tokens shared between processes.
+//!
+//! A [`TokenManager`](crate::auth::TokenManager) keeps its token in memory, and reads
+//! and writes it through a [`TokenStore`] so that other managers can reuse it. Cron jobs,
+//! CLI runs and a web service pointed at the same [`FileTokenStore`] fetch one token
+//! between them instead of each burning the daily `token` quota, and never invalidate
+//! each other's token by refreshing at the same time.
+//!
+//! ## Stores
+//!
+//! - [`MemoryTokenStore`] - Shared between managers in one process (the default)
+//! - [`FileTokenStore`] - A JSON file; refreshes are serialised with a file lock
+//!
+//! Other backends, such as Redis, implement [`TokenStore`] directly.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use wechat_pub_rs::auth::TokenManager;
+//! use wechat_pub_rs::http::WeChatHttpClient;
+//! use wechat_pub_rs::token_store::FileTokenStore;
+//! use std::sync::Arc;
+//!
+//! # async fn example() -> wechat_pub_rs::Result<()> {
+//! let http_client = Arc::new(WeChatHttpClient::new()?);
+//! let token_manager = TokenManager::new("app_id", "app_secret", http_client)
+//!     .with_store(Arc::new(FileTokenStore::new("/var/lib/wechat/tokens.json")));
+//! let token = token_manager.get_access_token().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::auth::AccessToken;
+use crate::error::{Result, WeChatError};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Held while a token is refreshed; dropping it lets other refreshes proceed.
+pub type RefreshGuard = Box<dyn Send + Sync>;
+
+/// Shared storage for access tokens, keyed by AppID.
+///
+/// `save` and `clear` are called while the guard from [`lock_refresh`](Self::lock_refresh)
+/// is held, so implementations must not take the same lock inside them.
+#[async_trait]
+pub trait TokenStore: Send + Sync + fmt::Debug {
+    /// Reads the stored token of an AppID, which may have expired.
+    async fn load(&self, app_id: &str) -> Result<Option<AccessToken>>;
+
+    /// Stores the token of an AppID, replacing the previous one.
+    async fn save(&self, app_id: &str, token: &AccessToken) -> Result<()>;
+
+    /// Removes the stored token of an AppID.
+    async fn clear(&self, app_id: &str) -> Result<()>;
+
+    /// Waits until no other manager sharing this store is refreshing the token.
+    ///
+    /// The default does not coordinate at all, for stores only used by one manager.
+    async fn lock_refresh(&self, app_id: &str) -> Result<RefreshGuard> {
+        let _ = app_id;
+        Ok(Box::new(()))
+    }
+}
+
+/// Tokens kept in memory, shared by cloning the store.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTokenStore {
+    tokens: Arc<RwLock<HashMap<String, AccessToken>>>,
+    refresh_lock: Arc<Mutex<()>>,
+}
+
+impl MemoryTokenStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self, app_id: &str) -> Result<Option<AccessToken>> {
+        Ok(self.tokens.read().await.get(app_id).cloned())
+    }
+
+    async fn save(&self, app_id: &str, token: &AccessToken) -> Result<()> {
+        self.tokens
+            .write()
+            .await
+            .insert(app_id.to_string(), token.clone());
+        Ok(())
+    }
+
+    async fn clear(&self, app_id: &str) -> Result<()> {
+        self.tokens.write().await.remove(app_id);
+        Ok(())
+    }
+
+    async fn lock_refresh(&self, _app_id: &str) -> Result<RefreshGuard> {
+        Ok(Box::new(Arc::clone(&self.refresh_lock).lock_owned().await))
+    }
+}
+
+/// Tokens kept in a JSON file, shared between processes.
+///
+/// Refreshes take an exclusive lock on a `.lock` file next to it, so only one process
+/// fetches a new token while the others wait and then reuse it. The file is written
+/// atomically and, on Unix, is readable by its owner only.
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Creates a store backed by `path`; the file is created on the first save.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Gets the path of the token file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        self.path.with_file_name(name)
+    }
+
+    fn file_error(&self, path: &Path, action: &str, e: impl fmt::Display) -> WeChatError {
+        WeChatError::file_error(
+            path.display().to_string(),
+            format!("Failed to {action} token store: {e}"),
+        )
+    }
+
+    async fn read_all(&self) -> Result<HashMap<String, AccessToken>> {
+        match tokio::fs::read(&self.path).await {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(|e| self.file_error(&self.path, "parse", e))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(self.file_error(&self.path, "read", e)),
+        }
+    }
+
+    async fn write_all(&self, tokens: &HashMap<String, AccessToken>) -> Result<()> {
+        let data = serde_json::to_vec_pretty(tokens)?;
+        let path = self.path.clone();
+        let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(format!(".{}.tmp", std::process::id()));
+        let temp_path = path.with_file_name(temp_name);
+
+        let written = tokio::task::spawn_blocking({
+            let temp_path = temp_path.clone();
+            move || -> std::io::Result<()> {
+                use std::io::Write;
+                let mut file = private_file(&temp_path, true)?;
+                file.write_all(&data)?;
+                file.sync_all()?;
+                std::fs::rename(&temp_path, &path)
+            }
+        })
+        .await
+        .map_err(|e| WeChatError::Internal {
+            message: format!("Token store write task failed: {e}"),
+        })?;
+
+        written.map_err(|e| self.file_error(&temp_path, "write", e))
+    }
+}
+
+#[async_trait]
+impl TokenStore for FileTokenStore {
+    async fn load(&self, app_id: &str) -> Result<Option<AccessToken>> {
+        Ok(self.read_all().await?.remove(app_id))
+    }
+
+    async fn save(&self, app_id: &str, token: &AccessToken) -> Result<()> {
+        let mut tokens = self.read_all().await?;
+        tokens.insert(app_id.to_string(), token.clone());
+        self.write_all(&tokens).await
+    }
+
+    async fn clear(&self, app_id: &str) -> Result<()> {
+        let mut tokens = self.read_all().await?;
+        if tokens.remove(app_id).is_some() {
+            self.write_all(&tokens).await?;
+        }
+        Ok(())
+    }
+
+    async fn lock_refresh(&self, _app_id: &str) -> Result<RefreshGuard> {
+        let lock_path = self.lock_path();
+        let locked = tokio::task::spawn_blocking({
+            let lock_path = lock_path.clone();
+            move || -> std::io::Result<File> {
+                let file = private_file(&lock_path, false)?;
+                file.lock()?;
+                Ok(file)
+            }
+        })
+        .await
+        .map_err(|e| WeChatError::Internal {
+            message: format!("Token store lock task failed: {e}"),
+        })?;
+
+        // The lock is released when the file is closed
+        let file = locked.map_err(|e| self.file_error(&lock_path, "lock", e))?;
+        Ok(Box::new(file))
+    }
+}
+
+/// Opens a file for writing that only its owner may read.
+fn private_file(path: &Path, truncate: bool) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(truncate);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        let store = MemoryTokenStore::new();
+        assert!(store.load("wx_a").await.unwrap().is_none());
+
+        store
+            .save("wx_a", &AccessToken::new("token_a".to_string(), 7200))
+            .await
+            .unwrap();
+        // Clones share their tokens
+        let shared = store.clone();
+        assert_eq!(shared.load("wx_a").await.unwrap().unwrap().token, "token_a");
+        assert!(shared.load("wx_b").await.unwrap().is_none());
+
+        shared.clear("wx_a").await.unwrap();
+        assert!(store.load("wx_a").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tokens.json");
+        let store = FileTokenStore::new(&path);
+        assert!(store.load("wx_a").await.unwrap().is_none());
+
+        {
+            let _guard = store.lock_refresh("wx_a").await.unwrap();
+            store
+                .save("wx_a", &AccessToken::new("token_a".to_string(), 7200))
+                .await
+                .unwrap();
+            store
+                .save("wx_b", &AccessToken::new("token_b".to_string(), 7200))
+                .await
+                .unwrap();
+        }
+
+        // Another store on the same file sees both tokens
+        let other = FileTokenStore::new(&path);
+        assert_eq!(other.load("wx_a").await.unwrap().unwrap().token, "token_a");
+        other.clear("wx_a").await.unwrap();
+        assert!(store.load("wx_a").await.unwrap().is_none());
+        assert_eq!(store.load("wx_b").await.unwrap().unwrap().token, "token_b");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_file_store_lock_is_exclusive() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileTokenStore::new(dir.path().join("tokens.json"));
+
+        let guard = store.lock_refresh("wx_a").await.unwrap();
+        let waiting = tokio::spawn({
+            let store = store.clone();
+            async move { store.lock_refresh("wx_a").await.map(|_| ()) }
+        });
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!waiting.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(std::time::Duration::from_secs(5), waiting)
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+    }
+}
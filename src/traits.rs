@@ -16,14 +16,27 @@ use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 
 /// Trait for managing WeChat access tokens with automatic refresh capabilities.
+///
+/// Every API call of [`WeChatClient`](crate::WeChatClient) gets its token from a provider.
+/// [`TokenManager`](crate::auth::TokenManager) talks to WeChat with the AppSecret, while
+/// the providers in [`token_provider`](crate::token_provider) get tokens from elsewhere.
 #[async_trait]
-pub trait TokenProvider: Send + Sync {
+pub trait TokenProvider: Send + Sync + std::fmt::Debug {
     /// Gets a valid access token, refreshing if necessary.
     async fn get_token(&self) -> Result<String>;
 
     /// Forces a token refresh.
     async fn refresh_token(&self) -> Result<String>;
 
+    /// Replaces a token that WeChat rejected and returns a fresh one.
+    ///
+    /// Called once before a rejected request is replayed. Defaults to
+    /// [`refresh_token`](Self::refresh_token).
+    async fn refresh_rejected(&self, rejected: &str) -> Result<String> {
+        let _ = rejected;
+        self.refresh_token().await
+    }
+
     /// Checks if the current token is expired.
     async fn is_token_expired(&self) -> bool;
 
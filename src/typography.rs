@@ -0,0 +1,273 @@
+//! CJK typography normalisation for mixed Chinese/English articles.
+//!
+//! The pass runs on comrak text nodes only, so markdown syntax, code spans, code
+//! blocks and inline HTML are never touched. Within text it:
+//!
+//! - inserts a space between CJK characters and Latin letters or digits
+//! - converts half-width punctuation that follows CJK text to its full-width form
+//! - leaves URLs unchanged, separating them from CJK text with a space
+//!
+//! ## Usage
+//!
+//! Enable it per article with `cjk_typography: true` in frontmatter, or call
+//! [`normalize_text`] directly:
+//!
+//! ```rust
+//! use wechat_pub_rs::typography::normalize_text;
+//!
+//! assert_eq!(normalize_text("使用Rust编写,速度很快!"), "使用 Rust 编写，速度很快！");
+//! ```
+
+use comrak::nodes::{AstNode, NodeValue};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches URLs that must be left as written.
+static URL_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(?:https?://|www\.)[^\s\p{Han}，。！？；：（）]+").unwrap());
+
+/// Matches an inline HTML tag, capturing the closing slash and the tag name.
+static HTML_TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^<(/?)([a-zA-Z][a-zA-Z0-9-]*)[^>]*?(/?)>$").unwrap());
+
+/// HTML elements that have no closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Applies the typography pass to every text node below `root`.
+///
+/// The text of autolinks is skipped, since it is the URL itself, and so is text
+/// wrapped in inline HTML such as `<b>标签tag</b>`.
+pub fn apply_cjk_typography<'a>(root: &'a AstNode<'a>) {
+    for node in root.descendants() {
+        let is_autolink_text = node.parent().is_some_and(|parent| {
+            matches!(
+                (&parent.data.borrow().value, &node.data.borrow().value),
+                (NodeValue::Link(link), NodeValue::Text(text)) if is_autolink(&link.url, text)
+            )
+        });
+        if is_autolink_text || is_inside_inline_html(node) {
+            continue;
+        }
+
+        if let NodeValue::Text(ref mut text) = node.data.borrow_mut().value {
+            *text = normalize_text(text);
+        }
+    }
+}
+
+/// Checks whether a link's text is its URL, without the scheme or `mailto:` comrak adds.
+fn is_autolink(url: &str, text: &str) -> bool {
+    let bare = url
+        .strip_prefix("mailto:")
+        .or_else(|| url.split_once("://").map(|(_, rest)| rest))
+        .unwrap_or(url);
+    text == url || text == bare
+}
+
+/// Checks whether a node sits between an opening inline HTML tag and its closing tag.
+fn is_inside_inline_html<'a>(node: &'a AstNode<'a>) -> bool {
+    node.ancestors()
+        .take_while(|ancestor| !ancestor.data.borrow().value.block())
+        .any(|ancestor| {
+            let mut depth = 0i32;
+            let mut sibling = ancestor.previous_sibling();
+            while let Some(previous) = sibling {
+                if let NodeValue::HtmlInline(ref html) = previous.data.borrow().value
+                    && let Some(caps) = HTML_TAG_REGEX.captures(html.trim())
+                    && !VOID_ELEMENTS.contains(&caps[2].to_ascii_lowercase().as_str())
+                    && caps[3].is_empty()
+                {
+                    depth += if caps[1].is_empty() { 1 } else { -1 };
+                }
+                sibling = previous.previous_sibling();
+            }
+            depth > 0
+        })
+}
+
+/// Normalises spacing and punctuation width in a run of plain text.
+pub fn normalize_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let mut last_end = 0;
+
+    for url in URL_REGEX.find_iter(text) {
+        normalized.push_str(&normalize_segment(&text[last_end..url.start()]));
+        if normalized.chars().last().is_some_and(is_cjk) {
+            normalized.push(' ');
+        }
+        normalized.push_str(url.as_str());
+        if text[url.end()..].chars().next().is_some_and(is_cjk) {
+            normalized.push(' ');
+        }
+        last_end = url.end();
+    }
+    normalized.push_str(&normalize_segment(&text[last_end..]));
+
+    normalized
+}
+
+/// Normalises a segment of text that contains no URLs.
+fn normalize_segment(segment: &str) -> String {
+    let chars: Vec<char> = segment.chars().collect();
+    let mut output = String::with_capacity(segment.len());
+    // Whether each open parenthesis was converted, so its closing one matches
+    let mut parens: Vec<bool> = Vec::new();
+
+    for (index, &ch) in chars.iter().enumerate() {
+        let prev = output.chars().last();
+        let next = chars.get(index + 1).copied();
+        let after_cjk = prev.is_some_and(is_cjk);
+
+        // Full-width punctuation carries its own spacing
+        if ch == ' ' && prev.is_some_and(is_fullwidth_punctuation) {
+            continue;
+        }
+
+        let converted = match ch {
+            ',' if after_cjk => '，',
+            '!' if after_cjk => '！',
+            '?' if after_cjk => '？',
+            ':' if after_cjk => '：',
+            ';' if after_cjk => '；',
+            '.' if after_cjk && next.is_none_or(|c| c.is_whitespace()) => '。',
+            '(' => {
+                let convert = after_cjk || next.is_some_and(is_cjk);
+                parens.push(convert);
+                if convert { '（' } else { '(' }
+            }
+            ')' if parens.pop().unwrap_or(false) || after_cjk => '）',
+            _ => ch,
+        };
+
+        if let Some(prev) = prev
+            && needs_space(prev, converted)
+        {
+            output.push(' ');
+        }
+        output.push(converted);
+    }
+
+    output
+}
+
+/// Checks whether a space belongs between two adjacent characters.
+fn needs_space(prev: char, next: char) -> bool {
+    (is_cjk(prev) && next.is_ascii_alphanumeric()) || (prev.is_ascii_alphanumeric() && is_cjk(next))
+}
+
+/// Checks whether a character is a CJK ideograph, kana or hangul syllable.
+///
+/// Unlike [`crate::utils::is_wide_char`], CJK punctuation is not included.
+fn is_cjk(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x3040..=0x30FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0x20000..=0x2FFFD
+    )
+}
+
+/// Checks whether a character is full-width punctuation.
+fn is_fullwidth_punctuation(ch: char) -> bool {
+    matches!(
+        ch,
+        '，' | '。' | '！' | '？' | '：' | '；' | '（' | '）' | '、' | '“' | '”' | '《' | '》'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spacing_between_cjk_and_latin() {
+        assert_eq!(
+            normalize_text("我用Rust写了3个工具"),
+            "我用 Rust 写了 3 个工具"
+        );
+        assert_eq!(normalize_text("已经是 Rust 风格"), "已经是 Rust 风格");
+        assert_eq!(normalize_text("plain English text."), "plain English text.");
+    }
+
+    #[test]
+    fn test_punctuation_width() {
+        assert_eq!(normalize_text("你好, 世界!"), "你好，世界！");
+        assert_eq!(normalize_text("时间:12:00"), "时间：12:00");
+        assert_eq!(normalize_text("使用(Rust)编写"), "使用（Rust）编写");
+        assert_eq!(normalize_text("结束了."), "结束了。");
+        // Decimals and English sentences are untouched
+        assert_eq!(normalize_text("版本1.2发布"), "版本 1.2 发布");
+        assert_eq!(
+            normalize_text("Hello, world (again)."),
+            "Hello, world (again)."
+        );
+    }
+
+    #[test]
+    fn test_urls_are_preserved() {
+        assert_eq!(
+            normalize_text("详见https://example.com/a,b页面"),
+            "详见 https://example.com/a,b 页面"
+        );
+    }
+
+    #[test]
+    fn test_ast_pass_skips_code_and_html() {
+        use comrak::{Arena, Options, format_html, parse_document};
+
+        let mut options = Options::default();
+        options.extension.autolink = true;
+        let arena = Arena::new();
+        let root = parse_document(
+            &arena,
+            "中文English `代码code` <b>标签tag</b> https://a.com\n\n```\n代码code\n```",
+            &options,
+        );
+        apply_cjk_typography(root);
+
+        let mut html = Vec::new();
+        format_html(root, &options, &mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        assert!(html.contains("中文 English"));
+        assert!(html.contains("标签tag"));
+        assert!(html.contains("<code>代码code</code>"));
+        assert!(html.contains("<pre><code>代码code\n"));
+        assert!(html.contains(r#"<a href="https://a.com">https://a.com</a>"#));
+    }
+
+    #[test]
+    fn test_ast_pass_links() {
+        use comrak::{Arena, Options, format_html, parse_document};
+
+        let mut options = Options::default();
+        options.extension.autolink = true;
+        options.render.unsafe_ = true;
+        let arena = Arena::new();
+        let root = parse_document(
+            &arena,
+            "见 www.a.com 和a@b.com,<b>粗体**bold**</b>后文text<br>换行line\n\n[文档docs](https://a.com/文档docs)",
+            &options,
+        );
+        apply_cjk_typography(root);
+
+        let mut html = Vec::new();
+        format_html(root, &options, &mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        // Autolink text stays as written
+        assert!(html.contains(r#"<a href="http://www.a.com">www.a.com</a>"#));
+        assert!(html.contains(r#"<a href="mailto:a@b.com">a@b.com</a>"#));
+        // Text inside inline HTML is skipped, text after it is not
+        assert!(html.contains("<b>粗体<strong>bold</strong></b>"));
+        assert!(html.contains("后文 text<br>换行 line"));
+        // A link whose URL merely ends with its text is normalised
+        assert!(html.contains(">文档 docs</a>"));
+    }
+}
@@ -101,16 +101,20 @@
 //! - **Deduplication**: O(1) hash-based duplicate detection
 //! - **Error Recovery**: Exponential backoff with jitter for failed requests
 
-use crate::auth::TokenManager;
-use crate::error::{Result, WeChatError};
-use crate::http::{DraftResponse, MaterialUploadResponse, WeChatHttpClient, WeChatResponse};
+use crate::error::{ErrorContext, ErrorFrame, FieldViolation, Result, WeChatError};
+use crate::error_code::ApiErrorCategory;
+use crate::http::{
+    DraftResponse, MATERIAL_ENDPOINT, MaterialUploadResponse, WeChatHttpClient, WeChatResponse,
+};
 use crate::markdown::ImageRef;
+use crate::traits::{TokenProvider, Validate};
 use blake3;
 use futures::future::try_join_all;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use std::time::{Duration, Instant};
 use tokio::fs;
 use tokio::sync::{RwLock, Semaphore};
@@ -149,7 +153,29 @@ impl CachedMaterial {
 const MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
 
 /// Maximum file size for streaming downloads (50 MB)
-const MAX_DOWNLOAD_SIZE: u64 = 50 * 1024 * 1024;
+pub(crate) const MAX_DOWNLOAD_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Maximum article title length in characters
+pub const MAX_TITLE_CHARS: usize = 64;
+
+/// Maximum article author length in characters
+pub const MAX_AUTHOR_CHARS: usize = 8;
+
+/// Maximum article digest length in characters
+pub const MAX_DIGEST_CHARS: usize = 120;
+
+/// Maximum article text length in characters, not counting HTML markup
+pub const MAX_CONTENT_CHARS: usize = 20_000;
+
+/// Maximum article content size in bytes, including HTML markup (1 MB)
+pub const MAX_CONTENT_BYTES: usize = 1024 * 1024;
+
+/// Matches HTML tags, for measuring the visible text of article content.
+static HTML_TAG_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+
+/// Matches elements that WeChat rejects in article content.
+static FORBIDDEN_TAG_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)<\s*(script|iframe|form|input|textarea|select)\b").unwrap());
 
 /// Represents the result of an image upload operation.
 #[derive(Debug, Clone)]
@@ -162,6 +188,132 @@ pub struct UploadResult {
     pub url: String,
 }
 
+/// What to do when an image of an article can't be uploaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImagePolicy {
+    /// Abort the upload at the first broken image
+    #[default]
+    FailFast,
+    /// Leave broken images out of the upload and report them
+    ///
+    /// Only failures of the image itself, such as a missing file, a failed download or a
+    /// file WeChat rejects, are skipped. Failures that would affect every image, such as
+    /// rejected credentials or a used-up quota, still abort the upload.
+    SkipBroken,
+    /// Retry retryable failures up to `retries` more times, then skip the image
+    RetryThenSkip {
+        /// Additional attempts per image
+        retries: u32,
+    },
+}
+
+/// The outcome of uploading one image.
+#[derive(Debug, Clone)]
+pub enum ImageOutcome {
+    /// The image was uploaded
+    Uploaded {
+        /// The uploaded image
+        result: UploadResult,
+        /// Number of attempts made
+        attempts: u32,
+    },
+    /// The image was skipped after failing
+    Skipped {
+        /// The image as referenced in the article
+        image_ref: ImageRef,
+        /// Why the last attempt failed
+        error: WeChatError,
+        /// Number of attempts made
+        attempts: u32,
+    },
+}
+
+impl ImageOutcome {
+    /// Gets the image as referenced in the article.
+    pub fn image_ref(&self) -> &ImageRef {
+        match self {
+            ImageOutcome::Uploaded { result, .. } => &result.image_ref,
+            ImageOutcome::Skipped { image_ref, .. } => image_ref,
+        }
+    }
+
+    /// Gets the number of attempts made.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            ImageOutcome::Uploaded { attempts, .. } | ImageOutcome::Skipped { attempts, .. } => {
+                *attempts
+            }
+        }
+    }
+}
+
+/// Per-image outcomes of uploading the images of an article.
+#[derive(Debug, Clone, Default)]
+pub struct ImageUploadReport {
+    /// Outcome of each image, in article order
+    pub outcomes: Vec<ImageOutcome>,
+}
+
+impl ImageUploadReport {
+    /// Iterates over the uploaded images.
+    pub fn uploaded(&self) -> impl Iterator<Item = &UploadResult> {
+        self.outcomes.iter().filter_map(|outcome| match outcome {
+            ImageOutcome::Uploaded { result, .. } => Some(result),
+            ImageOutcome::Skipped { .. } => None,
+        })
+    }
+
+    /// Iterates over the skipped images and why they failed.
+    pub fn skipped(&self) -> impl Iterator<Item = (&ImageRef, &WeChatError)> {
+        self.outcomes.iter().filter_map(|outcome| match outcome {
+            ImageOutcome::Skipped {
+                image_ref, error, ..
+            } => Some((image_ref, error)),
+            ImageOutcome::Uploaded { .. } => None,
+        })
+    }
+
+    /// Checks whether any image was skipped.
+    pub fn has_skipped(&self) -> bool {
+        self.skipped().next().is_some()
+    }
+
+    /// Maps original image URLs to WeChat URLs.
+    ///
+    /// Skipped images map to `placeholder` if given, and otherwise keep their original URL.
+    pub fn url_mapping(&self, placeholder: Option<&str>) -> HashMap<String, String> {
+        let mut mapping: HashMap<String, String> = self
+            .uploaded()
+            .map(|result| (result.image_ref.original_url.clone(), result.url.clone()))
+            .collect();
+        if let Some(placeholder) = placeholder {
+            for (image_ref, _) in self.skipped() {
+                mapping.insert(image_ref.original_url.clone(), placeholder.to_string());
+            }
+        }
+        mapping
+    }
+}
+
+impl std::fmt::Display for ImageUploadReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let skipped: Vec<_> = self.skipped().collect();
+        write!(
+            f,
+            "{} of {} images uploaded",
+            self.outcomes.len() - skipped.len(),
+            self.outcomes.len()
+        )?;
+        if !skipped.is_empty() {
+            write!(f, ", {} skipped:", skipped.len())?;
+            for (image_ref, error) in skipped {
+                write!(f, "\n  - {}: {}", image_ref.original_url, error.kind())?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Represents a WeChat article for draft creation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Article {
@@ -183,6 +335,12 @@ pub struct Article {
     pub need_open_comment: u8,
     /// Only fans can comment (0: no, 1: yes)
     pub only_fans_can_comment: u8,
+    /// Cover image URL, returned by WeChat when reading articles
+    #[serde(default, skip_serializing)]
+    pub thumb_url: Option<String>,
+    /// Permanent link of a published article, returned by WeChat when reading it
+    #[serde(default, skip_serializing)]
+    pub url: Option<String>,
 }
 
 impl Article {
@@ -198,6 +356,8 @@ impl Article {
             thumb_media_id: None,
             need_open_comment: 0,
             only_fans_can_comment: 0,
+            thumb_url: None,
+            url: None,
         }
     }
 
@@ -233,6 +393,85 @@ impl Article {
     }
 }
 
+impl Validate for Article {
+    type Error = WeChatError;
+
+    /// Checks the article against WeChat's draft limits, reporting every violation.
+    fn validate(&self) -> std::result::Result<(), Self::Error> {
+        let mut violations = Vec::new();
+
+        let limits = [
+            ("title", &self.title, MAX_TITLE_CHARS),
+            ("author", &self.author, MAX_AUTHOR_CHARS),
+            ("digest", &self.digest, MAX_DIGEST_CHARS),
+        ];
+        for (field, value, max) in limits {
+            let length = value.chars().count();
+            if length > max {
+                violations.push(FieldViolation::new(
+                    field,
+                    format!("{length} characters exceeds the limit of {max}"),
+                ));
+            }
+        }
+        if self.title.trim().is_empty() {
+            violations.push(FieldViolation::new("title", "must not be empty"));
+        }
+
+        let text_length = HTML_TAG_REGEX
+            .replace_all(&self.content, "")
+            .chars()
+            .count();
+        if text_length > MAX_CONTENT_CHARS {
+            violations.push(FieldViolation::new(
+                "content",
+                format!(
+                    "{text_length} characters of text exceeds the limit of {MAX_CONTENT_CHARS}"
+                ),
+            ));
+        }
+        if self.content.len() >= MAX_CONTENT_BYTES {
+            violations.push(FieldViolation::new(
+                "content",
+                format!(
+                    "{} bytes of HTML must be under {MAX_CONTENT_BYTES}",
+                    self.content.len()
+                ),
+            ));
+        }
+
+        let mut forbidden: Vec<String> = FORBIDDEN_TAG_REGEX
+            .captures_iter(&self.content)
+            .map(|caps| caps[1].to_lowercase())
+            .collect();
+        forbidden.sort();
+        forbidden.dedup();
+        for tag in forbidden {
+            violations.push(FieldViolation::new(
+                "content",
+                format!("<{tag}> elements are not allowed"),
+            ));
+        }
+
+        if let Some(url) = &self.content_source_url {
+            let valid = reqwest::Url::parse(url)
+                .is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https"));
+            if !valid {
+                violations.push(FieldViolation::new(
+                    "content_source_url",
+                    format!("'{url}' is not a valid http(s) URL"),
+                ));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(WeChatError::Validation { violations })
+        }
+    }
+}
+
 /// Request body for creating a draft.
 #[derive(Debug, Serialize)]
 struct DraftRequest {
@@ -247,7 +486,7 @@ pub struct DraftInfo {
     pub update_time: u64,
 }
 
-/// Content of a draft.
+/// Articles of a draft or published item.
 #[derive(Debug, Deserialize)]
 pub struct DraftContent {
     pub news_item: Vec<Article>,
@@ -282,7 +521,7 @@ pub struct MaterialListResponse {
 #[derive(Debug)]
 pub struct ImageUploader {
     http_client: Arc<WeChatHttpClient>,
-    token_manager: Arc<TokenManager>,
+    token_provider: Arc<dyn TokenProvider>,
     semaphore: Arc<Semaphore>,
     /// Cache for material lookups by hash to avoid redundant API calls
     material_cache: Arc<RwLock<HashMap<String, CachedMaterial>>>,
@@ -290,10 +529,10 @@ pub struct ImageUploader {
 
 impl ImageUploader {
     /// Creates a new image uploader.
-    pub fn new(http_client: Arc<WeChatHttpClient>, token_manager: Arc<TokenManager>) -> Self {
+    pub fn new(http_client: Arc<WeChatHttpClient>, token_provider: Arc<dyn TokenProvider>) -> Self {
         Self {
             http_client,
-            token_manager,
+            token_provider,
             semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_UPLOADS)),
             material_cache: Arc::new(RwLock::new(HashMap::new())),
         }
@@ -317,10 +556,14 @@ impl ImageUploader {
             .map(|image_ref| {
                 let uploader = self.clone();
                 let base_path = base_path.to_owned();
-
-                tokio::spawn(
-                    async move { uploader.upload_single_image(image_ref, &base_path).await },
-                )
+                let frame = ErrorFrame::Image(image_ref.original_url.clone());
+
+                tokio::spawn(async move {
+                    uploader
+                        .upload_single_image(image_ref, &base_path)
+                        .await
+                        .context(frame)
+                })
             })
             .collect();
 
@@ -339,6 +582,101 @@ impl ImageUploader {
         Ok(uploads)
     }
 
+    /// Uploads multiple images concurrently, handling broken images according to `policy`.
+    ///
+    /// With [`ImagePolicy::FailFast`] this behaves like [`upload_images`](Self::upload_images).
+    /// Otherwise every broken image is skipped and reported in its outcome. Failures that
+    /// are not specific to one image, such as rejected credentials, a used-up quota or a
+    /// crashed task, are returned as errors.
+    pub async fn upload_images_with_policy(
+        &self,
+        images: Vec<ImageRef>,
+        base_path: &Path,
+        policy: ImagePolicy,
+    ) -> Result<ImageUploadReport> {
+        let retries = match policy {
+            ImagePolicy::FailFast => {
+                let results = self.upload_images(images, base_path).await?;
+                let outcomes = results
+                    .into_iter()
+                    .map(|result| ImageOutcome::Uploaded {
+                        result,
+                        attempts: 1,
+                    })
+                    .collect();
+                return Ok(ImageUploadReport { outcomes });
+            }
+            ImagePolicy::SkipBroken => 0,
+            ImagePolicy::RetryThenSkip { retries } => retries,
+        };
+
+        let tasks: Vec<_> = images
+            .into_iter()
+            .map(|image_ref| {
+                let uploader = self.clone();
+                let base_path = base_path.to_owned();
+                tokio::spawn(async move {
+                    uploader
+                        .upload_or_skip(image_ref, &base_path, retries)
+                        .await
+                })
+            })
+            .collect();
+
+        let outcomes = try_join_all(tasks)
+            .await
+            .map_err(|e| WeChatError::Internal {
+                message: format!("Task join error: {e}"),
+            })?
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?;
+
+        let report = ImageUploadReport { outcomes };
+        if report.has_skipped() {
+            warn!("{report}");
+        } else {
+            info!("{report}");
+        }
+        Ok(report)
+    }
+
+    /// Uploads an image, retrying retryable failures, and reports a skip instead of failing
+    /// when the image itself is broken.
+    async fn upload_or_skip(
+        &self,
+        image_ref: ImageRef,
+        base_path: &Path,
+        retries: u32,
+    ) -> Result<ImageOutcome> {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.upload_single_image(image_ref.clone(), base_path).await {
+                Ok(result) => return Ok(ImageOutcome::Uploaded { result, attempts }),
+                Err(error) if attempts <= retries && error.is_retryable() => {
+                    warn!(
+                        "Image {} failed (attempt {attempts}), retrying: {error}",
+                        image_ref.original_url
+                    );
+                    tokio::time::sleep(error.retry_delay()).await;
+                }
+                Err(error) => {
+                    let error = error.context(ErrorFrame::Image(image_ref.original_url.clone()));
+                    if !is_image_error(&error) {
+                        return Err(error);
+                    }
+
+                    warn!("Skipping image {}: {error}", image_ref.original_url);
+                    return Ok(ImageOutcome::Skipped {
+                        image_ref,
+                        error,
+                        attempts,
+                    });
+                }
+            }
+        }
+    }
+
     /// Uploads a single image as permanent material.
     async fn upload_single_image(
         &self,
@@ -359,7 +697,7 @@ impl ImageUploader {
         // Load image data
         let image_data = if image_ref.is_local {
             let image_path = image_ref.resolve_path(base_path)?;
-            self.load_local_image(&image_path).await?
+            load_local_image(&image_path).await?
         } else {
             self.download_remote_image(&image_ref.original_url).await?
         };
@@ -433,15 +771,26 @@ impl ImageUploader {
         }
 
         // Use hash as filename with appropriate extension
-        let extension = self.get_image_extension(original_path, &image_data);
+        let extension = image_extension(original_path, &image_data);
         let filename = format!("{hash_str}.{extension}");
         debug!("Uploading new image as permanent material with filename: {filename}");
 
         // Upload as permanent material
-        let access_token = self.token_manager.get_access_token().await?;
         let response = self
             .http_client
-            .upload_material(&access_token, "image", image_data, &filename)
+            .execute_authorized(
+                MATERIAL_ENDPOINT,
+                self.token_provider.as_ref(),
+                |access_token| {
+                    let image_data = image_data.clone();
+                    let filename = &filename;
+                    async move {
+                        self.http_client
+                            .send_material(&access_token, "image", image_data, filename)
+                            .await
+                    }
+                },
+            )
             .await?;
 
         // Parse response - handle both direct and wrapped response formats
@@ -533,36 +882,6 @@ impl ImageUploader {
         (total, expired)
     }
 
-    /// Loads image data from local file with streaming and size validation.
-    async fn load_local_image(&self, path: &Path) -> Result<Vec<u8>> {
-        // Check file size before loading
-        let metadata = fs::metadata(path)
-            .await
-            .map_err(|e| WeChatError::ImageUpload {
-                path: path.display().to_string(),
-                reason: format!("Failed to get file metadata: {e}"),
-            })?;
-
-        let file_size = metadata.len();
-        if file_size > MAX_IMAGE_SIZE {
-            return Err(WeChatError::ImageUpload {
-                path: path.display().to_string(),
-                reason: format!("File too large: {file_size} bytes (max: {MAX_IMAGE_SIZE} bytes)"),
-            });
-        }
-
-        debug!(
-            "Loading local image: {} ({} bytes)",
-            path.display(),
-            file_size
-        );
-
-        fs::read(path).await.map_err(|e| WeChatError::ImageUpload {
-            path: path.display().to_string(),
-            reason: format!("Failed to read local file: {e}"),
-        })
-    }
-
     /// Downloads image data from remote URL with optimized streaming and size validation.
     async fn download_remote_image(&self, url: &str) -> Result<Vec<u8>> {
         debug!("Downloading remote image: {url}");
@@ -576,35 +895,29 @@ impl ImageUploader {
             })
     }
 
-    /// Gets the image extension based on URL and content.
-    fn get_image_extension(&self, url: &str, image_data: &[u8]) -> String {
-        // First try to get from URL
-        if let Some(ext) = Path::new(url)
-            .extension()
-            .and_then(|e| e.to_str())
-            .filter(|e| matches!(*e, "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp"))
-        {
-            return ext.to_string();
-        }
-
-        // Otherwise, detect from content
-        if image_data.len() >= 4 {
-            match &image_data[0..4] {
-                [0xFF, 0xD8, 0xFF, _] => return "jpg".to_string(),
-                [0x89, 0x50, 0x4E, 0x47] => return "png".to_string(),
-                [0x47, 0x49, 0x46, _] => return "gif".to_string(),
-                [0x42, 0x4D, _, _] => return "bmp".to_string(),
-                _ => {}
-            }
-        }
+    /// Lists permanent image materials, most recent first.
+    pub async fn list_image_materials(
+        &self,
+        offset: u32,
+        count: u32,
+    ) -> Result<MaterialListResponse> {
+        let request = serde_json::json!({
+            "type": "image",
+            "offset": offset,
+            "count": count
+        });
 
-        // Check for WebP
-        if image_data.len() >= 12 && &image_data[0..4] == b"RIFF" && &image_data[8..12] == b"WEBP" {
-            return "webp".to_string();
-        }
+        let response = self
+            .http_client
+            .post_json_authorized(
+                "/cgi-bin/material/batchget_material",
+                self.token_provider.as_ref(),
+                &request,
+            )
+            .await?;
 
-        // Default to jpg
-        "jpg".to_string()
+        let materials: WeChatResponse<MaterialListResponse> = response.json().await?;
+        materials.into_result()
     }
 
     /// Searches for an existing material by hash and returns both URL and media_id.
@@ -612,8 +925,6 @@ impl ImageUploader {
         debug!("Checking for existing material with hash: {hash_str}");
 
         // Check the most recent 20 materials
-        let access_token = self.token_manager.get_access_token().await?;
-
         let request = serde_json::json!({
             "type": "image",
             "offset": 0,
@@ -622,9 +933,9 @@ impl ImageUploader {
 
         let response = self
             .http_client
-            .post_json_with_token(
+            .post_json_authorized(
                 "/cgi-bin/material/batchget_material",
-                &access_token,
+                self.token_provider.as_ref(),
                 &request,
             )
             .await
@@ -679,7 +990,7 @@ impl ImageUploader {
         );
 
         // Load image data
-        let image_data = self.load_local_image(cover_path).await?;
+        let image_data = load_local_image(cover_path).await?;
 
         // Use unified upload method
         let (media_id, _url) = self
@@ -700,26 +1011,104 @@ impl Clone for ImageUploader {
     fn clone(&self) -> Self {
         Self {
             http_client: Arc::clone(&self.http_client),
-            token_manager: Arc::clone(&self.token_manager),
+            token_provider: Arc::clone(&self.token_provider),
             semaphore: Arc::clone(&self.semaphore),
             material_cache: Arc::clone(&self.material_cache),
         }
     }
 }
 
+/// Gets the image extension based on URL and content.
+pub(crate) fn image_extension(url: &str, image_data: &[u8]) -> String {
+    // First try to get from URL
+    if let Some(ext) = Path::new(url)
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| matches!(*e, "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp"))
+    {
+        return ext.to_string();
+    }
+
+    // Otherwise, detect from content
+    if image_data.len() >= 4 {
+        match &image_data[0..4] {
+            [0xFF, 0xD8, 0xFF, _] => return "jpg".to_string(),
+            [0x89, 0x50, 0x4E, 0x47] => return "png".to_string(),
+            [0x47, 0x49, 0x46, _] => return "gif".to_string(),
+            [0x42, 0x4D, _, _] => return "bmp".to_string(),
+            _ => {}
+        }
+    }
+
+    // Check for WebP
+    if image_data.len() >= 12 && &image_data[0..4] == b"RIFF" && &image_data[8..12] == b"WEBP" {
+        return "webp".to_string();
+    }
+
+    // Default to jpg
+    "jpg".to_string()
+}
+
+/// Checks whether an error concerns a single image rather than the upload as a whole.
+///
+/// Missing files, failed downloads and files WeChat rejects as media are broken images.
+/// Credential, token, quota and other API errors would fail every image alike.
+fn is_image_error(error: &WeChatError) -> bool {
+    match error.kind() {
+        WeChatError::ImageUpload { .. } | WeChatError::FileNotFound { .. } => true,
+        _ => error.api_category() == Some(ApiErrorCategory::Media),
+    }
+}
+
+/// Loads image data from local file with streaming and size validation.
+pub(crate) async fn load_local_image(path: &Path) -> Result<Vec<u8>> {
+    // Check file size before loading
+    let metadata = fs::metadata(path).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            WeChatError::FileNotFound {
+                path: path.display().to_string(),
+            }
+        } else {
+            WeChatError::ImageUpload {
+                path: path.display().to_string(),
+                reason: format!("Failed to get file metadata: {e}"),
+            }
+        }
+    })?;
+
+    let file_size = metadata.len();
+    if file_size > MAX_IMAGE_SIZE {
+        return Err(WeChatError::ImageUpload {
+            path: path.display().to_string(),
+            reason: format!("File too large: {file_size} bytes (max: {MAX_IMAGE_SIZE} bytes)"),
+        });
+    }
+
+    debug!(
+        "Loading local image: {} ({} bytes)",
+        path.display(),
+        file_size
+    );
+
+    fs::read(path).await.map_err(|e| WeChatError::ImageUpload {
+        path: path.display().to_string(),
+        reason: format!("Failed to read local file: {e}"),
+    })
+}
+
 /// Draft manager for creating and managing article drafts.
 #[derive(Debug)]
 pub struct DraftManager {
     http_client: Arc<WeChatHttpClient>,
-    token_manager: Arc<TokenManager>,
+    token_provider: Arc<dyn TokenProvider>,
 }
 
 impl DraftManager {
     /// Creates a new draft manager.
-    pub fn new(http_client: Arc<WeChatHttpClient>, token_manager: Arc<TokenManager>) -> Self {
+    pub fn new(http_client: Arc<WeChatHttpClient>, token_provider: Arc<dyn TokenProvider>) -> Self {
         Self {
             http_client,
-            token_manager,
+            token_provider,
         }
     }
 
@@ -747,13 +1136,22 @@ impl DraftManager {
 
         // No existing draft found, create new one
         info!("No existing draft found, creating new draft");
+        self.add_draft(articles).await
+    }
+
+    /// Creates a new draft with articles, even if one with the same title exists.
+    pub async fn add_draft(&self, articles: Vec<Article>) -> Result<String> {
+        if articles.is_empty() {
+            return Err(WeChatError::config_error(
+                "At least one article is required",
+            ));
+        }
 
         let request = DraftRequest { articles };
-        let access_token = self.token_manager.get_access_token().await?;
 
         let response = self
             .http_client
-            .post_json_with_token("/cgi-bin/draft/add", &access_token, &request)
+            .post_json_authorized("/cgi-bin/draft/add", self.token_provider.as_ref(), &request)
             .await?;
 
         let draft_response: WeChatResponse<DraftResponse> = response.json().await?;
@@ -770,18 +1168,36 @@ impl DraftManager {
     pub async fn get_draft(&self, media_id: &str) -> Result<DraftInfo> {
         debug!("Getting draft: {media_id}");
 
-        let access_token = self.token_manager.get_access_token().await?;
         let request = serde_json::json!({ "media_id": media_id });
 
         let response = self
             .http_client
-            .post_json_with_token("/cgi-bin/draft/get", &access_token, &request)
+            .post_json_authorized("/cgi-bin/draft/get", self.token_provider.as_ref(), &request)
             .await?;
 
         let draft_response: WeChatResponse<DraftInfo> = response.json().await?;
         draft_response.into_result()
     }
 
+    /// Gets the articles of a published item by its article ID.
+    pub async fn get_published_article(&self, article_id: &str) -> Result<Vec<Article>> {
+        debug!("Getting published article: {article_id}");
+
+        let request = serde_json::json!({ "article_id": article_id });
+
+        let response = self
+            .http_client
+            .post_json_authorized(
+                "/cgi-bin/freepublish/getarticle",
+                self.token_provider.as_ref(),
+                &request,
+            )
+            .await?;
+
+        let published: WeChatResponse<DraftContent> = response.json().await?;
+        Ok(published.into_result()?.news_item)
+    }
+
     /// Updates a draft.
     pub async fn update_draft(&self, media_id: &str, articles: Vec<Article>) -> Result<()> {
         if articles.is_empty() {
@@ -803,11 +1219,13 @@ impl DraftManager {
             "articles": articles[0]  // WeChat expects a single article object, not an array
         });
 
-        let access_token = self.token_manager.get_access_token().await?;
-
         let response = self
             .http_client
-            .post_json_with_token("/cgi-bin/draft/update", &access_token, &request)
+            .post_json_authorized(
+                "/cgi-bin/draft/update",
+                self.token_provider.as_ref(),
+                &request,
+            )
             .await?;
 
         let update_response: WeChatResponse<serde_json::Value> = response.json().await?;
@@ -822,11 +1240,14 @@ impl DraftManager {
         info!("Deleting draft: {media_id}");
 
         let request = serde_json::json!({ "media_id": media_id });
-        let access_token = self.token_manager.get_access_token().await?;
 
         let response = self
             .http_client
-            .post_json_with_token("/cgi-bin/draft/delete", &access_token, &request)
+            .post_json_authorized(
+                "/cgi-bin/draft/delete",
+                self.token_provider.as_ref(),
+                &request,
+            )
             .await?;
 
         let delete_response: WeChatResponse<serde_json::Value> = response.json().await?;
@@ -846,11 +1267,13 @@ impl DraftManager {
             "no_content": 0
         });
 
-        let access_token = self.token_manager.get_access_token().await?;
-
         let response = self
             .http_client
-            .post_json_with_token("/cgi-bin/draft/batchget", &access_token, &request)
+            .post_json_authorized(
+                "/cgi-bin/draft/batchget",
+                self.token_provider.as_ref(),
+                &request,
+            )
             .await?;
 
         let response_text = response.text().await?;
@@ -871,7 +1294,9 @@ impl DraftManager {
     }
 
     /// Finds a draft by title in recent drafts.
-    async fn find_draft_by_title(&self, title: &str) -> Result<Option<String>> {
+    ///
+    /// This is the draft [`create_draft`](Self::create_draft) updates.
+    pub async fn find_draft_by_title(&self, title: &str) -> Result<Option<String>> {
         debug!("Searching for draft with title: {title}");
 
         // List recent 20 drafts
@@ -902,6 +1327,7 @@ impl DraftManager {
 mod tests {
     use super::*;
     use crate::auth::TokenManager;
+    use crate::test_support::MockServer;
     use std::sync::Arc;
 
     #[tokio::test]
@@ -919,6 +1345,48 @@ mod tests {
         assert_eq!(article.need_open_comment, 0);
     }
 
+    #[test]
+    fn test_article_validation() {
+        let article = Article::new(
+            "标题".to_string(),
+            "作者".to_string(),
+            "<p>正文</p>".to_string(),
+        )
+        .with_digest("摘要".to_string())
+        .with_source_url("https://example.com/post".to_string());
+        assert!(article.validate().is_ok());
+
+        let article = Article::new(
+            "长".repeat(65),
+            "一位很长名字的作者".to_string(),
+            format!(
+                "<p>{}</p><script>alert(1)</script><IFRAME src=x></iframe><script></script>",
+                "字".repeat(MAX_CONTENT_CHARS)
+            ),
+        )
+        .with_digest("d".repeat(121))
+        .with_source_url("ftp://example.com".to_string());
+
+        let Err(WeChatError::Validation { violations }) = article.validate() else {
+            panic!("Expected Validation error");
+        };
+        let fields: Vec<&str> = violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            [
+                "title",
+                "author",
+                "digest",
+                "content",
+                "content",
+                "content",
+                "content_source_url"
+            ]
+        );
+        assert!(violations[4].message.contains("<iframe>"));
+        assert!(violations[5].message.contains("<script>"));
+    }
+
     #[tokio::test]
     async fn test_article_builder_methods() {
         let article = Article::new(
@@ -959,6 +1427,153 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_errcode_errors_carry_endpoint() {
+        use crate::config::{Config, HttpConfig};
+        use crate::error::ErrorFrame;
+
+        let server = MockServer::serve([
+            r#"{"errcode":40007,"errmsg":"invalid media_id"}"#,
+            r#"{"errcode":0,"total_count":0,"item_count":0,"item":[]}"#,
+            r#"{"errcode":40005,"errmsg":"invalid file type"}"#,
+        ])
+        .await;
+        let config = Config::builder()
+            .http(HttpConfig::builder().base_url(server.url).build())
+            .build();
+        let http_client = Arc::new(WeChatHttpClient::with_config(config).unwrap());
+        let token_provider = Arc::new(crate::token_provider::StaticTokenProvider::new("token"));
+
+        // HTTP 200 with an errcode, as WeChat reports most failures
+        let drafts = DraftManager::new(Arc::clone(&http_client), token_provider.clone());
+        let article = Article::new("标题".into(), String::new(), "<p>正文</p>".into());
+        let error = drafts.update_draft("m1", vec![article]).await.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::WeChatApi { code: 40007, .. }
+        ));
+        assert_eq!(
+            error.frames(),
+            [ErrorFrame::Endpoint("/cgi-bin/draft/update".to_string())]
+        );
+        assert!(
+            error
+                .to_string()
+                .starts_with("endpoint /cgi-bin/draft/update: ")
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let cover = dir.path().join("cover.png");
+        std::fs::write(&cover, b"png").unwrap();
+        let uploader = ImageUploader::new(http_client, token_provider);
+        let error = uploader.upload_cover_material(&cover).await.unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::WeChatApi { code: 40005, .. }
+        ));
+        assert_eq!(
+            error.frames(),
+            [ErrorFrame::Endpoint(
+                "/cgi-bin/material/add_material".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_upload_images_with_policy() {
+        let http_client = Arc::new(WeChatHttpClient::new().unwrap());
+        let token_provider = Arc::new(crate::token_provider::StaticTokenProvider::new("token"));
+        let uploader = ImageUploader::new(http_client, token_provider);
+        let dir = tempfile::tempdir().unwrap();
+        let images = || {
+            vec![ImageRef::new(
+                "missing".to_string(),
+                "images/missing.png".to_string(),
+                (0, 0),
+            )]
+        };
+
+        let error = uploader
+            .upload_images_with_policy(images(), dir.path(), ImagePolicy::FailFast)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.kind(), WeChatError::FileNotFound { .. }));
+
+        let report = uploader
+            .upload_images_with_policy(images(), dir.path(), ImagePolicy::SkipBroken)
+            .await
+            .unwrap();
+        assert!(report.has_skipped());
+        assert_eq!(report.uploaded().count(), 0);
+        assert_eq!(report.outcomes[0].attempts(), 1);
+        let (image_ref, error) = report.skipped().next().unwrap();
+        assert_eq!(image_ref.original_url, "images/missing.png");
+        assert_eq!(
+            error.frames(),
+            [ErrorFrame::Image("images/missing.png".to_string())]
+        );
+        assert!(
+            report
+                .to_string()
+                .starts_with("0 of 1 images uploaded, 1 skipped:\n  - images/missing.png: ")
+        );
+
+        // Skipped images keep their URL unless a placeholder is given
+        assert!(report.url_mapping(None).is_empty());
+        assert_eq!(
+            report.url_mapping(Some("https://mmbiz.qpic.cn/placeholder"))["images/missing.png"],
+            "https://mmbiz.qpic.cn/placeholder"
+        );
+
+        // A missing file won't appear by trying again
+        let report = uploader
+            .upload_images_with_policy(
+                images(),
+                dir.path(),
+                ImagePolicy::RetryThenSkip { retries: 1 },
+            )
+            .await
+            .unwrap();
+        assert!(report.has_skipped());
+        assert_eq!(report.outcomes[0].attempts(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_skip_policy_propagates_account_errors() {
+        use crate::config::{Config, HttpConfig};
+
+        // The material lookup fails quietly, then the upload itself is refused
+        let server = MockServer::serve([
+            r#"{"errcode":40164,"errmsg":"invalid ip, not in whitelist"}"#,
+            r#"{"errcode":40164,"errmsg":"invalid ip, not in whitelist"}"#,
+        ])
+        .await;
+        let config = Config::builder()
+            .http(HttpConfig::builder().base_url(server.url).build())
+            .build();
+        let uploader = ImageUploader::new(
+            Arc::new(WeChatHttpClient::with_config(config).unwrap()),
+            Arc::new(crate::token_provider::StaticTokenProvider::new("token")),
+        );
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.png"), b"png").unwrap();
+
+        let images = vec![ImageRef::new("a".to_string(), "a.png".to_string(), (0, 0))];
+        let error = uploader
+            .upload_images_with_policy(images, dir.path(), ImagePolicy::SkipBroken)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            WeChatError::WeChatApi { code: 40164, .. }
+        ));
+        assert!(
+            error
+                .frames()
+                .contains(&ErrorFrame::Image("a.png".to_string()))
+        );
+    }
+
     #[tokio::test]
     async fn test_draft_manager_creation() {
         let http_client = Arc::new(WeChatHttpClient::new().unwrap());
@@ -973,26 +1588,18 @@ mod tests {
 
     #[test]
     fn test_image_extension_detection() {
-        let http_client = Arc::new(WeChatHttpClient::new().unwrap());
-        let token_manager = Arc::new(TokenManager::new(
-            "test_app_id",
-            "test_secret",
-            Arc::clone(&http_client),
-        ));
-        let uploader = ImageUploader::new(http_client, token_manager);
-
         // Test URL-based extension detection
-        assert_eq!(uploader.get_image_extension("test.jpg", &[]), "jpg");
-        assert_eq!(uploader.get_image_extension("test.png", &[]), "png");
-        assert_eq!(uploader.get_image_extension("test.webp", &[]), "webp");
+        assert_eq!(image_extension("test.jpg", &[]), "jpg");
+        assert_eq!(image_extension("test.png", &[]), "png");
+        assert_eq!(image_extension("test.webp", &[]), "webp");
 
         // Test content-based detection for JPEG
         let jpeg_header = vec![0xFF, 0xD8, 0xFF, 0xE0];
-        assert_eq!(uploader.get_image_extension("noext", &jpeg_header), "jpg");
+        assert_eq!(image_extension("noext", &jpeg_header), "jpg");
 
         // Test content-based detection for PNG
         let png_header = vec![0x89, 0x50, 0x4E, 0x47];
-        assert_eq!(uploader.get_image_extension("noext", &png_header), "png");
+        assert_eq!(image_extension("noext", &png_header), "png");
     }
 
     #[test]
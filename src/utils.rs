@@ -3,6 +3,7 @@
 //! This module provides security-focused utilities with input validation
 //! and safe path handling to prevent common vulnerabilities.
 
+use regex::Regex;
 use std::path::{Component, Path, PathBuf};
 use std::sync::LazyLock;
 use std::{collections::HashSet, ffi::OsStr};
@@ -241,6 +242,25 @@ pub fn validate_file_size(size: u64, max_size: u64, file_type: &str) -> Result<(
     Ok(())
 }
 
+/// Matches an `access_token` or `secret` value, as a query parameter or a JSON string field.
+static SECRET_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"((?:access_token|secret)=)[^&# )"]*|("(?:access_token|secret)"\s*:\s*")[^"]*"#)
+        .unwrap()
+});
+
+/// Replaces access tokens and AppSecrets in a URL or message with `***`.
+///
+/// Covers the `access_token` and `secret` query parameters, as in token and API URLs,
+/// and the same keys as JSON string fields, as in token responses and request bodies.
+pub fn redact_secrets(text: &str) -> String {
+    SECRET_REGEX
+        .replace_all(text, |caps: &regex::Captures| {
+            let key = caps.get(1).or_else(|| caps.get(2)).unwrap();
+            format!("{}***", key.as_str())
+        })
+        .into_owned()
+}
+
 /// Extracts the base directory from a file path.
 pub fn get_base_directory(file_path: &Path) -> Option<&Path> {
     file_path.parent()
@@ -300,6 +320,41 @@ pub fn resolve_path(base_dir: &Path, relative_path: &str) -> Result<PathBuf, Str
     }
 }
 
+/// Checks whether a character is a CJK ideograph, kana, hangul or full-width form.
+///
+/// These characters occupy two columns in a monospace font.
+pub fn is_wide_char(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x20000..=0x2FFFD
+            | 0x30000..=0x3FFFD
+    )
+}
+
+/// Returns the number of monospace columns a line of text occupies.
+///
+/// Wide (CJK) characters count as two columns and tabs as four.
+pub fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| match ch {
+            '\t' => 4,
+            ch if is_wide_char(ch) => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -421,6 +476,34 @@ mod tests {
         assert!(!has_path_traversal("folder/subfolder/file"));
     }
 
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(
+            redact_secrets("https://api.weixin.qq.com/cgi-bin/draft/add?access_token=SECRET"),
+            "https://api.weixin.qq.com/cgi-bin/draft/add?access_token=***"
+        );
+        assert_eq!(
+            redact_secrets("error for url (https://x/y?access_token=SECRET&type=image)"),
+            "error for url (https://x/y?access_token=***&type=image)"
+        );
+        assert_eq!(
+            redact_secrets(
+                "https://x/cgi-bin/token?grant_type=client_credential&appid=wx1&secret=SECRET"
+            ),
+            "https://x/cgi-bin/token?grant_type=client_credential&appid=wx1&secret=***"
+        );
+        assert_eq!(
+            redact_secrets(r#"{"access_token":"SECRET","expires_in":7200,"secret":"SECRET"}"#),
+            r#"{"access_token":"***","expires_in":7200,"secret":"***"}"#
+        );
+        // Pretty-printed JSON puts whitespace around the colon
+        assert_eq!(
+            redact_secrets("{\n  \"access_token\" : \"SECRET\",\n  \"secret\":  \"SECRET\"\n}"),
+            "{\n  \"access_token\" : \"***\",\n  \"secret\":  \"***\"\n}"
+        );
+        assert_eq!(redact_secrets("no token here"), "no token here");
+    }
+
     #[test]
     fn test_sanitize_filename() {
         assert_eq!(sanitize_filename("normal_file.txt"), "normal_file.txt");
@@ -450,4 +533,14 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("image file too large"));
     }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("fn main() {}"), 12);
+        assert_eq!(display_width("中文"), 4);
+        assert_eq!(display_width("a中b"), 4);
+        assert_eq!(display_width("\tx"), 5);
+        assert!(is_wide_char('，'));
+        assert!(!is_wide_char('a'));
+    }
 }